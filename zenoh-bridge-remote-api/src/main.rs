@@ -0,0 +1,177 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+use std::future;
+
+use clap::Parser;
+use tokio::{select, signal};
+use zenoh::{internal::runtime::RuntimeBuilder, Config as ZenohConfig};
+use zenoh_plugin_remote_api::{Config, RemoteApiPlugin};
+use zenoh_plugin_trait::Plugin;
+use zenoh_result::{zerror, ZResult};
+
+const DEFAULT_WEBSOCKET_PORT: &str = "10000";
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "zenoh-bridge-remote-api",
+    version = RemoteApiPlugin::PLUGIN_VERSION,
+    long_version = RemoteApiPlugin::PLUGIN_LONG_VERSION,
+    about = "Zenoh bridge serving the remote API over WebSockets, without zenohd"
+)]
+struct Args {
+    /// A zenoh configuration file, the bridge being configured by its `plugins/remote_api` section.
+    #[arg(short, long, value_name = "FILE")]
+    config: Option<String>,
+    /// The zenoh session mode [default: peer].
+    #[arg(short, long, value_name = "MODE", value_parser = ["peer", "client", "router"])]
+    mode: Option<String>,
+    /// Endpoints to connect to, e.g. `tcp/192.168.1.1:7447`. Repeat this option to connect to several endpoints.
+    #[arg(short = 'e', long, value_name = "ENDPOINT")]
+    connect: Vec<String>,
+    /// Endpoints to listen on, e.g. `tcp/0.0.0.0:7447`. Repeat this option to listen on several endpoints.
+    #[arg(short, long, value_name = "ENDPOINT")]
+    listen: Vec<String>,
+    /// Disable the multicast-based scouting mechanism.
+    #[arg(long)]
+    no_multicast_scouting: bool,
+    /// The WebSocket `[interface:]port` to serve the remote API on [default: 10000].
+    #[arg(short = 'p', long, value_name = "[INTERFACE:]PORT")]
+    websocket_port: Option<String>,
+    /// Certificate of the secure WebSocket, requires `--private-key-path`.
+    #[arg(long, value_name = "FILE", requires = "private_key_path")]
+    certificate_path: Option<String>,
+    /// Private key of the secure WebSocket, requires `--certificate-path`.
+    #[arg(long, value_name = "FILE", requires = "certificate_path")]
+    private_key_path: Option<String>,
+}
+
+// Build the zenoh configuration from the configuration file, overridden by the command line
+fn zenoh_config(args: &Args) -> ZResult<ZenohConfig> {
+    let mut config = match &args.config {
+        Some(path) => ZenohConfig::from_file(path)?,
+        None => ZenohConfig::default(),
+    };
+
+    if let Some(mode) = &args.mode {
+        config.insert_json5("mode", &serde_json::json!(mode).to_string())?;
+    }
+    if !args.connect.is_empty() {
+        config.insert_json5(
+            "connect/endpoints",
+            &serde_json::json!(args.connect).to_string(),
+        )?;
+    }
+    if !args.listen.is_empty() {
+        config.insert_json5(
+            "listen/endpoints",
+            &serde_json::json!(args.listen).to_string(),
+        )?;
+    }
+    if args.no_multicast_scouting {
+        config.insert_json5("scouting/multicast/enabled", "false")?;
+    }
+
+    let plugin_config = config.plugin(RemoteApiPlugin::DEFAULT_NAME);
+    let has_websocket_port = plugin_config.is_some_and(|c| c.get("websocket_port").is_some());
+    if let Some(websocket_port) = &args.websocket_port {
+        config.insert_json5(
+            "plugins/remote_api/websocket_port",
+            &serde_json::json!(websocket_port).to_string(),
+        )?;
+    } else if !has_websocket_port {
+        config.insert_json5(
+            "plugins/remote_api/websocket_port",
+            &serde_json::json!(DEFAULT_WEBSOCKET_PORT).to_string(),
+        )?;
+    }
+    if let (Some(certificate_path), Some(private_key_path)) =
+        (&args.certificate_path, &args.private_key_path)
+    {
+        config.insert_json5(
+            "plugins/remote_api/secure_websocket",
+            &serde_json::json!({
+                "certificate_path": certificate_path,
+                "private_key_path": private_key_path,
+            })
+            .to_string(),
+        )?;
+    }
+
+    Ok(config)
+}
+
+#[tokio::main]
+async fn main() -> ZResult<()> {
+    zenoh::init_log_from_env_or("z=info");
+
+    let args = Args::parse();
+    let config = zenoh_config(&args)?;
+
+    let plugin_config = config
+        .plugin(RemoteApiPlugin::DEFAULT_NAME)
+        .cloned()
+        .ok_or_else(|| zerror!("Missing `plugins/remote_api` configuration"))?;
+    let plugin_config: Config = serde_json::from_value(plugin_config)
+        .map_err(|e| zerror!("`plugins/remote_api` configuration error: {}", e))?;
+
+    let mut runtime = RuntimeBuilder::new(config)
+        .build()
+        .await
+        .map_err(|e| zerror!("Failed to build the zenoh runtime: {}", e))?;
+    runtime
+        .start()
+        .await
+        .map_err(|e| zerror!("Failed to start the zenoh runtime: {}", e))?;
+
+    tracing::info!(
+        "{} {} serving the remote API on {}",
+        RemoteApiPlugin::DEFAULT_NAME,
+        RemoteApiPlugin::PLUGIN_LONG_VERSION,
+        plugin_config.websocket_port
+    );
+
+    // The certificate and private key are loaded from the `secure_websocket` configuration
+    zenoh_plugin_remote_api::run_until(runtime, plugin_config, None, shutdown_signal()).await
+}
+
+// Ctrl-C, or SIGTERM on unix, starts draining the connected clients
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    let terminate = async {
+        match signal::unix::signal(signal::unix::SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(err) => {
+                tracing::warn!("Unable to listen to SIGTERM: {err}");
+                future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = future::pending::<()>();
+
+    let ctrl_c = async {
+        if let Err(err) = signal::ctrl_c().await {
+            tracing::warn!("Unable to listen to Ctrl-C: {err}");
+            future::pending::<()>().await;
+        }
+    };
+
+    select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+    tracing::info!("Shutting down, draining the connected clients");
+}
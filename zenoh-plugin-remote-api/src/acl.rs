@@ -0,0 +1,101 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use zenoh::key_expr::{keyexpr, OwnedKeyExpr};
+use zenoh_result::{zerror, ZResult};
+
+use crate::config::Acl;
+
+/// Key expressions the clients may use, from the `acl` configuration
+#[derive(Debug, Clone, Default)]
+pub(crate) struct AccessControl {
+    // Any key expression is allowed without it
+    allow: Option<Vec<OwnedKeyExpr>>,
+    deny: Vec<OwnedKeyExpr>,
+}
+
+impl AccessControl {
+    pub(crate) fn new(acl: &Acl) -> ZResult<Self> {
+        let allow = match &acl.allow {
+            Some(allow) => Some(key_exprs(allow)?),
+            None => None,
+        };
+        Ok(AccessControl {
+            allow,
+            deny: key_exprs(&acl.deny)?,
+        })
+    }
+
+    /// A key expression is allowed when an `allow` one includes it, and no `deny` one intersects it
+    pub(crate) fn allows(&self, key_expr: &keyexpr) -> bool {
+        let allowed = match &self.allow {
+            Some(allow) => allow.iter().any(|allowed| allowed.includes(key_expr)),
+            None => true,
+        };
+        allowed && !self.deny.iter().any(|denied| denied.intersects(key_expr))
+    }
+}
+
+fn key_exprs(key_exprs: &[String]) -> ZResult<Vec<OwnedKeyExpr>> {
+    let mut owned = Vec::with_capacity(key_exprs.len());
+    for key_expr in key_exprs {
+        let key_expr = keyexpr::new(key_expr.as_str())
+            .map_err(|e| zerror!("Invalid ACL key expression `{}`: {}", key_expr, e))?;
+        owned.push(key_expr.to_owned());
+    }
+    Ok(owned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn access_control(allow: Option<&[&str]>, deny: &[&str]) -> ZResult<AccessControl> {
+        let strings = |key_exprs: &[&str]| key_exprs.iter().map(|k| k.to_string()).collect();
+        AccessControl::new(&Acl {
+            allow: allow.map(strings),
+            deny: strings(deny),
+        })
+    }
+
+    fn allows(acl: &AccessControl, key_expr: &str) -> bool {
+        acl.allows(keyexpr::new(key_expr).unwrap())
+    }
+
+    #[test]
+    fn allow_and_deny() {
+        let everything = AccessControl::default();
+        assert!(allows(&everything, "**"));
+
+        let acl = access_control(Some(&["demo/**"]), &["demo/secret/**"]).unwrap();
+        assert!(allows(&acl, "demo/example"));
+        assert!(allows(&acl, "demo/example/**"));
+        assert!(!allows(&acl, "demo/secret"));
+        assert!(!allows(&acl, "demo/secret/key"));
+        // Wildcards reaching denied key expressions are denied
+        assert!(!allows(&acl, "demo/*"));
+        assert!(!allows(&acl, "**"));
+        assert!(!allows(&acl, "other/example"));
+
+        let acl = access_control(None, &["@/**"]).unwrap();
+        assert!(allows(&acl, "demo/example"));
+        assert!(!allows(&acl, "@/router/local"));
+    }
+
+    #[test]
+    fn invalid_key_expressions() {
+        assert!(access_control(Some(&["demo//example"]), &[]).is_err());
+        assert!(access_control(None, &["demo/**/**"]).is_err());
+    }
+}
@@ -0,0 +1,114 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use tracing::warn;
+use zenoh_remote_api_protocol::CompressionWS;
+
+use crate::config::Compression;
+
+/// Compresses the payloads of the samples forwarded to a subscriber which asked for it
+#[derive(Debug, Clone)]
+pub(crate) struct Compressor {
+    compression: CompressionWS,
+    min_size: usize,
+    zstd_level: i32,
+}
+
+impl Compressor {
+    pub(crate) fn new(compression: CompressionWS, config: &Compression) -> Self {
+        Compressor {
+            compression,
+            min_size: config.min_size,
+            zstd_level: config.zstd_level,
+        }
+    }
+
+    pub(crate) fn compression(&self) -> CompressionWS {
+        self.compression
+    }
+
+    /// Compressed payload, None when it is below the size threshold or does not get smaller
+    pub(crate) fn compress(&self, payload: &[u8]) -> Option<Vec<u8>> {
+        if payload.len() < self.min_size {
+            return None;
+        }
+        let compressed = match self.compression {
+            CompressionWS::Zstd => match zstd::bulk::compress(payload, self.zstd_level) {
+                Ok(compressed) => compressed,
+                Err(err) => {
+                    warn!("Could not compress payload with zstd: {err}");
+                    return None;
+                }
+            },
+            CompressionWS::Lz4 => {
+                // The uncompressed size must fit in the u32 prefix of the block
+                if u32::try_from(payload.len()).is_err() {
+                    return None;
+                }
+                lz4_flex::compress_prepend_size(payload)
+            }
+        };
+        (compressed.len() < payload.len()).then_some(compressed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compressor(compression: CompressionWS) -> Compressor {
+        Compressor::new(compression, &Compression::default())
+    }
+
+    #[test]
+    fn round_trip() {
+        let payload = r#"{"temperature": 21.5, "unit": "C"}"#.repeat(100);
+
+        let compressed = compressor(CompressionWS::Zstd)
+            .compress(payload.as_bytes())
+            .unwrap();
+        assert!(compressed.len() < payload.len());
+        assert_eq!(
+            zstd::decode_all(compressed.as_slice()).unwrap(),
+            payload.as_bytes()
+        );
+
+        let compressed = compressor(CompressionWS::Lz4)
+            .compress(payload.as_bytes())
+            .unwrap();
+        assert!(compressed.len() < payload.len());
+        assert_eq!(
+            lz4_flex::decompress_size_prepended(&compressed).unwrap(),
+            payload.as_bytes()
+        );
+    }
+
+    #[test]
+    fn below_threshold_or_incompressible() {
+        let compressor = compressor(CompressionWS::Zstd);
+        assert_eq!(compressor.compress(&[0; 100]), None);
+
+        // Bytes of a xorshift generator, which zstd cannot shrink
+        let mut state = 0x2545_f491_u32;
+        let noise: Vec<u8> = (0..4096)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect();
+        assert_eq!(compressor.compress(&noise), None);
+    }
+}
@@ -21,6 +21,11 @@ use serde::{
 };
 
 const DEFAULT_HTTP_INTERFACE: &str = "[::]";
+const DEFAULT_DRAIN_TIMEOUT_MS: u64 = 5000;
+const DEFAULT_COMPRESSION_MIN_SIZE: usize = 1024;
+const DEFAULT_ZSTD_LEVEL: i32 = 3;
+const DEFAULT_DEFLATE_MIN_SIZE: usize = 1024;
+const DEFAULT_DEFLATE_LEVEL: u32 = 1;
 
 #[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
@@ -30,6 +35,33 @@ pub struct Config {
 
     pub secure_websocket: Option<SecureWebsocket>,
 
+    pub metrics: Option<MetricsEndpoint>,
+
+    #[serde(default)]
+    pub admin_space_control: bool,
+
+    pub client_liveliness: Option<ClientLiveliness>,
+
+    // Time given to clients to close on shutdown, before they are cleaned up
+    #[serde(default = "default_drain_timeout_ms")]
+    pub drain_timeout_ms: u64,
+
+    // Thresholds of the payload compression asked by subscribers
+    pub compression: Option<Compression>,
+
+    // Maximum number of connected clients, unlimited by default
+    pub max_connections: Option<usize>,
+
+    // Values of the `Origin` header of the browsers allowed to connect, any by default.
+    // Clients which send no `Origin` header, which are not browsers, are always allowed
+    pub allowed_origins: Option<Vec<String>>,
+
+    // Key expressions the clients may use
+    pub acl: Option<Acl>,
+
+    // Filter of the logs, with the syntax of `RUST_LOG` which it overrides
+    pub log_level: Option<String>,
+
     #[serde(default, deserialize_with = "deserialize_path")]
     __path__: Option<Vec<String>>,
     __required__: Option<bool>,
@@ -43,6 +75,84 @@ pub struct SecureWebsocket {
     pub private_key_path: String,
 }
 
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct MetricsEndpoint {
+    #[serde(deserialize_with = "deserialize_ws_port")]
+    pub port: String,
+}
+
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ClientLiveliness {
+    // Key expression prefix of the clients tokens, `@/<zid>/remote-plugin/clients` by default
+    pub prefix: Option<String>,
+}
+
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Compression {
+    // Payloads smaller than this number of bytes are sent uncompressed
+    #[serde(default = "default_compression_min_size")]
+    pub min_size: usize,
+    #[serde(default = "default_zstd_level")]
+    pub zstd_level: i32,
+    // Negotiate the `permessage-deflate` WebSocket extension with the clients offering it
+    #[serde(default = "default_deflate")]
+    pub deflate: bool,
+    // Messages smaller than this number of bytes are sent uncompressed with `permessage-deflate`
+    #[serde(default = "default_deflate_min_size")]
+    pub deflate_min_size: usize,
+    #[serde(default = "default_deflate_level")]
+    pub deflate_level: u32,
+}
+
+#[derive(JsonSchema, Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Acl {
+    // Key expressions including those the clients may use, any by default
+    pub allow: Option<Vec<String>>,
+    // Key expressions intersecting those the clients may not use, even if allowed
+    #[serde(default)]
+    pub deny: Vec<String>,
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        Compression {
+            min_size: DEFAULT_COMPRESSION_MIN_SIZE,
+            zstd_level: DEFAULT_ZSTD_LEVEL,
+            deflate: default_deflate(),
+            deflate_min_size: DEFAULT_DEFLATE_MIN_SIZE,
+            deflate_level: DEFAULT_DEFLATE_LEVEL,
+        }
+    }
+}
+
+fn default_drain_timeout_ms() -> u64 {
+    DEFAULT_DRAIN_TIMEOUT_MS
+}
+
+fn default_compression_min_size() -> usize {
+    DEFAULT_COMPRESSION_MIN_SIZE
+}
+
+fn default_zstd_level() -> i32 {
+    DEFAULT_ZSTD_LEVEL
+}
+
+fn default_deflate() -> bool {
+    true
+}
+
+fn default_deflate_min_size() -> usize {
+    DEFAULT_DEFLATE_MIN_SIZE
+}
+
+fn default_deflate_level() -> u32 {
+    DEFAULT_DEFLATE_LEVEL
+}
+
 impl From<&Config> for serde_json::Value {
     fn from(c: &Config) -> Self {
         serde_json::to_value(c).unwrap()
@@ -154,7 +264,7 @@ impl<'de> serde::de::Visitor<'de> for PathVisitor {
 
 #[cfg(test)]
 mod tests {
-    use super::{Config, DEFAULT_HTTP_INTERFACE};
+    use super::{Acl, Compression, Config, DEFAULT_DRAIN_TIMEOUT_MS, DEFAULT_HTTP_INTERFACE};
 
     #[test]
     fn test_path_field() {
@@ -214,6 +324,109 @@ mod tests {
         assert_eq!(__required__, Some(true));
     }
 
+    #[test]
+    fn test_metrics_field() {
+        let config = serde_json::from_str::<Config>(
+            r#"{"websocket_port": 8080, "metrics": {"port": "127.0.0.1:9090"}}"#,
+        );
+
+        assert!(config.is_ok());
+        let Config { metrics, .. } = config.unwrap();
+
+        assert_eq!(metrics.unwrap().port, "127.0.0.1:9090");
+    }
+
+    #[test]
+    fn test_admin_space_control_field() {
+        let config = serde_json::from_str::<Config>(r#"{"websocket_port": 8080}"#);
+        assert!(!config.unwrap().admin_space_control);
+
+        let config = serde_json::from_str::<Config>(
+            r#"{"websocket_port": 8080, "admin_space_control": true}"#,
+        );
+        assert!(config.unwrap().admin_space_control);
+    }
+
+    #[test]
+    fn test_client_liveliness_field() {
+        let config = serde_json::from_str::<Config>(
+            r#"{"websocket_port": 8080, "client_liveliness": {"prefix": "browsers"}}"#,
+        );
+
+        assert!(config.is_ok());
+        let Config {
+            client_liveliness, ..
+        } = config.unwrap();
+
+        assert_eq!(
+            client_liveliness.unwrap().prefix.as_deref(),
+            Some("browsers")
+        );
+    }
+
+    #[test]
+    fn test_drain_timeout_field() {
+        let config = serde_json::from_str::<Config>(r#"{"websocket_port": 8080}"#);
+        assert_eq!(config.unwrap().drain_timeout_ms, DEFAULT_DRAIN_TIMEOUT_MS);
+
+        let config =
+            serde_json::from_str::<Config>(r#"{"websocket_port": 8080, "drain_timeout_ms": 100}"#);
+        assert_eq!(config.unwrap().drain_timeout_ms, 100);
+    }
+
+    #[test]
+    fn test_compression_field() {
+        let config = serde_json::from_str::<Config>(r#"{"websocket_port": 8080}"#);
+        assert_eq!(config.unwrap().compression, None);
+
+        let config = serde_json::from_str::<Config>(
+            r#"{"websocket_port": 8080, "compression": {"min_size": 256}}"#,
+        );
+        assert_eq!(
+            config.unwrap().compression,
+            Some(Compression {
+                min_size: 256,
+                ..Default::default()
+            })
+        );
+
+        let config = serde_json::from_str::<Config>(
+            r#"{"websocket_port": 8080, "compression": {"deflate": false}}"#,
+        );
+        assert_eq!(
+            config.unwrap().compression,
+            Some(Compression {
+                deflate: false,
+                ..Default::default()
+            })
+        );
+        assert!(Compression::default().deflate);
+
+        let config = serde_json::from_str::<Config>(
+            r#"{"websocket_port": 8080, "compression": {"level": 3}}"#,
+        );
+        assert!(config.is_err());
+    }
+
+    #[test]
+    fn test_acl_field() {
+        let config = serde_json::from_str::<Config>(
+            r#"{"websocket_port": 8080, "acl": {"deny": ["@/**"]}}"#,
+        );
+        assert_eq!(
+            config.unwrap().acl,
+            Some(Acl {
+                allow: None,
+                deny: vec!["@/**".to_string()],
+            })
+        );
+
+        let config = serde_json::from_str::<Config>(
+            r#"{"websocket_port": 8080, "acl": {"allow": ["demo/**"], "rules": []}}"#,
+        );
+        assert!(config.is_err());
+    }
+
     #[test]
     fn test_no_path_field_and_no_required_field() {
         // See: https://github.com/eclipse-zenoh/zenoh-plugin-webserver/issues/19
@@ -0,0 +1,650 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! The `permessage-deflate` WebSocket extension (RFC 7692), which tungstenite does not support.
+//! It is negotiated in the handshake callback, the messages sent are deflated into frames with
+//! the RSV1 bit set, and the compressed frames of the clients are inflated by [`InflateStream`]
+//! before tungstenite reads them, as it refuses frames with reserved bits.
+
+use std::{
+    collections::HashSet,
+    io,
+    pin::Pin,
+    sync::{Arc, OnceLock},
+    task::{ready, Context, Poll},
+};
+
+use flate2::{Compress, Decompress, FlushCompress, FlushDecompress};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio_tungstenite::tungstenite::{
+    handshake::server::Request,
+    http::{header::SEC_WEBSOCKET_EXTENSIONS, HeaderValue},
+    protocol::{
+        frame::{
+            coding::{Data, OpCode},
+            Frame,
+        },
+        Message,
+    },
+};
+use tracing::warn;
+
+use crate::config::Compression;
+
+const EXTENSION: &str = "permessage-deflate";
+// Removed from the end of deflated messages, and added back to inflate them
+const TRAILER: [u8; 4] = [0x00, 0x00, 0xff, 0xff];
+// Same limits as tungstenite's defaults
+const MAX_MESSAGE_SIZE: usize = 64 << 20;
+const MAX_FRAME_SIZE: usize = 16 << 20;
+
+const OPCODE_CONTINUATION: u8 = 0x0;
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_BINARY: u8 = 0x2;
+
+/// Parameters of an accepted `permessage-deflate` offer
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct DeflateParams {
+    server_no_context_takeover: bool,
+}
+
+impl DeflateParams {
+    /// Parameters of the first `permessage-deflate` offer of the request that can be accepted
+    pub(crate) fn negotiate(request: &Request) -> Option<DeflateParams> {
+        request
+            .headers()
+            .get_all(SEC_WEBSOCKET_EXTENSIONS)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .find_map(DeflateParams::accept)
+    }
+
+    fn accept(offer: &str) -> Option<DeflateParams> {
+        let mut params = offer.split(';').map(str::trim);
+        if params.next()? != EXTENSION {
+            return None;
+        }
+        let mut accepted = DeflateParams::default();
+        let mut names = HashSet::new();
+        for param in params {
+            let (name, value) = match param.split_once('=') {
+                Some((name, value)) => (name.trim(), Some(value.trim().trim_matches('"'))),
+                None => (param, None),
+            };
+            // An offer with a parameter given twice is invalid
+            if !names.insert(name) {
+                return None;
+            }
+            match (name, value) {
+                ("server_no_context_takeover", None) => accepted.server_no_context_takeover = true,
+                // The inflater keeps its context, which works whether the client resets its own
+                ("client_no_context_takeover", None) | ("client_max_window_bits", None) => {}
+                ("client_max_window_bits", Some(bits)) if is_window_bits(bits) => {}
+                // The deflater always uses the largest window
+                ("server_max_window_bits", Some("15")) => {}
+                _ => return None,
+            }
+        }
+        Some(accepted)
+    }
+
+    /// Value of the `Sec-WebSocket-Extensions` header of the handshake response
+    pub(crate) fn header(&self) -> HeaderValue {
+        if self.server_no_context_takeover {
+            HeaderValue::from_static("permessage-deflate; server_no_context_takeover")
+        } else {
+            HeaderValue::from_static(EXTENSION)
+        }
+    }
+}
+
+fn is_window_bits(bits: &str) -> bool {
+    bits.parse::<u8>()
+        .is_ok_and(|bits| (8..=15).contains(&bits))
+}
+
+/// Deflates the messages sent to a client which negotiated `permessage-deflate`
+pub(crate) struct Deflater {
+    compress: Compress,
+    no_context_takeover: bool,
+    min_size: usize,
+}
+
+impl Deflater {
+    pub(crate) fn new(params: &DeflateParams, config: &Compression) -> Self {
+        Deflater {
+            compress: Compress::new(flate2::Compression::new(config.deflate_level), false),
+            no_context_takeover: params.server_no_context_takeover,
+            min_size: config.deflate_min_size,
+        }
+    }
+
+    /// Text message, sent as a deflated frame unless it is below the size threshold
+    pub(crate) fn message(&mut self, text: String) -> Message {
+        if text.len() < self.min_size {
+            return Message::Text(text);
+        }
+        match self.deflate(text.as_bytes()) {
+            Ok(payload) => {
+                let mut frame = Frame::message(payload, OpCode::Data(Data::Text), true);
+                frame.header_mut().rsv1 = true;
+                Message::Frame(frame)
+            }
+            Err(err) => {
+                warn!("Could not deflate message: {err}");
+                // The next message must not refer to a partly deflated one
+                self.compress.reset();
+                Message::Text(text)
+            }
+        }
+    }
+
+    fn deflate(&mut self, input: &[u8]) -> io::Result<Vec<u8>> {
+        let mut payload = Vec::with_capacity(input.len() / 2 + 64);
+        let mut consumed = 0;
+        loop {
+            if payload.len() == payload.capacity() {
+                payload.reserve(payload.capacity());
+            }
+            let total_in = self.compress.total_in();
+            self.compress
+                .compress_vec(&input[consumed..], &mut payload, FlushCompress::Sync)
+                .map_err(io::Error::other)?;
+            consumed += (self.compress.total_in() - total_in) as usize;
+            // The flush is complete once there is room left in the output
+            if consumed == input.len() && payload.len() < payload.capacity() {
+                break;
+            }
+        }
+        if !payload.ends_with(&TRAILER) {
+            return Err(io::Error::other(
+                "deflated message does not end with a sync flush",
+            ));
+        }
+        payload.truncate(payload.len() - TRAILER.len());
+        if self.no_context_takeover {
+            self.compress.reset();
+        }
+        Ok(payload)
+    }
+}
+
+/// Inflates the messages of a client which negotiated `permessage-deflate`
+struct Inflater {
+    decompress: Decompress,
+    // Opcode and payload of the compressed message being received
+    message: Option<(u8, Vec<u8>)>,
+}
+
+impl Inflater {
+    fn new() -> Self {
+        Inflater {
+            decompress: Decompress::new(false),
+            message: None,
+        }
+    }
+
+    // Takes the first frame of `input` when it is complete, writing the frames tungstenite reads
+    // for it to `output`. Returns false when more input is needed.
+    fn process(&mut self, input: &mut Vec<u8>, output: &mut Vec<u8>) -> io::Result<bool> {
+        let Some(header) = FrameHeader::parse(input)? else {
+            return Ok(false);
+        };
+        let frame_len = header.len + header.payload_len;
+        if input.len() < frame_len {
+            return Ok(false);
+        }
+        let is_data = header.opcode & 0x8 == 0;
+        let compressed = match (&self.message, header.opcode) {
+            (None, OPCODE_TEXT | OPCODE_BINARY) => header.rsv1,
+            (Some(_), OPCODE_CONTINUATION) => true,
+            _ => false,
+        };
+        if !compressed {
+            // Left to tungstenite, which also rejects the frames with reserved bits
+            if is_data && self.message.is_some() {
+                return Err(invalid_data(
+                    "new message before the end of a compressed one",
+                ));
+            }
+            output.extend(input.drain(..frame_len));
+            return Ok(true);
+        }
+        let Some(mask) = header.mask else {
+            return Err(invalid_data("unmasked frame from client"));
+        };
+        if header.opcode == OPCODE_CONTINUATION && header.rsv1 {
+            return Err(invalid_data("RSV1 set on a continuation frame"));
+        }
+        let (_, payload) = self
+            .message
+            .get_or_insert_with(|| (header.opcode, Vec::new()));
+        if payload.len() + header.payload_len > MAX_MESSAGE_SIZE {
+            return Err(invalid_data("compressed message too long"));
+        }
+        payload.extend(
+            input
+                .drain(..frame_len)
+                .skip(header.len)
+                .enumerate()
+                .map(|(i, byte)| byte ^ mask[i % 4]),
+        );
+        if header.fin {
+            let (opcode, mut payload) = self.message.take().unwrap_or_default();
+            payload.extend_from_slice(&TRAILER);
+            let message = self.inflate(&payload)?;
+            write_frames(output, opcode, &message);
+        }
+        Ok(true)
+    }
+
+    fn inflate(&mut self, input: &[u8]) -> io::Result<Vec<u8>> {
+        let mut message = Vec::with_capacity(input.len() * 4);
+        let mut consumed = 0;
+        loop {
+            if message.len() == message.capacity() {
+                message.reserve(message.capacity());
+            }
+            let (total_in, total_out) = (self.decompress.total_in(), self.decompress.total_out());
+            self.decompress
+                .decompress_vec(&input[consumed..], &mut message, FlushDecompress::Sync)
+                .map_err(invalid_data)?;
+            consumed += (self.decompress.total_in() - total_in) as usize;
+            if message.len() > MAX_MESSAGE_SIZE {
+                return Err(invalid_data("inflated message too long"));
+            }
+            if consumed == input.len() && message.len() < message.capacity() {
+                return Ok(message);
+            }
+            if self.decompress.total_in() == total_in && self.decompress.total_out() == total_out {
+                return Err(invalid_data("truncated compressed message"));
+            }
+        }
+    }
+}
+
+// Frames of a message as a client sends them, masked with a zero key leaving the payload as is
+fn write_frames(output: &mut Vec<u8>, opcode: u8, message: &[u8]) {
+    let mut fragments = message.chunks(MAX_FRAME_SIZE).peekable();
+    let mut opcode = opcode;
+    loop {
+        let fragment = fragments.next().unwrap_or_default();
+        let fin = fragments.peek().is_none();
+        output.push(if fin { 0x80 | opcode } else { opcode });
+        match fragment.len() {
+            len if len < 126 => output.push(0x80 | len as u8),
+            len if len <= u16::MAX as usize => {
+                output.push(0x80 | 126);
+                output.extend_from_slice(&(len as u16).to_be_bytes());
+            }
+            len => {
+                output.push(0x80 | 127);
+                output.extend_from_slice(&(len as u64).to_be_bytes());
+            }
+        }
+        output.extend_from_slice(&[0; 4]);
+        output.extend_from_slice(fragment);
+        if fin {
+            return;
+        }
+        opcode = OPCODE_CONTINUATION;
+    }
+}
+
+fn invalid_data<E>(err: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+struct FrameHeader {
+    fin: bool,
+    rsv1: bool,
+    opcode: u8,
+    mask: Option<[u8; 4]>,
+    // Length of the header itself
+    len: usize,
+    payload_len: usize,
+}
+
+impl FrameHeader {
+    // Header at the start of `input`, None until it is complete
+    fn parse(input: &[u8]) -> io::Result<Option<FrameHeader>> {
+        let &[first, second, ..] = input else {
+            return Ok(None);
+        };
+        let (payload_len, mut len) = match second & 0x7f {
+            126 => match input.get(2..4) {
+                Some(bytes) => (u16::from_be_bytes([bytes[0], bytes[1]]) as u64, 4),
+                None => return Ok(None),
+            },
+            127 => match input.get(2..10) {
+                Some(bytes) => (u64::from_be_bytes(bytes.try_into().unwrap()), 10),
+                None => return Ok(None),
+            },
+            payload_len => (payload_len as u64, 2),
+        };
+        if payload_len > MAX_MESSAGE_SIZE as u64 {
+            return Err(invalid_data("frame too long"));
+        }
+        let mask = if second & 0x80 != 0 {
+            match input.get(len..len + 4) {
+                Some(mask) => {
+                    len += 4;
+                    Some(mask.try_into().unwrap())
+                }
+                None => return Ok(None),
+            }
+        } else {
+            None
+        };
+        Ok(Some(FrameHeader {
+            fin: first & 0x80 != 0,
+            rsv1: first & 0x40 != 0,
+            opcode: first & 0x0f,
+            mask,
+            len,
+            payload_len: payload_len as usize,
+        }))
+    }
+}
+
+/// Stream of a WebSocket connection, inflating the compressed frames of the client once
+/// `permessage-deflate` is negotiated, so that tungstenite reads them uncompressed
+pub(crate) struct InflateStream<S> {
+    inner: S,
+    negotiated: Arc<OnceLock<DeflateParams>>,
+    inflater: Option<Inflater>,
+    input: Vec<u8>,
+    output: Vec<u8>,
+    // Bytes of `output` already read
+    read: usize,
+}
+
+impl<S> InflateStream<S> {
+    /// The extension is set in `negotiated` by the handshake callback, the stream being read as
+    /// is until then
+    pub(crate) fn new(inner: S, negotiated: Arc<OnceLock<DeflateParams>>) -> Self {
+        InflateStream {
+            inner,
+            negotiated,
+            inflater: None,
+            input: Vec::new(),
+            output: Vec::new(),
+            read: 0,
+        }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for InflateStream<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        // The client sends no frame before the handshake response
+        if this.inflater.is_none() && this.negotiated.get().is_none() {
+            return Pin::new(&mut this.inner).poll_read(cx, buf);
+        }
+        let inflater = this.inflater.get_or_insert_with(Inflater::new);
+        loop {
+            if this.read < this.output.len() {
+                let len = buf.remaining().min(this.output.len() - this.read);
+                buf.put_slice(&this.output[this.read..this.read + len]);
+                this.read += len;
+                if this.read == this.output.len() {
+                    this.output.clear();
+                    this.read = 0;
+                }
+                return Poll::Ready(Ok(()));
+            }
+            if inflater.process(&mut this.input, &mut this.output)? {
+                continue;
+            }
+            let mut chunk = [0; 8192];
+            let mut chunk = ReadBuf::new(&mut chunk);
+            ready!(Pin::new(&mut this.inner).poll_read(cx, &mut chunk))?;
+            if chunk.filled().is_empty() {
+                // End of stream, a partial frame left is for tungstenite to report
+                return Poll::Ready(Ok(()));
+            }
+            this.input.extend_from_slice(chunk.filled());
+        }
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for InflateStream<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{SinkExt, StreamExt};
+    use tokio::io::AsyncWriteExt;
+    use tokio_tungstenite::{tungstenite::protocol::Role, WebSocketStream};
+
+    use super::*;
+
+    fn request(extensions: &[&str]) -> Request {
+        let mut request = Request::builder();
+        for extension in extensions {
+            request = request.header(SEC_WEBSOCKET_EXTENSIONS, *extension);
+        }
+        request.body(()).unwrap()
+    }
+
+    fn test_deflater(server_no_context_takeover: bool) -> Deflater {
+        let config = Compression {
+            deflate_min_size: 0,
+            ..Default::default()
+        };
+        let params = DeflateParams {
+            server_no_context_takeover,
+        };
+        Deflater::new(&params, &config)
+    }
+
+    fn deflate(deflater: &mut Deflater, text: &str) -> Vec<u8> {
+        match deflater.message(text.to_string()) {
+            Message::Frame(frame) => {
+                assert!(frame.header().rsv1);
+                frame.into_data()
+            }
+            message => panic!("Not deflated: {message:?}"),
+        }
+    }
+
+    // A frame as a client sends it
+    fn client_frame(first: u8, payload: &[u8]) -> Vec<u8> {
+        let mask = [0x12, 0x34, 0x56, 0x78];
+        let mut frame = vec![first];
+        match payload.len() {
+            len if len < 126 => frame.push(0x80 | len as u8),
+            len => {
+                frame.push(0x80 | 126);
+                frame.extend_from_slice(&(len as u16).to_be_bytes());
+            }
+        }
+        frame.extend_from_slice(&mask);
+        frame.extend(payload.iter().enumerate().map(|(i, b)| b ^ mask[i % 4]));
+        frame
+    }
+
+    #[test]
+    fn negotiation() {
+        let negotiate = |extensions: &[&str]| DeflateParams::negotiate(&request(extensions));
+        let accepted = Some(DeflateParams::default());
+
+        assert_eq!(negotiate(&[]), None);
+        assert_eq!(negotiate(&["x-webkit-deflate-frame"]), None);
+        assert_eq!(negotiate(&["permessage-deflate"]), accepted);
+        // As browsers offer it
+        assert_eq!(
+            negotiate(&["permessage-deflate; client_max_window_bits"]),
+            accepted
+        );
+        assert_eq!(
+            negotiate(&["permessage-deflate; server_no_context_takeover"]),
+            Some(DeflateParams {
+                server_no_context_takeover: true
+            })
+        );
+        // Smaller windows cannot be used by the deflater, unknown or repeated parameters are invalid
+        assert_eq!(
+            negotiate(&["permessage-deflate; server_max_window_bits=10"]),
+            None
+        );
+        assert_eq!(negotiate(&["permessage-deflate; unknown"]), None);
+        assert_eq!(
+            negotiate(&[
+                "permessage-deflate; client_no_context_takeover; client_no_context_takeover"
+            ]),
+            None
+        );
+        // The first offer that can be accepted is taken
+        assert_eq!(
+            negotiate(&[
+                "permessage-deflate; server_max_window_bits=10, permessage-deflate; client_max_window_bits=\"12\""
+            ]),
+            accepted
+        );
+        assert_eq!(
+            negotiate(&["foo", "permessage-deflate; server_max_window_bits=15"]),
+            accepted
+        );
+
+        assert_eq!(
+            DeflateParams::default().header(),
+            HeaderValue::from_static("permessage-deflate")
+        );
+    }
+
+    #[test]
+    fn context_takeover() {
+        let text = r#"{"temperature": 21.5, "unit": "C"}"#.repeat(10);
+        let mut inflater = Inflater::new();
+        let mut inflate = |payload: Vec<u8>| {
+            let mut payload = payload;
+            payload.extend_from_slice(&TRAILER);
+            String::from_utf8(inflater.inflate(&payload).unwrap()).unwrap()
+        };
+
+        let mut deflater = test_deflater(false);
+        let first = deflate(&mut deflater, &text);
+        let second = deflate(&mut deflater, &text);
+        // The second message refers to the first one
+        assert!(second.len() < first.len());
+        assert_eq!(inflate(first), text);
+        assert_eq!(inflate(second), text);
+
+        let mut deflater = test_deflater(true);
+        let first = deflate(&mut deflater, &text);
+        assert_eq!(deflate(&mut deflater, &text), first);
+        assert_eq!(inflate(first), text);
+    }
+
+    #[test]
+    fn small_messages_are_not_deflated() {
+        let config = Compression::default();
+        let mut deflater = Deflater::new(&DeflateParams::default(), &config);
+        let text = "a".repeat(config.deflate_min_size - 1);
+        assert_eq!(deflater.message(text.clone()), Message::Text(text));
+    }
+
+    #[tokio::test]
+    async fn inflate_client_frames() {
+        let (client, server) = tokio::io::duplex(1 << 16);
+        let negotiated = Arc::new(OnceLock::new());
+        negotiated.set(DeflateParams::default()).unwrap();
+        let server = InflateStream::new(server, negotiated);
+        let mut server = WebSocketStream::from_raw_socket(server, Role::Server, None).await;
+
+        let text = r#"{"temperature": 21.5, "unit": "C"}"#.repeat(10);
+        let mut deflater = test_deflater(false);
+        let (mut client_rx, mut client_tx) = tokio::io::split(client);
+        let deflated = deflate(&mut deflater, &text);
+        let mut frames = Vec::new();
+        // Compressed message
+        frames.extend(client_frame(0x80 | 0x40 | OPCODE_TEXT, &deflated));
+        // Compressed message in two fragments, with a ping in between
+        let deflated = deflate(&mut deflater, &text);
+        let (start, end) = deflated.split_at(deflated.len() / 2);
+        frames.extend(client_frame(0x40 | OPCODE_TEXT, start));
+        frames.extend(client_frame(0x80 | 0x9, b"ping"));
+        frames.extend(client_frame(0x80 | OPCODE_CONTINUATION, end));
+        // Uncompressed message
+        frames.extend(client_frame(0x80 | OPCODE_TEXT, b"plain"));
+        client_tx.write_all(&frames).await.unwrap();
+
+        assert_eq!(
+            server.next().await.unwrap().unwrap(),
+            Message::Text(text.clone())
+        );
+        assert_eq!(
+            server.next().await.unwrap().unwrap(),
+            Message::Ping(b"ping".to_vec())
+        );
+        assert_eq!(server.next().await.unwrap().unwrap(), Message::Text(text));
+        assert_eq!(
+            server.next().await.unwrap().unwrap(),
+            Message::Text("plain".to_string())
+        );
+
+        // Deflated frames are sent with the RSV1 bit
+        server
+            .send(deflater.message("sent".repeat(10)))
+            .await
+            .unwrap();
+        // After the pong answering the ping
+        let mut sent = [0; 7];
+        tokio::io::AsyncReadExt::read_exact(&mut client_rx, &mut sent)
+            .await
+            .unwrap();
+        assert_eq!(sent[..6], [0x80 | 0xa, 4, b'p', b'i', b'n', b'g']);
+        assert_eq!(sent[6], 0x80 | 0x40 | OPCODE_TEXT);
+    }
+
+    #[tokio::test]
+    async fn corrupted_frames_are_refused() {
+        let (client, server) = tokio::io::duplex(1 << 16);
+        let negotiated = Arc::new(OnceLock::new());
+        negotiated.set(DeflateParams::default()).unwrap();
+        let server = InflateStream::new(server, negotiated);
+        let mut server = WebSocketStream::from_raw_socket(server, Role::Server, None).await;
+
+        let (_client_rx, mut client_tx) = tokio::io::split(client);
+        client_tx
+            .write_all(&client_frame(0x80 | 0x40 | OPCODE_TEXT, &[0xff; 16]))
+            .await
+            .unwrap();
+        assert!(server.next().await.unwrap().is_err());
+    }
+}
@@ -0,0 +1,260 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use serde_json::Value;
+use zenoh::{
+    bytes::Encoding,
+    query::Parameters,
+    sample::{Sample, SampleKind},
+};
+use zenoh_remote_api_protocol::{JsonPredicateWS, SampleFilterWS, SampleKindWS};
+use zenoh_result::{bail, zerror, ZResult};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Field(String),
+    Index(usize),
+}
+
+// Parses the subset of JSONPath made of fields and indexes: `$.a.b[0]`, `$['a b'][1]`
+fn parse_path(path: &str) -> ZResult<Vec<Segment>> {
+    let mut rest = path.strip_prefix('$').unwrap_or(path);
+    let mut segments = Vec::new();
+    while !rest.is_empty() {
+        if let Some(field) = rest.strip_prefix('.') {
+            let end = field.find(['.', '[']).unwrap_or(field.len());
+            if end == 0 {
+                bail!("Empty field in JSON path {path:?}");
+            }
+            segments.push(Segment::Field(field[..end].to_string()));
+            rest = &field[end..];
+        } else if let Some(bracket) = rest.strip_prefix('[') {
+            let (inside, after) = bracket
+                .split_once(']')
+                .ok_or_else(|| zerror!("Unclosed bracket in JSON path {path:?}"))?;
+            let segment = match inside.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')) {
+                Some(field) => Segment::Field(field.to_string()),
+                None => Segment::Index(
+                    inside
+                        .parse()
+                        .map_err(|_| zerror!("Invalid index {inside:?} in JSON path {path:?}"))?,
+                ),
+            };
+            segments.push(segment);
+            rest = after;
+        } else if segments.is_empty() && path == rest {
+            // The first field may have no leading dot
+            let end = rest.find(['.', '[']).unwrap_or(rest.len());
+            segments.push(Segment::Field(rest[..end].to_string()));
+            rest = &rest[end..];
+        } else {
+            bail!("Invalid JSON path {path:?}");
+        }
+    }
+    Ok(segments)
+}
+
+fn select<'a>(value: &'a Value, path: &[Segment]) -> Option<&'a Value> {
+    path.iter().try_fold(value, |value, segment| match segment {
+        Segment::Field(field) => value.get(field),
+        Segment::Index(index) => value.get(index),
+    })
+}
+
+fn matches_predicate(value: Option<&Value>, predicate: &JsonPredicateWS) -> bool {
+    let number = value.and_then(Value::as_f64);
+    match predicate {
+        JsonPredicateWS::Exists => value.is_some(),
+        JsonPredicateWS::Eq(expected) => value == Some(expected),
+        JsonPredicateWS::Ne(expected) => value.is_some_and(|value| value != expected),
+        JsonPredicateWS::Lt(bound) => number.is_some_and(|number| number < *bound),
+        JsonPredicateWS::Le(bound) => number.is_some_and(|number| number <= *bound),
+        JsonPredicateWS::Gt(bound) => number.is_some_and(|number| number > *bound),
+        JsonPredicateWS::Ge(bound) => number.is_some_and(|number| number >= *bound),
+    }
+}
+
+fn is_json(encoding: &Encoding) -> bool {
+    let encoding = encoding.to_string();
+    let name = encoding
+        .split_once(';')
+        .map_or(encoding.as_str(), |(name, _)| name);
+    matches!(name, "application/json" | "text/json")
+}
+
+#[derive(Debug, Clone)]
+enum Filter {
+    Kind(SampleKind),
+    Attachment {
+        key: String,
+        value: Option<String>,
+    },
+    Json {
+        path: Vec<Segment>,
+        predicate: JsonPredicateWS,
+    },
+}
+
+/// Drops the samples of a subscriber which do not match all the filters of its client
+#[derive(Debug, Clone)]
+pub(crate) struct SampleFilter {
+    filters: Vec<Filter>,
+}
+
+impl SampleFilter {
+    pub(crate) fn new(filters: Vec<SampleFilterWS>) -> ZResult<Self> {
+        let filters = filters
+            .into_iter()
+            .map(|filter| {
+                Ok(match filter {
+                    SampleFilterWS::Kind(SampleKindWS::Put) => Filter::Kind(SampleKind::Put),
+                    SampleFilterWS::Kind(SampleKindWS::Delete) => Filter::Kind(SampleKind::Delete),
+                    SampleFilterWS::Attachment { key, value } => Filter::Attachment { key, value },
+                    SampleFilterWS::Json { path, predicate } => Filter::Json {
+                        path: parse_path(&path)?,
+                        predicate,
+                    },
+                })
+            })
+            .collect::<ZResult<_>>()?;
+        Ok(SampleFilter { filters })
+    }
+
+    pub(crate) fn matches(&self, sample: &Sample) -> bool {
+        // The payload is parsed once, by the first JSON filter
+        let mut json: Option<Option<Value>> = None;
+        self.filters.iter().all(|filter| match filter {
+            Filter::Kind(kind) => sample.kind() == *kind,
+            Filter::Attachment { key, value } => {
+                let Some(attachment) = sample.attachment() else {
+                    return false;
+                };
+                let Ok(attachment) = attachment.try_to_string() else {
+                    return false;
+                };
+                let parameters = Parameters::from(attachment.as_ref());
+                match (parameters.get(key.as_str()), value) {
+                    (Some(found), Some(value)) => found == value,
+                    (found, None) => found.is_some(),
+                    (None, Some(_)) => false,
+                }
+            }
+            Filter::Json { path, predicate } => {
+                let json = json.get_or_insert_with(|| {
+                    let is_put_json =
+                        sample.kind() == SampleKind::Put && is_json(sample.encoding());
+                    is_put_json
+                        .then(|| serde_json::from_slice(&sample.payload().to_bytes()).ok())
+                        .flatten()
+                });
+                json.as_ref()
+                    .is_some_and(|json| matches_predicate(select(json, path), predicate))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use zenoh::{key_expr::KeyExpr, sample::SampleBuilder};
+
+    use super::*;
+
+    fn sample(payload: Value, attachment: &str) -> Sample {
+        SampleBuilder::put(KeyExpr::try_from("a/b").unwrap(), payload.to_string())
+            .encoding(Encoding::APPLICATION_JSON)
+            .attachment(attachment.to_string())
+            .into()
+    }
+
+    #[test]
+    fn paths() {
+        use Segment::{Field, Index};
+
+        assert_eq!(
+            parse_path("$.a.b[0]").unwrap(),
+            [Field("a".into()), Field("b".into()), Index(0)]
+        );
+        assert_eq!(
+            parse_path("a['b c'][12]").unwrap(),
+            [Field("a".into()), Field("b c".into()), Index(12)]
+        );
+        assert!(parse_path("$").unwrap().is_empty());
+        for path in ["$.", "$.a..b", "$[x]", "$[0", "$a"] {
+            assert!(parse_path(path).is_err(), "{path}");
+        }
+    }
+
+    #[test]
+    fn predicates() {
+        let value = json!({"temperature": 31.5, "tags": ["alarm"]});
+        let temperature = select(&value, &parse_path("$.temperature").unwrap());
+        let tag = select(&value, &parse_path("$.tags[0]").unwrap());
+        let missing = select(&value, &parse_path("$.tags[1]").unwrap());
+
+        assert!(matches_predicate(temperature, &JsonPredicateWS::Gt(30.0)));
+        assert!(!matches_predicate(temperature, &JsonPredicateWS::Le(30.0)));
+        assert!(matches_predicate(tag, &JsonPredicateWS::Eq(json!("alarm"))));
+        assert!(!matches_predicate(tag, &JsonPredicateWS::Gt(0.0)));
+        assert!(!matches_predicate(missing, &JsonPredicateWS::Exists));
+        assert!(!matches_predicate(
+            missing,
+            &JsonPredicateWS::Ne(json!("alarm"))
+        ));
+    }
+
+    #[test]
+    fn sample_filters() {
+        let filter = SampleFilter::new(vec![
+            SampleFilterWS::Kind(SampleKindWS::Put),
+            SampleFilterWS::Attachment {
+                key: "unit".into(),
+                value: Some("C".into()),
+            },
+        ])
+        .unwrap();
+        assert!(filter.matches(&sample(json!(1), "unit=C;room=kitchen")));
+        assert!(!filter.matches(&sample(json!(1), "unit=F")));
+        assert!(!filter.matches(&sample(json!(1), "")));
+
+        let filter = SampleFilter::new(vec![
+            SampleFilterWS::Json {
+                path: "$.temperature".into(),
+                predicate: JsonPredicateWS::Ge(20.0),
+            },
+            SampleFilterWS::Json {
+                path: "$.unit".into(),
+                predicate: JsonPredicateWS::Eq(json!("C")),
+            },
+        ])
+        .unwrap();
+        assert!(filter.matches(&sample(json!({"temperature": 21, "unit": "C"}), "")));
+        assert!(!filter.matches(&sample(json!({"temperature": 19, "unit": "C"}), "")));
+        assert!(!filter.matches(&sample(json!({"temperature": 21}), "")));
+        // Only the payloads in a JSON encoding are parsed
+        let text: Sample = SampleBuilder::put(
+            KeyExpr::try_from("a/b").unwrap(),
+            r#"{"temperature": 21, "unit": "C"}"#,
+        )
+        .into();
+        assert!(!filter.matches(&text));
+
+        assert!(SampleFilter::new(vec![SampleFilterWS::Json {
+            path: "$[x]".into(),
+            predicate: JsonPredicateWS::Exists,
+        }])
+        .is_err());
+    }
+}
@@ -0,0 +1,320 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{
+    future::{self, Future},
+    mem,
+    ops::Deref,
+    time::Duration,
+};
+
+use flume::Sender;
+use tokio::{
+    select,
+    sync::oneshot,
+    task::JoinHandle,
+    time::{sleep_until, Instant},
+};
+use tracing::error;
+use uuid::Uuid;
+use zenoh::{
+    handlers::{FifoChannelHandler, RingChannelHandler},
+    sample::{Sample, SampleKind},
+};
+use zenoh_remote_api_protocol::{
+    BatchConfigWS, DataMsg, RemoteAPIMsg, SampleFilterWS, SampleWS, ThrottleWS,
+};
+use zenoh_result::{bail, ZResult};
+
+use crate::{
+    compress::Compressor, filter::SampleFilter, spawn_future, throttle::Throttle,
+    transcode::Transcoder,
+};
+
+/// Converts the samples received by a subscriber to the messages forwarded to its client,
+/// transcoding then compressing their payloads if the client asked for it
+#[derive(Debug, Clone)]
+pub(crate) struct SampleForwarder {
+    transcoder: Option<Transcoder>,
+    compressor: Option<Compressor>,
+}
+
+impl SampleForwarder {
+    pub(crate) fn new(transcoder: Option<Transcoder>, compressor: Option<Compressor>) -> Self {
+        SampleForwarder {
+            transcoder,
+            compressor,
+        }
+    }
+
+    pub(crate) fn sample_ws(&self, sample: &Sample) -> SampleWS {
+        let transcoded = self.transcoder.as_ref().and_then(|transcoder| {
+            let payload = transcoder.transcode_sample(sample)?;
+            Some((payload, transcoder.encoding()))
+        });
+        let compressor = match &self.compressor {
+            Some(compressor) if sample.kind() == SampleKind::Put => compressor,
+            _ => {
+                return match transcoded {
+                    Some((payload, encoding)) => SampleWS::with_payload(sample, payload, encoding),
+                    None => SampleWS::from(sample),
+                }
+            }
+        };
+
+        let (payload, encoding) = match &transcoded {
+            Some((payload, encoding)) => (payload.as_slice().into(), *encoding),
+            None => (sample.payload().to_bytes(), sample.encoding()),
+        };
+        match compressor.compress(&payload) {
+            Some(compressed) => SampleWS::with_payload(sample, compressed, encoding)
+                .with_compression(compressor.compression()),
+            None => SampleWS::with_payload(sample, payload, encoding),
+        }
+    }
+}
+
+// Samples waiting to be sent together
+#[derive(Debug)]
+struct Batch {
+    max_size: usize,
+    max_latency: Duration,
+    samples: Vec<SampleWS>,
+    // Time the oldest pending sample must be sent by
+    deadline: Option<Instant>,
+}
+
+/// Sends the samples of a subscriber to its client, each in its own `DataMsg::Sample`, or
+/// in `DataMsg::Samples` batches, after filtering and throttling them if the client asked for it
+#[derive(Debug)]
+pub(crate) struct SampleSender {
+    forwarder: SampleForwarder,
+    websocket_tx: Sender<RemoteAPIMsg>,
+    subscriber_id: Uuid,
+    batch: Option<Batch>,
+    throttle: Option<Throttle>,
+    filter: Option<SampleFilter>,
+}
+
+impl SampleSender {
+    pub(crate) fn new(
+        forwarder: SampleForwarder,
+        websocket_tx: Sender<RemoteAPIMsg>,
+        subscriber_id: Uuid,
+        batch: Option<BatchConfigWS>,
+        throttle: Option<ThrottleWS>,
+        filters: Option<Vec<SampleFilterWS>>,
+    ) -> ZResult<Self> {
+        let batch = match batch {
+            Some(BatchConfigWS { max_size: 0, .. }) => {
+                bail!("Batches of subscriber {subscriber_id} must hold at least one sample")
+            }
+            Some(config) => Some(Batch {
+                max_size: config.max_size,
+                max_latency: Duration::from_millis(config.max_latency),
+                samples: Vec::new(),
+                deadline: None,
+            }),
+            None => None,
+        };
+        let throttle = throttle.map(Throttle::new).transpose()?;
+        let filter = filters.map(SampleFilter::new).transpose()?;
+        Ok(SampleSender {
+            forwarder,
+            websocket_tx,
+            subscriber_id,
+            batch,
+            throttle,
+            filter,
+        })
+    }
+
+    pub(crate) fn push(&mut self, sample: Sample) {
+        if self
+            .filter
+            .as_ref()
+            .is_some_and(|filter| !filter.matches(&sample))
+        {
+            return;
+        }
+        let sample = match &mut self.throttle {
+            Some(throttle) => match throttle.push(sample, Instant::now()) {
+                Some(sample) => sample,
+                None => return,
+            },
+            None => sample,
+        };
+        self.forward(&sample);
+    }
+
+    /// Sends the samples held by the throttling and the batch whose deadline has passed
+    pub(crate) fn send_due(&mut self) {
+        let now = Instant::now();
+        if let Some(throttle) = &mut self.throttle {
+            for sample in throttle.take_due(now) {
+                self.forward(&sample);
+            }
+        }
+        if self
+            .batch_deadline()
+            .is_some_and(|deadline| deadline <= now)
+        {
+            self.flush_batch();
+        }
+    }
+
+    /// Sends all the pending samples
+    pub(crate) fn flush(&mut self) {
+        if let Some(throttle) = &mut self.throttle {
+            for sample in throttle.take_all() {
+                self.forward(&sample);
+            }
+        }
+        self.flush_batch();
+    }
+
+    /// Completes when some pending samples must be sent
+    pub(crate) async fn deadline(&self) {
+        let throttle_deadline = self.throttle.as_ref().and_then(Throttle::deadline);
+        match throttle_deadline
+            .into_iter()
+            .chain(self.batch_deadline())
+            .min()
+        {
+            Some(deadline) => sleep_until(deadline).await,
+            None => future::pending().await,
+        }
+    }
+
+    fn forward(&mut self, sample: &Sample) {
+        let sample_ws = self.forwarder.sample_ws(sample);
+        let Some(batch) = &mut self.batch else {
+            return self.send(DataMsg::Sample(sample_ws, self.subscriber_id));
+        };
+        if batch.samples.is_empty() {
+            batch.deadline = Some(Instant::now() + batch.max_latency);
+        }
+        batch.samples.push(sample_ws);
+        if batch.samples.len() >= batch.max_size {
+            self.flush_batch();
+        }
+    }
+
+    fn batch_deadline(&self) -> Option<Instant> {
+        self.batch.as_ref().and_then(|batch| batch.deadline)
+    }
+
+    fn flush_batch(&mut self) {
+        let Some(batch) = &mut self.batch else {
+            return;
+        };
+        if batch.samples.is_empty() {
+            return;
+        }
+        batch.deadline = None;
+        let samples = mem::take(&mut batch.samples);
+        self.send(DataMsg::Samples(samples, self.subscriber_id));
+    }
+
+    fn send(&self, data_msg: DataMsg) {
+        if let Err(e) = self.websocket_tx.send(RemoteAPIMsg::Data(data_msg)) {
+            error!("Forward Sample Channel error: {e}");
+        };
+    }
+}
+
+/// Channel handler of a subscriber
+pub(crate) trait ReceiveSample: Send + Sync + 'static {
+    /// The next sample, None once the subscriber is undeclared
+    fn recv_sample(&self) -> impl Future<Output = Option<Sample>> + Send + '_;
+
+    /// The next sample already received, without waiting for one
+    fn try_recv_sample(&self) -> Option<Sample>;
+}
+
+impl ReceiveSample for FifoChannelHandler<Sample> {
+    async fn recv_sample(&self) -> Option<Sample> {
+        self.recv_async().await.ok()
+    }
+
+    fn try_recv_sample(&self) -> Option<Sample> {
+        self.try_recv().ok().flatten()
+    }
+}
+
+impl ReceiveSample for RingChannelHandler<Sample> {
+    async fn recv_sample(&self) -> Option<Sample> {
+        self.recv_async().await.ok()
+    }
+
+    fn try_recv_sample(&self) -> Option<Sample> {
+        self.try_recv().ok().flatten()
+    }
+}
+
+/// Task forwarding the samples of a subscriber to its client with a `SampleSender`
+#[derive(Debug)]
+pub(crate) struct SubscriberTask {
+    join_handle: JoinHandle<()>,
+    stop_tx: oneshot::Sender<()>,
+}
+
+impl SubscriberTask {
+    pub(crate) fn spawn<S, H>(subscriber: S, mut sender: SampleSender) -> Self
+    where
+        S: Deref<Target = H> + Send + Sync + 'static,
+        H: ReceiveSample,
+    {
+        let (stop_tx, mut stop_rx) = oneshot::channel();
+        let join_handle = spawn_future(async move {
+            loop {
+                // Stopping is checked first, a subscriber receiving samples continuously
+                // would otherwise never be undeclared
+                select! {
+                    biased;
+                    _ = &mut stop_rx => {
+                        // The samples already received are forwarded before stopping
+                        while let Some(sample) = subscriber.try_recv_sample() {
+                            sender.push(sample);
+                        }
+                        sender.send_due();
+                        break;
+                    }
+                    _ = sender.deadline() => sender.send_due(),
+                    sample = subscriber.recv_sample() => match sample {
+                        Some(sample) => sender.push(sample),
+                        None => break,
+                    },
+                }
+            }
+            // The subscriber is undeclared once the pending samples are sent
+            sender.flush();
+            drop(subscriber);
+        });
+        SubscriberTask {
+            join_handle,
+            stop_tx,
+        }
+    }
+
+    /// Undeclares the subscriber after sending its pending samples
+    pub(crate) fn stop(self) {
+        drop(self.stop_tx);
+    }
+
+    /// Undeclares the subscriber right away, dropping its pending samples
+    pub(crate) fn abort(self) {
+        self.join_handle.abort();
+    }
+}
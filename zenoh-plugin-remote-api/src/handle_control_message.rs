@@ -15,35 +15,33 @@
 use std::{error::Error, net::SocketAddr, time::Duration};
 
 use tracing::{error, warn};
+use uhlc::Timestamp;
 use uuid::Uuid;
 use zenoh::{
+    bytes::Encoding,
     handlers::{FifoChannel, RingChannel},
     key_expr::KeyExpr,
-    query::Selector,
+    qos::Priority,
+    query::{Reply, Selector},
+    sample::SourceInfo,
+};
+use zenoh_ext::{
+    AdvancedPublisherBuilderExt, AdvancedSubscriberBuilderExt, CacheConfig, HistoryConfig, Miss,
+    MissDetectionConfig, RecoveryConfig,
 };
 
-use crate::{
-    interface::{
-        ControlMsg, DataMsg, HandlerChannel, LivelinessMsg, QueryWS, QueryableMsg, RemoteAPIMsg,
-        ReplyWS, SampleWS,
-    },
-    spawn_future, RemoteState, StateMap,
+use zenoh_remote_api_protocol::{
+    ControlMsg, DataMsg, EntityGlobalIdWS, HandlerChannel, LivelinessMsg, QueryWS, QueryableMsg,
+    RemoteAPIMsg, ReplyWS, SampleWS,
 };
 
-///
-/// Macro to replace the pattern of adding to builders if a field exists
-/// i.e. add_if_some!(consolidation, get_builder);
-/// expands to
-/// if Some(consolidation) = consolidation{
-///     get_builder = get_builder.consolidation(consolidation);
-/// }
-macro_rules! add_if_some {
-    ($x:ident, $y:ident) => {
-        if let Some($x) = $x {
-            $y = $y.$x($x);
-        }
-    };
-}
+use crate::{
+    compress::Compressor,
+    forward::{SampleForwarder, SampleSender, SubscriberTask},
+    spawn_future,
+    transcode::Transcoder,
+    RemoteState, StateMap,
+};
 
 /// Function to handle control messages recieved from the client to Plugin
 pub(crate) async fn handle_control_message(
@@ -86,6 +84,8 @@ pub(crate) async fn handle_control_message(
             payload,
             attachment,
         } => {
+            let priority = priority.map(Priority::try_from).transpose()?;
+            let encoding = encoding.map(Encoding::from);
             let selector = Selector::owned(key_expr, parameters.unwrap_or_default());
             let mut get_builder = state_map.session.get(selector);
 
@@ -154,7 +154,13 @@ pub(crate) async fn handle_control_message(
             priority,
             express,
             attachment,
+            timestamp,
+            source_info,
         } => {
+            let timestamp = timestamp.map(Timestamp::try_from).transpose()?;
+            let source_info = source_info.map(SourceInfo::try_from).transpose()?;
+            let priority = priority.map(Priority::try_from).transpose()?;
+            let encoding = encoding.map(Encoding::from);
             let mut put_builder = match payload.b64_to_bytes() {
                 Ok(payload) => state_map.session.put(key_expr, payload),
                 Err(err) => {
@@ -167,6 +173,8 @@ pub(crate) async fn handle_control_message(
             add_if_some!(congestion_control, put_builder);
             add_if_some!(priority, put_builder);
             add_if_some!(express, put_builder);
+            add_if_some!(timestamp, put_builder);
+            add_if_some!(source_info, put_builder);
 
             if let Some(attachment_b64) = attachment {
                 match attachment_b64.b64_to_bytes() {
@@ -183,11 +191,18 @@ pub(crate) async fn handle_control_message(
             priority,
             express,
             attachment,
+            timestamp,
+            source_info,
         } => {
+            let timestamp = timestamp.map(Timestamp::try_from).transpose()?;
+            let source_info = source_info.map(SourceInfo::try_from).transpose()?;
+            let priority = priority.map(Priority::try_from).transpose()?;
             let mut delete_builder = state_map.session.delete(key_expr);
             add_if_some!(congestion_control, delete_builder);
             add_if_some!(priority, delete_builder);
             add_if_some!(express, delete_builder);
+            add_if_some!(timestamp, delete_builder);
+            add_if_some!(source_info, delete_builder);
             if let Some(attachment_b64) = attachment {
                 match attachment_b64.b64_to_bytes() {
                     Ok(attachment) => delete_builder = delete_builder.attachment(attachment),
@@ -202,57 +217,49 @@ pub(crate) async fn handle_control_message(
             key_expr: owned_key_expr,
             handler,
             id: subscriber_uuid,
+            transcode_to,
+            compression,
+            batch,
+            throttle,
+            filters,
         } => {
             let key_expr = KeyExpr::new(owned_key_expr.clone())?;
-            let ch_tx = state_map.websocket_tx.clone();
+            let transcoder = transcode_to
+                .map(|encoding| Transcoder::new(encoding.into()))
+                .transpose()?;
+            let compressor =
+                compression.map(|compression| Compressor::new(compression, &state_map.compression));
+            let forwarder = SampleForwarder::new(transcoder, compressor);
+            let sender = SampleSender::new(
+                forwarder,
+                state_map.websocket_tx.clone(),
+                subscriber_uuid,
+                batch,
+                throttle,
+                filters,
+            )?;
 
-            let join_handle = match handler {
+            let subscriber_builder = state_map.session.declare_subscriber(key_expr);
+            let task = match handler {
                 HandlerChannel::Fifo(size) => {
-                    let subscriber = state_map
-                        .session
-                        .declare_subscriber(key_expr)
-                        .with(FifoChannel::new(size))
-                        .await?;
-
-                    spawn_future(async move {
-                        while let Ok(sample) = subscriber.recv_async().await {
-                            let sample_ws = SampleWS::from(sample);
-                            let remote_api_message =
-                                RemoteAPIMsg::Data(DataMsg::Sample(sample_ws, subscriber_uuid));
-                            if let Err(e) = ch_tx.send(remote_api_message) {
-                                error!("Forward Sample Channel error: {e}");
-                            };
-                        }
-                    })
+                    let subscriber = subscriber_builder.with(FifoChannel::new(size)).await?;
+                    SubscriberTask::spawn(subscriber, sender)
                 }
                 HandlerChannel::Ring(size) => {
-                    let subscriber = state_map
-                        .session
-                        .declare_subscriber(key_expr)
-                        .with(RingChannel::new(size))
-                        .await?;
-
-                    spawn_future(async move {
-                        while let Ok(sample) = subscriber.recv_async().await {
-                            let sample_ws = SampleWS::from(sample);
-                            let remote_api_message =
-                                RemoteAPIMsg::Data(DataMsg::Sample(sample_ws, subscriber_uuid));
-                            if let Err(e) = ch_tx.send(remote_api_message) {
-                                error!("Forward Sample Channel error: {e}");
-                            };
-                        }
-                    })
+                    let subscriber = subscriber_builder.with(RingChannel::new(size)).await?;
+                    SubscriberTask::spawn(subscriber, sender)
                 }
             };
 
             state_map
                 .subscribers
-                .insert(subscriber_uuid, (join_handle, owned_key_expr));
+                .insert(subscriber_uuid, (task, owned_key_expr, handler));
             return Ok(Some(ControlMsg::Subscriber(subscriber_uuid)));
         }
         ControlMsg::UndeclareSubscriber(uuid) => {
-            if let Some((join_handle, _)) = state_map.subscribers.remove(&uuid) {
-                join_handle.abort(); // This should drop the underlying subscriber of the future
+            if let Some((task, _, _)) = state_map.subscribers.remove(&uuid) {
+                // The pending batched or throttled samples are sent before the subscriber is dropped
+                task.stop();
             } else {
                 warn!("UndeclareSubscriber: No Subscriber with UUID {uuid}");
             }
@@ -267,6 +274,8 @@ pub(crate) async fn handle_control_message(
             express,
             reliability,
         } => {
+            let priority = priority.map(Priority::try_from).transpose()?;
+            let encoding = encoding.map(Encoding::from);
             let mut publisher_builder = state_map.session.declare_publisher(key_expr);
             add_if_some!(encoding, publisher_builder);
             add_if_some!(congestion_control, publisher_builder);
@@ -279,11 +288,200 @@ pub(crate) async fn handle_control_message(
         }
         ControlMsg::UndeclarePublisher(id) => {
             if let Some(publisher) = state_map.publishers.remove(&id) {
+                // Matching listeners do not outlive their publisher
+                state_map
+                    .matching_listeners
+                    .retain(|_, (_, publisher_id)| *publisher_id != id);
                 publisher.undeclare().await?;
             } else {
                 warn!("UndeclarePublisher: No Publisher with UUID {id}");
             }
         }
+        // Matching Listener
+        ControlMsg::DeclareMatchingListener { id, publisher_id } => {
+            // Publishers, advanced publishers and queriers all have matching listeners
+            let matching_listener_builder = if let Some(publisher) =
+                state_map.publishers.get(&publisher_id)
+            {
+                Some(publisher.matching_listener())
+            } else if let Some((publisher, _)) = state_map.advanced_publishers.get(&publisher_id) {
+                Some(publisher.matching_listener())
+            } else {
+                state_map
+                    .queriers
+                    .get(&publisher_id)
+                    .map(|querier| querier.matching_listener())
+            };
+            if let Some(matching_listener_builder) = matching_listener_builder {
+                let ch_tx = state_map.websocket_tx.clone();
+                let matching_listener = matching_listener_builder
+                    .callback(move |matching_status| {
+                        let remote_api_msg = RemoteAPIMsg::Data(DataMsg::MatchingStatus {
+                            id,
+                            matching: matching_status.matching(),
+                        });
+                        if let Err(e) = ch_tx.send(remote_api_msg) {
+                            error!("Forward Matching Status Channel error: {e}");
+                        };
+                    })
+                    .await?;
+                state_map
+                    .matching_listeners
+                    .insert(id, (matching_listener, publisher_id));
+            } else {
+                warn!("DeclareMatchingListener: No Publisher or Querier with UUID {publisher_id}");
+            }
+        }
+        ControlMsg::UndeclareMatchingListener(id) => {
+            if let Some((matching_listener, _)) = state_map.matching_listeners.remove(&id) {
+                matching_listener.undeclare().await?;
+            } else {
+                warn!("UndeclareMatchingListener: No Matching Listener with UUID {id}");
+            }
+        }
+        // Advanced Publisher
+        ControlMsg::DeclareAdvancedPublisher {
+            key_expr,
+            encoding,
+            congestion_control,
+            priority,
+            reliability,
+            express,
+            cache,
+            sample_miss_detection,
+            publisher_detection,
+            id: uuid,
+        } => {
+            let priority = priority.map(Priority::try_from).transpose()?;
+            let encoding = encoding.map(Encoding::from);
+            let mut publisher_builder = state_map.session.declare_publisher(key_expr);
+            add_if_some!(encoding, publisher_builder);
+            add_if_some!(congestion_control, publisher_builder);
+            add_if_some!(priority, publisher_builder);
+            add_if_some!(express, publisher_builder);
+            add_if_some!(reliability, publisher_builder);
+
+            let mut advanced_builder = publisher_builder.advanced();
+            if let Some(cache) = cache {
+                let mut cache_config = CacheConfig::default();
+                if let Some(max_samples) = cache.max_samples {
+                    cache_config = cache_config.max_samples(max_samples);
+                }
+                advanced_builder = advanced_builder.cache(cache_config);
+            }
+            if let Some(sample_miss_detection) = sample_miss_detection {
+                let mut miss_detection_config = MissDetectionConfig::default();
+                if let Some(heartbeat) = sample_miss_detection.heartbeat {
+                    miss_detection_config =
+                        miss_detection_config.heartbeat(Duration::from_millis(heartbeat));
+                }
+                advanced_builder = advanced_builder.sample_miss_detection(miss_detection_config);
+            }
+            if publisher_detection.unwrap_or(false) {
+                advanced_builder = advanced_builder.publisher_detection();
+            }
+
+            let publisher = advanced_builder.await?;
+            state_map
+                .advanced_publishers
+                .insert(uuid, (publisher, reliability.unwrap_or_default()));
+        }
+        ControlMsg::UndeclareAdvancedPublisher(id) => {
+            if let Some((publisher, _)) = state_map.advanced_publishers.remove(&id) {
+                state_map
+                    .matching_listeners
+                    .retain(|_, (_, publisher_id)| *publisher_id != id);
+                publisher.undeclare().await?;
+            } else {
+                warn!("UndeclareAdvancedPublisher: No Advanced Publisher with UUID {id}");
+            }
+        }
+        // Advanced Subscriber
+        ControlMsg::DeclareAdvancedSubscriber {
+            key_expr: owned_key_expr,
+            handler,
+            id: subscriber_uuid,
+            history,
+            recovery,
+            subscriber_detection,
+        } => {
+            let key_expr = KeyExpr::new(owned_key_expr.clone())?;
+            let ch_tx = state_map.websocket_tx.clone();
+
+            let mut subscriber_builder = state_map.session.declare_subscriber(key_expr).advanced();
+            if let Some(history) = history {
+                let mut history_config = HistoryConfig::default();
+                if history.detect_late_publishers.unwrap_or(false) {
+                    history_config = history_config.detect_late_publishers();
+                }
+                if let Some(max_samples) = history.max_samples {
+                    history_config = history_config.max_samples(max_samples);
+                }
+                if let Some(max_age) = history.max_age {
+                    let max_age = Duration::from_millis(max_age).as_secs_f64();
+                    history_config = history_config.max_age(max_age);
+                }
+                subscriber_builder = subscriber_builder.history(history_config);
+            }
+            if let Some(recovery) = recovery {
+                let recovery_config = match recovery.periodic_queries {
+                    Some(period) => {
+                        RecoveryConfig::default().periodic_queries(Duration::from_millis(period))
+                    }
+                    None => RecoveryConfig::default().heartbeat(),
+                };
+                subscriber_builder = subscriber_builder.recovery(recovery_config);
+            }
+            if subscriber_detection.unwrap_or(false) {
+                subscriber_builder = subscriber_builder.subscriber_detection();
+            }
+
+            let miss_ch_tx = ch_tx.clone();
+            let on_miss = move |miss: Miss| {
+                let remote_api_message = RemoteAPIMsg::Data(DataMsg::SampleMiss {
+                    id: subscriber_uuid,
+                    source: EntityGlobalIdWS::from(&miss.source()),
+                    nb: miss.nb(),
+                });
+                if let Err(e) = miss_ch_tx.send(remote_api_message) {
+                    error!("Forward Sample Miss Channel error: {e}");
+                };
+            };
+
+            let sender = SampleSender::new(
+                SampleForwarder::new(None, None),
+                ch_tx,
+                subscriber_uuid,
+                None,
+                None,
+                None,
+            )?;
+            let (task, miss_listener) = match handler {
+                HandlerChannel::Fifo(size) => {
+                    let subscriber = subscriber_builder.with(FifoChannel::new(size)).await?;
+                    let miss_listener = subscriber.sample_miss_listener().callback(on_miss).await?;
+                    (SubscriberTask::spawn(subscriber, sender), miss_listener)
+                }
+                HandlerChannel::Ring(size) => {
+                    let subscriber = subscriber_builder.with(RingChannel::new(size)).await?;
+                    let miss_listener = subscriber.sample_miss_listener().callback(on_miss).await?;
+                    (SubscriberTask::spawn(subscriber, sender), miss_listener)
+                }
+            };
+
+            state_map.advanced_subscribers.insert(
+                subscriber_uuid,
+                (task, miss_listener, owned_key_expr, handler),
+            );
+            return Ok(Some(ControlMsg::Subscriber(subscriber_uuid)));
+        }
+        ControlMsg::UndeclareAdvancedSubscriber(uuid) => {
+            if let Some((task, _, _, _)) = state_map.advanced_subscribers.remove(&uuid) {
+                task.stop();
+            } else {
+                warn!("UndeclareAdvancedSubscriber: No Advanced Subscriber with UUID {uuid}");
+            }
+        }
         // Queryable
         ControlMsg::DeclareQueryable {
             key_expr,
@@ -327,13 +525,111 @@ pub(crate) async fn handle_control_message(
                 queryable.undeclare().await?;
             };
         }
+        // Querier
+        ControlMsg::DeclareQuerier {
+            key_expr,
+            id,
+            consolidation,
+            congestion_control,
+            priority,
+            express,
+            timeout,
+        } => {
+            let priority = priority.map(Priority::try_from).transpose()?;
+            let timeout = timeout.map(Duration::from_millis);
+            let mut querier_builder = state_map.session.declare_querier(key_expr);
+            add_if_some!(consolidation, querier_builder);
+            add_if_some!(congestion_control, querier_builder);
+            add_if_some!(priority, querier_builder);
+            add_if_some!(express, querier_builder);
+            add_if_some!(timeout, querier_builder);
+
+            let querier = querier_builder.await?;
+            state_map.queriers.insert(id, querier);
+        }
+        ControlMsg::UndeclareQuerier(id) => {
+            if let Some(querier) = state_map.queriers.remove(&id) {
+                // Matching listeners do not outlive their querier
+                state_map
+                    .matching_listeners
+                    .retain(|_, (_, querier_id)| *querier_id != id);
+                querier.undeclare().await?;
+            } else {
+                warn!("UndeclareQuerier: No Querier with UUID {id}");
+            }
+        }
+        ControlMsg::QuerierGet {
+            querier_id,
+            id,
+            parameters,
+            handler,
+            encoding,
+            payload,
+            attachment,
+        } => {
+            let Some(querier) = state_map.queriers.get(&querier_id) else {
+                warn!("QuerierGet: No Querier with UUID {querier_id}");
+                return Ok(None);
+            };
+            let encoding = encoding.map(Encoding::from);
+            let mut get_builder = querier.get();
+            add_if_some!(parameters, get_builder);
+            add_if_some!(encoding, get_builder);
+            if let Some(payload_b64) = payload {
+                match payload_b64.b64_to_bytes() {
+                    Ok(payload) => get_builder = get_builder.payload(payload),
+                    Err(err) => warn!("Could not decode B64 encoded bytes {err}"),
+                }
+            }
+            if let Some(attachment_b64) = attachment {
+                match attachment_b64.b64_to_bytes() {
+                    Ok(attachment) => get_builder = get_builder.attachment(attachment),
+                    Err(err) => warn!("Could not decode B64 encoded bytes {err}"),
+                }
+            }
+
+            // The query is sent under the lock, its replies are forwarded from a task
+            let websocket_tx = state_map.websocket_tx.clone();
+            let send_reply = move |reply: Option<Reply>| {
+                let remote_api_msg = match reply {
+                    Some(reply) => {
+                        RemoteAPIMsg::Data(DataMsg::GetReply(ReplyWS::from((reply, id))))
+                    }
+                    None => RemoteAPIMsg::Control(ControlMsg::GetFinished { id }),
+                };
+                if let Err(err) = websocket_tx.send(remote_api_msg) {
+                    tracing::error!("{}", err);
+                }
+            };
+            match handler {
+                HandlerChannel::Fifo(size) => {
+                    let receiver = get_builder.with(FifoChannel::new(size)).await?;
+                    spawn_future(async move {
+                        while let Ok(reply) = receiver.recv_async().await {
+                            send_reply(Some(reply));
+                        }
+                        send_reply(None);
+                    });
+                }
+                HandlerChannel::Ring(size) => {
+                    let receiver = get_builder.with(RingChannel::new(size)).await?;
+                    spawn_future(async move {
+                        while let Ok(reply) = receiver.recv_async().await {
+                            send_reply(Some(reply));
+                        }
+                        send_reply(None);
+                    });
+                }
+            };
+        }
         ControlMsg::Liveliness(liveliness_msg) => {
             return handle_liveliness(liveliness_msg, state_map).await;
         }
 
         msg @ (ControlMsg::GetFinished { id: _ }
         | ControlMsg::Session(_)
-        | ControlMsg::Subscriber(_)) => {
+        | ControlMsg::Subscriber(_)
+        | ControlMsg::Error(_)) => {
             // make server recieving these types unrepresentable
             error!("Backend should not recieve this message Type: {msg:?}");
         }
@@ -349,11 +645,11 @@ async fn handle_liveliness(
     let liveliness = state_map.session.liveliness();
     match liveliness_msg {
         LivelinessMsg::DeclareToken { key_expr, id } => {
-            let token = liveliness.declare_token(key_expr).await?;
-            state_map.liveliness_tokens.insert(id, token);
+            let token = liveliness.declare_token(key_expr.clone()).await?;
+            state_map.liveliness_tokens.insert(id, (token, key_expr));
         }
         LivelinessMsg::UndeclareToken(uuid) => {
-            if let Some(token) = state_map.liveliness_tokens.remove(&uuid) {
+            if let Some((token, _)) = state_map.liveliness_tokens.remove(&uuid) {
                 token.undeclare().await?;
             }
         }
@@ -14,12 +14,11 @@
 use std::{error::Error, net::SocketAddr};
 
 use tracing::{error, warn};
-use zenoh::query::Query;
+use uhlc::Timestamp;
+use zenoh::{bytes::Encoding, query::Query, sample::SourceInfo};
+use zenoh_remote_api_protocol::{B64String, DataMsg, QueryReplyVariant, QueryableMsg};
 
-use crate::{
-    interface::{DataMsg, QueryReplyVariant, QueryableMsg},
-    StateMap,
-};
+use crate::StateMap;
 
 pub async fn handle_data_message(
     data_msg: DataMsg,
@@ -43,30 +42,44 @@ pub async fn handle_data_message(
             payload,
             attachment,
             encoding,
+            timestamp,
+            source_info,
         } => {
-            if let Some(publisher) = state_map.publishers.get(&id) {
-                let mut put_builder = match payload.b64_to_bytes() {
-                    Ok(payload) => publisher.put(payload),
-                    Err(err) => {
-                        warn!("DataMsg::PublisherPut : Could not decode B64 encoded bytes {err}");
-                        return Err(Box::new(err));
-                    }
-                };
+            let payload = match payload.b64_to_bytes() {
+                Ok(payload) => payload,
+                Err(err) => {
+                    warn!("DataMsg::PublisherPut : Could not decode B64 encoded bytes {err}");
+                    return Err(Box::new(err));
+                }
+            };
+            let attachment = match attachment.map(B64String::b64_to_bytes).transpose() {
+                Ok(attachment) => attachment,
+                Err(err) => {
+                    warn!("DataMsg::PublisherPut : Could not decode B64 encoded bytes {err}");
+                    return Err(Box::new(err));
+                }
+            };
+            let timestamp = timestamp.map(Timestamp::try_from).transpose()?;
+            let source_info = source_info.map(SourceInfo::try_from).transpose()?;
+            let encoding = encoding.map(Encoding::from);
 
-                if let Some(attachment_b64) = attachment {
-                    match attachment_b64.b64_to_bytes() {
-                        Ok(payload) => put_builder = put_builder.attachment(payload),
-                        Err(err) => {
-                            warn!(
-                                "DataMsg::PublisherPut : Could not decode B64 encoded bytes {err}"
-                            );
-                            return Err(Box::new(err));
-                        }
-                    }
+            if let Some(publisher) = state_map.publishers.get(&id) {
+                let mut put_builder = publisher.put(payload);
+                add_if_some!(attachment, put_builder);
+                add_if_some!(encoding, put_builder);
+                add_if_some!(timestamp, put_builder);
+                add_if_some!(source_info, put_builder);
+                if let Err(err) = put_builder.await {
+                    error!("PublisherPut {id}, {err}");
                 }
-                if let Some(encoding) = encoding {
-                    put_builder = put_builder.encoding(encoding);
+            } else if let Some((publisher, _)) = state_map.advanced_publishers.get(&id) {
+                if source_info.is_some() {
+                    warn!("PublisherPut {id}, Advanced Publishers manage their own source info");
                 }
+                let mut put_builder = publisher.put(payload);
+                add_if_some!(attachment, put_builder);
+                add_if_some!(encoding, put_builder);
+                add_if_some!(timestamp, put_builder);
                 if let Err(err) = put_builder.await {
                     error!("PublisherPut {id}, {err}");
                 }
@@ -74,10 +87,48 @@ pub async fn handle_data_message(
                 warn!("Publisher {id}, does not exist in State");
             }
         }
+        DataMsg::PublisherDelete {
+            id,
+            attachment,
+            timestamp,
+            source_info,
+        } => {
+            let attachment = match attachment.map(B64String::b64_to_bytes).transpose() {
+                Ok(attachment) => attachment,
+                Err(err) => {
+                    warn!("DataMsg::PublisherDelete : Could not decode B64 encoded bytes {err}");
+                    return Err(Box::new(err));
+                }
+            };
+            let timestamp = timestamp.map(Timestamp::try_from).transpose()?;
+            let source_info = source_info.map(SourceInfo::try_from).transpose()?;
+
+            if let Some(publisher) = state_map.publishers.get(&id) {
+                let mut delete_builder = publisher.delete();
+                add_if_some!(attachment, delete_builder);
+                add_if_some!(timestamp, delete_builder);
+                add_if_some!(source_info, delete_builder);
+                if let Err(err) = delete_builder.await {
+                    error!("PublisherDelete {id}, {err}");
+                }
+            } else if let Some((publisher, _)) = state_map.advanced_publishers.get(&id) {
+                if source_info.is_some() {
+                    warn!("PublisherDelete {id}, Advanced Publishers manage their own source info");
+                }
+                let mut delete_builder = publisher.delete();
+                add_if_some!(attachment, delete_builder);
+                add_if_some!(timestamp, delete_builder);
+                if let Err(err) = delete_builder.await {
+                    error!("PublisherDelete {id}, {err}");
+                }
+            } else {
+                warn!("Publisher {id}, does not exist in State");
+            }
+        }
         DataMsg::Queryable(queryable_msg) => match queryable_msg {
             QueryableMsg::Reply { reply } => {
                 let query: Option<Query> = match state_map.unanswered_queries.write() {
-                    Ok(mut wr) => wr.remove(&reply.query_uuid),
+                    Ok(mut wr) => wr.remove(&reply.query_uuid()),
                     Err(err) => {
                         tracing::error!("unanswered Queries RwLock Poisened {err}");
                         return Ok(());
@@ -85,7 +136,7 @@ pub async fn handle_data_message(
                 };
 
                 if let Some(q) = query {
-                    match reply.result {
+                    match reply.into_result() {
                         QueryReplyVariant::Reply { key_expr, payload } => {
                             match payload.b64_to_bytes() {
                                 Ok(payload) => q.reply(key_expr, payload).await?,
@@ -107,7 +158,7 @@ pub async fn handle_data_message(
                         }
                     }
                 } else {
-                    tracing::error!("Query id not found in map {}", reply.query_uuid);
+                    tracing::error!("Query id not found in map {}", reply.query_uuid());
                 };
             }
             QueryableMsg::Query {
@@ -19,25 +19,30 @@
 //! [Click here for Zenoh's documentation](../zenoh/index.html)
 
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     fs::File,
     future::Future,
     io::{self, BufReader, ErrorKind},
     net::SocketAddr,
     path::Path,
-    sync::Arc,
+    sync::{Arc, OnceLock, Weak},
+    time::{Duration, Instant},
 };
 
 use flume::Sender;
-use futures::{future, pin_mut, StreamExt, TryStreamExt};
-use interface::RemoteAPIMsg;
+use futures::{
+    future::{self, Either},
+    pin_mut, SinkExt, StreamExt, TryStreamExt,
+};
 use rustls_pemfile::{certs, private_key};
 use serde::Serialize;
 use tokio::{
     net::{TcpListener, TcpStream},
+    runtime::RuntimeFlavor,
     select,
-    sync::RwLock,
+    sync::{watch, Notify, RwLock},
     task::JoinHandle,
+    time::{sleep, timeout},
 };
 use tokio_rustls::{
     rustls::{
@@ -47,7 +52,11 @@ use tokio_rustls::{
     server::TlsStream,
     TlsAcceptor,
 };
-use tokio_tungstenite::tungstenite::protocol::Message;
+use tokio_tungstenite::tungstenite::{
+    handshake::server::{ErrorResponse, Request, Response},
+    http::{header::SEC_WEBSOCKET_EXTENSIONS, StatusCode},
+    protocol::{frame::coding::CloseCode, CloseFrame, Message},
+};
 use tracing::{debug, error};
 use uuid::Uuid;
 use zenoh::{
@@ -61,21 +70,56 @@ use zenoh::{
         keyexpr, OwnedKeyExpr,
     },
     liveliness::LivelinessToken,
+    matching::MatchingListener,
     pubsub::Publisher,
-    query::{Query, Queryable},
+    qos::Reliability,
+    query::{Querier, Query, Queryable},
     Session,
 };
+use zenoh_ext::{AdvancedPublisher, SampleMissListener};
 use zenoh_plugin_trait::{plugin_long_version, plugin_version, Plugin, PluginControl};
+use zenoh_remote_api_protocol::{ControlMsg, HandlerChannel, LivelinessMsg, RemoteAPIMsg};
 use zenoh_result::{bail, zerror, ZResult};
 
+///
+/// Macro to replace the pattern of adding to builders if a field exists
+/// i.e. add_if_some!(consolidation, get_builder);
+/// expands to
+/// if Some(consolidation) = consolidation{
+///     get_builder = get_builder.consolidation(consolidation);
+/// }
+macro_rules! add_if_some {
+    ($x:ident, $y:ident) => {
+        if let Some($x) = $x {
+            $y = $y.$x($x);
+        }
+    };
+}
+
+mod acl;
+mod compress;
 mod config;
 pub use config::Config;
 
+mod deflate;
+mod filter;
+mod forward;
 mod handle_control_message;
 mod handle_data_message;
-mod interface;
+mod logging;
+mod metrics;
+#[cfg(test)]
+mod tests;
+mod throttle;
+mod transcode;
 use crate::{
-    handle_control_message::handle_control_message, handle_data_message::handle_data_message,
+    acl::AccessControl,
+    config::Compression,
+    deflate::{DeflateParams, Deflater, InflateStream},
+    forward::SubscriberTask,
+    handle_control_message::handle_control_message,
+    handle_data_message::handle_data_message,
+    metrics::{run_metrics_server, ClientStats, ClientStatsSnapshot, Metrics, PluginStats},
 };
 
 kedefine!(
@@ -84,8 +128,58 @@ kedefine!(
 
     // Admin prefix of this bridge
     pub ke_admin_prefix: "@/${zenoh_id:*}/remote-plugin/",
+
+    // Admin space key expressions of a client and of one of its entities
+    pub ke_admin_client: "@/${zenoh_id:*}/remote-plugin/clients/${client_id:*}",
+    pub ke_admin_client_stats: "@/${zenoh_id:*}/remote-plugin/stats/${client_id:*}",
+    pub ke_admin_client_entity: "@/${zenoh_id:*}/remote-plugin/clients/${client_id:*}/${entity_type:*}/${entity_id:*}",
 );
 
+// Entity types of a client, named as in the admin space, the stats and the metrics
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EntityType {
+    Publishers,
+    Subscribers,
+    Queryables,
+    Queriers,
+    MatchingListeners,
+    AdvancedPublishers,
+    AdvancedSubscribers,
+    LivelinessTokens,
+    LivelinessSubscribers,
+    Queries,
+}
+
+impl EntityType {
+    const ALL: [EntityType; 10] = [
+        EntityType::Publishers,
+        EntityType::Subscribers,
+        EntityType::Queryables,
+        EntityType::Queriers,
+        EntityType::MatchingListeners,
+        EntityType::AdvancedPublishers,
+        EntityType::AdvancedSubscribers,
+        EntityType::LivelinessTokens,
+        EntityType::LivelinessSubscribers,
+        EntityType::Queries,
+    ];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            EntityType::Publishers => "publishers",
+            EntityType::Subscribers => "subscribers",
+            EntityType::Queryables => "queryables",
+            EntityType::Queriers => "queriers",
+            EntityType::MatchingListeners => "matching_listeners",
+            EntityType::AdvancedPublishers => "advanced_publishers",
+            EntityType::AdvancedSubscribers => "advanced_subscribers",
+            EntityType::LivelinessTokens => "liveliness_tokens",
+            EntityType::LivelinessSubscribers => "liveliness_subscribers",
+            EntityType::Queries => "queries",
+        }
+    }
+}
+const DRAIN_POLL_PERIOD: Duration = Duration::from_millis(50);
 const WORKER_THREAD_NUM: usize = 2;
 const MAX_BLOCK_THREAD_NUM: usize = 50;
 const GIT_VERSION: &str = git_version::git_version!(prefix = "v", cargo_prefix = "v");
@@ -102,11 +196,12 @@ lazy_static::lazy_static! {
     static ref KE_ANY_N_SEGMENT: &'static keyexpr =  unsafe { keyexpr::from_str_unchecked("**") };
 }
 
-// An reference used in admin space to point to a struct (DdsEntity or Route) stored in another map
+// A reference used in admin space to point to a static node of the admin tree
 #[derive(Debug)]
 enum AdminRef {
     Config,
     Version,
+    Stats,
 }
 
 #[inline(always)]
@@ -164,12 +259,6 @@ impl Plugin for RemoteApiPlugin {
         name: &str,
         runtime: &Self::StartArgs,
     ) -> ZResult<zenoh::internal::plugins::RunningPlugin> {
-        // Try to initiate login.
-        // Required in case of dynamic lib, otherwise no logs.
-        // But cannot be done twice in case of static link.
-        zenoh_util::try_init_log_from_env();
-        tracing::info!("Starting {name}");
-
         let runtime_conf = runtime.config().lock();
 
         let plugin_conf = runtime_conf
@@ -178,89 +267,387 @@ impl Plugin for RemoteApiPlugin {
 
         let conf: Config = serde_json::from_value(plugin_conf.clone())
             .map_err(|e| zerror!("Plugin `{}` configuration error: {}", name, e))?;
+        drop(runtime_conf);
 
-        let wss_config: Option<(Vec<CertificateDer<'_>>, PrivateKeyDer<'_>)> =
-            match conf.secure_websocket.clone() {
-                Some(wss_config) => {
-                    tracing::info!("Loading certs from : {} ...", wss_config.certificate_path);
-                    let certs = load_certs(Path::new(&wss_config.certificate_path))
-                        .map_err(|err| zerror!("Could not Load WSS Cert `{}`", err))?;
-                    tracing::info!(
-                        "Loading Private Key from : {} ...",
-                        wss_config.private_key_path
-                    );
-                    let key = load_key(Path::new(&wss_config.private_key_path))
-                        .map_err(|err| zerror!("Could not Load WSS Private Key `{}`", err))?;
-                    Some((certs, key))
-                }
-                None => None,
-            };
+        // Try to initiate login, with a log level that configuration changes can update
+        logging::try_init_log(conf.log_level.as_deref());
+        tracing::info!("Starting {name}");
+
+        let live_config = LiveConfig::load(conf)
+            .map_err(|e| zerror!("Plugin `{}` configuration error: {}", name, e))?;
+        let (config_tx, config_rx) = watch::channel(live_config);
+        let config_tx = Arc::new(config_tx);
+        let shutdown = Arc::new(Notify::new());
+        let (stopped_tx, stopped_rx) = flume::bounded(1);
+        let config_notifications = runtime.config().subscribe();
 
         let weak_runtime = Runtime::downgrade(runtime);
         if let Some(runtime) = weak_runtime.upgrade() {
-            spawn_runtime(run(runtime, conf, wss_config));
+            let remote_api_runtime = RemoteAPIRuntime::new(runtime, config_rx, shutdown.clone());
+            spawn_runtime(async move {
+                if let Err(err) = remote_api_runtime.run().await {
+                    error!("{err}");
+                }
+                let _ = stopped_tx.send(());
+            });
 
-            Ok(Box::new(RunningPlugin(RemoteAPIPlugin)))
+            // Changes validated by `config_checker` are applied once zenohd notifies them
+            let plugin_name = name.to_string();
+            spawn_runtime(apply_config_changes(
+                config_notifications,
+                format!("plugins/{name}"),
+                move || {
+                    let runtime = weak_runtime.upgrade()?;
+                    let runtime_conf = runtime.config().lock();
+                    runtime_conf.plugin(&plugin_name).cloned()
+                },
+                Arc::downgrade(&config_tx),
+            ));
+
+            Ok(Box::new(RunningPlugin {
+                config_tx,
+                shutdown,
+                stopped_rx,
+            }))
         } else {
             bail!("Cannot Get Zenoh Instance of Runtime !")
         }
     }
 }
 
+// Reload the configuration of the plugin each time a change of `plugin_path` is notified,
+// until the plugin is stopped
+async fn apply_config_changes(
+    notifications: flume::Receiver<impl AsRef<str>>,
+    plugin_path: String,
+    plugin_config: impl Fn() -> Option<serde_json::Value>,
+    config_tx: Weak<watch::Sender<LiveConfig>>,
+) {
+    while let Ok(path) = notifications.recv_async().await {
+        // Changes of the plugin configuration, or of one of its parents
+        let path = path.as_ref().trim_matches('/');
+        if !path
+            .split('/')
+            .zip(plugin_path.split('/'))
+            .all(|(segment, plugin_segment)| segment == plugin_segment)
+        {
+            continue;
+        }
+        let Some(config_tx) = config_tx.upgrade() else {
+            break;
+        };
+        let Some(plugin_config) = plugin_config() else {
+            continue;
+        };
+
+        match serde_json::from_value(plugin_config)
+            .map_err(|e| zerror!("Configuration error: {}", e).into())
+            .and_then(LiveConfig::load)
+        {
+            Ok(live_config) => {
+                tracing::info!("Applying the configuration change of `{path}`");
+                let log_level = live_config.config.log_level.clone();
+                if config_tx.borrow().config.log_level != log_level {
+                    logging::set_log_level(log_level.as_deref());
+                }
+                config_tx.send_replace(live_config);
+            }
+            Err(err) => error!("Unable to apply the configuration change of `{path}`: {err}"),
+        }
+    }
+}
+
+// Configuration of the running plugin, updated at runtime by `apply_config_changes`
+#[derive(Clone)]
+struct LiveConfig {
+    config: Arc<Config>,
+    tls_acceptor: Option<TlsAcceptor>,
+    acl: Arc<AccessControl>,
+}
+
+impl LiveConfig {
+    // Validate the configuration and load the TLS material it refers to
+    fn load(config: Config) -> ZResult<Self> {
+        if let Some(prefix) = config
+            .client_liveliness
+            .as_ref()
+            .and_then(|client_liveliness| client_liveliness.prefix.as_ref())
+        {
+            keyexpr::new(prefix.as_str())
+                .map_err(|e| zerror!("Invalid client liveliness prefix: {}", e))?;
+        }
+        logging::log_filter(config.log_level.as_deref())?;
+
+        let tls_acceptor = match &config.secure_websocket {
+            Some(wss_config) => {
+                tracing::info!("Loading certs from : {} ...", wss_config.certificate_path);
+                let certs = load_certs(Path::new(&wss_config.certificate_path))
+                    .map_err(|err| zerror!("Could not Load WSS Cert `{}`", err))?;
+                tracing::info!(
+                    "Loading Private Key from : {} ...",
+                    wss_config.private_key_path
+                );
+                let key = load_key(Path::new(&wss_config.private_key_path))
+                    .map_err(|err| zerror!("Could not Load WSS Private Key `{}`", err))?;
+                Some(tls_acceptor(certs, key)?)
+            }
+            None => None,
+        };
+
+        Ok(LiveConfig {
+            acl: Arc::new(LiveConfig::access_control(&config)?),
+            config: Arc::new(config),
+            tls_acceptor,
+        })
+    }
+
+    fn access_control(config: &Config) -> ZResult<AccessControl> {
+        match &config.acl {
+            Some(acl) => AccessControl::new(acl),
+            None => Ok(AccessControl::default()),
+        }
+    }
+}
+
+fn tls_acceptor(
+    certs: Vec<CertificateDer<'static>>,
+    key: PrivateKeyDer<'static>,
+) -> ZResult<TlsAcceptor> {
+    let config = rustls::ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|err| {
+            zerror!(
+                "Could not build TLS Configuration from Certificate/Key Combo: {}",
+                err
+            )
+        })?;
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// Serve the remote API on `runtime`, outside of zenohd
 pub async fn run(
     runtime: Runtime,
     config: Config,
     opt_certs: Option<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>)>,
-) {
-    let hm: HashMap<SocketAddr, RemoteState> = HashMap::new();
-    let state_map = Arc::new(RwLock::new(hm));
-
-    // Return WebServer And State
-    let remote_api_runtime = RemoteAPIRuntime {
-        config: Arc::new(config),
-        wss_certs: opt_certs,
-        zenoh_runtime: runtime,
-        state_map,
+) -> ZResult<()> {
+    run_until(runtime, config, opt_certs, future::pending()).await
+}
+
+/// Like [`run`], until `shutdown` completes, then drains the connected clients
+pub async fn run_until(
+    runtime: Runtime,
+    config: Config,
+    opt_certs: Option<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>)>,
+    shutdown: impl Future<Output = ()>,
+) -> ZResult<()> {
+    // Logs are initialised by the caller
+    logging::set_log_level(config.log_level.as_deref());
+    let live_config = match opt_certs {
+        Some((certs, key)) => LiveConfig {
+            acl: Arc::new(LiveConfig::access_control(&config)?),
+            config: Arc::new(config),
+            tls_acceptor: Some(tls_acceptor(certs, key)?),
+        },
+        // Otherwise the certificate and key of `secure_websocket` are loaded, if any
+        None => LiveConfig::load(config)?,
     };
 
-    remote_api_runtime.run().await;
+    // Without a running plugin, the configuration never changes
+    let (_config_tx, config_rx) = watch::channel(live_config);
+
+    let shutdown_notify = Arc::new(Notify::new());
+    let running = RemoteAPIRuntime::new(runtime, config_rx, shutdown_notify.clone()).run();
+    pin_mut!(running);
+    pin_mut!(shutdown);
+    match future::select(running, shutdown).await {
+        Either::Left((result, _)) => result,
+        Either::Right((_, running)) => {
+            shutdown_notify.notify_one();
+            running.await
+        }
+    }
 }
 
 struct RemoteAPIRuntime {
-    config: Arc<Config>,
-    wss_certs: Option<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>)>,
+    live_config: watch::Receiver<LiveConfig>,
     zenoh_runtime: Runtime,
     state_map: StateMap,
+    metrics: Arc<Metrics>,
+    shutdown: Arc<Notify>,
 }
 
 impl RemoteAPIRuntime {
-    async fn run(self) {
+    fn new(
+        zenoh_runtime: Runtime,
+        live_config: watch::Receiver<LiveConfig>,
+        shutdown: Arc<Notify>,
+    ) -> Self {
+        let hm: HashMap<SocketAddr, RemoteState> = HashMap::new();
+
+        RemoteAPIRuntime {
+            live_config,
+            zenoh_runtime,
+            state_map: Arc::new(RwLock::new(hm)),
+            metrics: Arc::new(Metrics::new()),
+            shutdown,
+        }
+    }
+
+    async fn run(self) -> ZResult<()> {
         let run_websocket_server = run_websocket_server(
-            &self.config.websocket_port,
             self.zenoh_runtime.clone(),
             self.state_map.clone(),
-            self.wss_certs,
+            self.metrics.clone(),
+            self.live_config.clone(),
+        );
+
+        let run_admin_space_queryable = run_admin_space_queryable(
+            self.zenoh_runtime.clone(),
+            self.state_map.clone(),
+            self.live_config.clone(),
+            self.metrics.clone(),
         );
 
-        let config = (*self.config).clone();
+        // The metrics endpoint is optional, and must not stop the plugin if it fails
+        let run_metrics_server = run_reconfigurable(
+            self.live_config.clone(),
+            |config| config.metrics.clone(),
+            |metrics_config| {
+                let metrics = self.metrics.clone();
+                let state_map = self.state_map.clone();
+                async move {
+                    if let Some(metrics_config) = metrics_config {
+                        run_metrics_server(&metrics_config.port, metrics, state_map).await;
+                    }
+                }
+            },
+        );
 
-        let run_admin_space_queryable =
-            run_admin_space_queryable(self.zenoh_runtime.clone(), self.state_map.clone(), config);
+        // Control actions from the admin space are opt-in
+        let run_admin_space_control = run_reconfigurable(
+            self.live_config.clone(),
+            |config| config.admin_space_control,
+            |admin_space_control| {
+                let zenoh_runtime = self.zenoh_runtime.clone();
+                let state_map = self.state_map.clone();
+                async move {
+                    if admin_space_control {
+                        run_admin_space_control(zenoh_runtime, state_map).await;
+                    }
+                }
+            },
+        );
 
-        select!(
-            _ = run_websocket_server => {},
-            _ = run_admin_space_queryable => {},
+        let result = select!(
+            result = run_websocket_server => result,
+            _ = run_admin_space_queryable => Ok(()),
+            _ = run_metrics_server => Ok(()),
+            _ = run_admin_space_control => Ok(()),
+            _ = self.shutdown.notified() => {
+                tracing::info!("Remote API plugin is shutting down");
+                Ok(())
+            },
         );
+
+        // New connections are not accepted anymore
+        self.drain().await;
+        result
     }
+
+    // Close every client connection, waiting up to the drain timeout for the clients to be cleaned up
+    async fn drain(&self) {
+        let drain_timeout =
+            Duration::from_millis(self.live_config.borrow().config.drain_timeout_ms);
+
+        for remote_state in self.state_map.read().await.values() {
+            remote_state.disconnect(CloseCode::Away, "Remote API plugin is shutting down");
+        }
+
+        let drained = timeout(drain_timeout, async {
+            while !self.state_map.read().await.is_empty() {
+                sleep(DRAIN_POLL_PERIOD).await;
+            }
+        })
+        .await;
+
+        if drained.is_err() {
+            let remaining: Vec<RemoteState> = self
+                .state_map
+                .write()
+                .await
+                .drain()
+                .map(|(_, remote_state)| remote_state)
+                .collect();
+            tracing::warn!(
+                "{} clients not closed after {drain_timeout:?}, cleaning them up",
+                remaining.len()
+            );
+            for remote_state in remaining {
+                remote_state.cleanup().await;
+            }
+        }
+    }
+}
+
+// Run a task configured by a section of the configuration,
+// and restart it whenever that section changes
+async fn run_reconfigurable<T, S, F, Fut>(
+    mut live_config: watch::Receiver<LiveConfig>,
+    section: S,
+    task: F,
+) where
+    T: Clone + PartialEq,
+    S: Fn(&Config) -> T,
+    F: Fn(T) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let mut current = section(live_config.borrow_and_update().config.as_ref());
+    loop {
+        let new = {
+            let running = task(current.clone());
+            let changed = async {
+                loop {
+                    if live_config.changed().await.is_err() {
+                        // The plugin is stopped, the configuration won't change anymore
+                        future::pending::<()>().await;
+                    }
+                    let new = section(live_config.borrow_and_update().config.as_ref());
+                    if new != current {
+                        return new;
+                    }
+                }
+            };
+            pin_mut!(running, changed);
+            match future::select(running, changed).await {
+                // A task that ended on its own (e.g. unable to bind) waits for a new configuration
+                Either::Left((_, changed)) => changed.await,
+                Either::Right((new, _)) => new,
+            }
+        };
+        current = new;
+    }
+}
+
+// Connection details of a client, captured during the WebSocket handshake
+#[derive(Debug, Clone, Default, Serialize)]
+struct ClientIdentity {
+    secure: bool,
+    origin: Option<String>,
+    user_agent: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 struct AdminSpaceClient {
     uuid: String,
     remote_address: SocketAddr,
-    publishers: Vec<String>,
-    subscribers: Vec<String>,
-    queryables: Vec<String>,
+    // Milliseconds since UNIX epoch
+    connected_at: u64,
+    #[serde(flatten)]
+    identity: ClientIdentity,
+    // Declared entities and pending queries per entity type
+    #[serde(flatten)]
+    entities: BTreeMap<&'static str, Vec<AdminSpaceEntity>>,
 }
 
 impl From<(&SocketAddr, &RemoteState)> for AdminSpaceClient {
@@ -268,35 +655,164 @@ impl From<(&SocketAddr, &RemoteState)> for AdminSpaceClient {
         let remote_state = value.1;
         let sock_addr = value.0;
 
-        let pub_keyexprs = remote_state
-            .publishers
-            .values()
-            .map(|x| x.key_expr().to_string())
-            .collect::<Vec<String>>();
+        // Every entity type is listed, even without any entity
+        let mut entities: BTreeMap<&'static str, Vec<AdminSpaceEntity>> = EntityType::ALL
+            .into_iter()
+            .map(|entity_type| (entity_type.as_str(), Vec::new()))
+            .collect();
+        for (entity_type, _, entity) in remote_state.admin_entities() {
+            entities
+                .entry(entity_type.as_str())
+                .or_default()
+                .push(entity);
+        }
+
+        AdminSpaceClient {
+            uuid: remote_state.session_id.to_string(),
+            remote_address: *sock_addr,
+            connected_at: remote_state.stats.connected_at(),
+            identity: remote_state.identity.clone(),
+            entities,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct AdminSpaceStats {
+    #[serde(flatten)]
+    plugin: PluginStats,
+    clients: usize,
+    entities: BTreeMap<&'static str, usize>,
+}
 
-        let query_keyexprs = remote_state
-            .queryables
-            .values()
-            .map(|(_, key_expr)| key_expr.to_string())
-            .collect::<Vec<String>>();
+#[derive(Debug, Serialize)]
+struct AdminSpaceClientStats {
+    uuid: String,
+    remote_address: SocketAddr,
+    #[serde(flatten)]
+    stats: ClientStatsSnapshot,
+    queue_depth: usize,
+    entities: BTreeMap<&'static str, usize>,
+}
 
-        let sub_keyexprs = remote_state
-            .subscribers
-            .values()
-            .map(|(_, key_expr)| key_expr.to_string())
-            .collect::<Vec<String>>();
+impl From<(&SocketAddr, &RemoteState)> for AdminSpaceClientStats {
+    fn from(value: (&SocketAddr, &RemoteState)) -> Self {
+        let remote_state = value.1;
+        let sock_addr = value.0;
 
-        AdminSpaceClient {
+        AdminSpaceClientStats {
             uuid: remote_state.session_id.to_string(),
             remote_address: *sock_addr,
-            publishers: pub_keyexprs,
-            subscribers: sub_keyexprs,
-            queryables: query_keyexprs,
+            stats: remote_state.stats.stats(),
+            queue_depth: remote_state.websocket_tx.len(),
+            entities: remote_state.entity_counts().into_iter().collect(),
         }
     }
 }
 
-async fn run_admin_space_queryable(zenoh_runtime: Runtime, state_map: StateMap, config: Config) {
+#[derive(Debug, Default, Serialize)]
+struct AdminSpaceEntity {
+    uuid: String,
+    key_expr: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    publisher_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    encoding: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    congestion_control: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    priority: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reliability: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    handler: Option<HandlerChannel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parameters: Option<String>,
+}
+
+// Reply to the query with every client node intersecting it:
+// `clients/<uuid>`, `clients/<uuid>/<entity_type>/<id>` and `stats/<uuid>`
+async fn send_clients_replies(query: &Query, zenoh_id: &keyexpr, state_map: &StateMap) {
+    let query_ke: &keyexpr = query.key_expr();
+    let read_guard = state_map.read().await;
+
+    for (sock, remote_state) in read_guard.iter() {
+        let client_id = remote_state.session_id;
+
+        match keformat!(
+            ke_admin_client::formatter(),
+            zenoh_id = zenoh_id,
+            client_id = client_id
+        ) {
+            Ok(client_ke) if query_ke.intersects(&client_ke) => {
+                send_reply(
+                    AdminSpaceClient::from((sock, remote_state)),
+                    query,
+                    client_ke,
+                )
+                .await;
+            }
+            Ok(_) => {}
+            Err(err) => error!("AdminSpace: Invalid client key expression {err}"),
+        }
+
+        match keformat!(
+            ke_admin_client_stats::formatter(),
+            zenoh_id = zenoh_id,
+            client_id = client_id
+        ) {
+            Ok(client_stats_ke) if query_ke.intersects(&client_stats_ke) => {
+                send_reply(
+                    AdminSpaceClientStats::from((sock, remote_state)),
+                    query,
+                    client_stats_ke,
+                )
+                .await;
+            }
+            Ok(_) => {}
+            Err(err) => error!("AdminSpace: Invalid client key expression {err}"),
+        }
+
+        // Only list the entities of the client if the query may reach any of them
+        match keformat!(
+            ke_admin_client_entity::formatter(),
+            zenoh_id = zenoh_id,
+            client_id = client_id,
+            entity_type = "*",
+            entity_id = "*"
+        ) {
+            Ok(entities_ke) if query_ke.intersects(&entities_ke) => {}
+            Ok(_) => continue,
+            Err(err) => {
+                error!("AdminSpace: Invalid client key expression {err}");
+                continue;
+            }
+        }
+
+        for (entity_type, entity_id, entity) in remote_state.admin_entities() {
+            match keformat!(
+                ke_admin_client_entity::formatter(),
+                zenoh_id = zenoh_id,
+                client_id = client_id,
+                entity_type = entity_type.as_str(),
+                entity_id = entity_id
+            ) {
+                Ok(entity_ke) if query_ke.intersects(&entity_ke) => {
+                    send_reply(entity, query, entity_ke).await;
+                }
+                Ok(_) => {}
+                Err(err) => error!("AdminSpace: Invalid entity key expression {err}"),
+            }
+        }
+    }
+}
+
+async fn run_admin_space_queryable(
+    zenoh_runtime: Runtime,
+    state_map: StateMap,
+    live_config: watch::Receiver<LiveConfig>,
+    metrics: Arc<Metrics>,
+) {
     let session = match zenoh::session::init(zenoh_runtime.clone()).await {
         Ok(session) => session,
         Err(err) => {
@@ -305,11 +821,8 @@ async fn run_admin_space_queryable(zenoh_runtime: Runtime, state_map: StateMap,
         }
     };
 
-    let admin_prefix = keformat!(
-        ke_admin_prefix::formatter(),
-        zenoh_id = session.zid().into_keyexpr()
-    )
-    .unwrap();
+    let zenoh_id = session.zid().into_keyexpr();
+    let admin_prefix = keformat!(ke_admin_prefix::formatter(), zenoh_id = &zenoh_id).unwrap();
 
     let mut admin_space: HashMap<OwnedKeyExpr, AdminRef> = HashMap::new();
 
@@ -321,6 +834,10 @@ async fn run_admin_space_queryable(zenoh_runtime: Runtime, state_map: StateMap,
         &admin_prefix / unsafe { keyexpr::from_str_unchecked("version") },
         AdminRef::Version,
     );
+    admin_space.insert(
+        &admin_prefix / unsafe { keyexpr::from_str_unchecked("stats") },
+        AdminRef::Stats,
+    );
 
     let admin_keyexpr_expr = (&admin_prefix) / *KE_ANY_N_SEGMENT;
 
@@ -329,68 +846,90 @@ async fn run_admin_space_queryable(zenoh_runtime: Runtime, state_map: StateMap,
         .await
         .expect("Failed fo create AdminSpace Queryable");
 
-    loop {
-        match admin_queryable.recv_async().await {
-            Ok(query) => {
-                let query_ke: OwnedKeyExpr = query.key_expr().to_owned().into();
-
-                if query_ke.is_wild() {
-                    if query_ke.contains("clients") {
-                        let read_guard = state_map.read().await;
-                        let mut admin_space_clients = Vec::new();
-                        for (sock, remote_state) in read_guard.iter() {
-                            admin_space_clients.push(AdminSpaceClient::from((sock, remote_state)));
-                        }
-                        send_reply(admin_space_clients, query, query_ke).await;
-                    } else {
-                        for (ke, admin_ref) in admin_space.iter() {
-                            if query_ke.intersects(ke) {
-                                send_admin_reply(&query, ke, admin_ref, &config).await;
-                            }
-                        }
-                    }
-                } else {
-                    let own_ke: OwnedKeyExpr = query_ke.to_owned();
-                    if own_ke.contains("config") {
-                        send_admin_reply(&query, &own_ke, &AdminRef::Config, &config).await;
-                    }
-                    if own_ke.contains("client") {
-                        let mut opt_id = None;
-                        let split = own_ke.split('/');
-                        let mut next_is_id = false;
-                        for elem in split {
-                            if next_is_id {
-                                opt_id = Some(elem);
-                            } else if elem.contains("client") {
-                                next_is_id = true;
-                            }
-                        }
-                        if let Some(id) = opt_id {
-                            let read_guard = state_map.read().await;
-                            for (sock, remote_state) in read_guard.iter() {
-                                if remote_state.session_id.to_string() == id {
-                                    send_reply(
-                                        AdminSpaceClient::from((sock, remote_state)),
-                                        query,
-                                        own_ke,
-                                    )
-                                    .await;
-
-                                    break;
-                                }
-                            }
-                        }
+    while let Ok(query) = admin_queryable.recv_async().await {
+        let config = live_config.borrow().config.clone();
+        for (ke, admin_ref) in admin_space.iter() {
+            if query.key_expr().intersects(ke) {
+                send_admin_reply(&query, ke, admin_ref, &config, &state_map, &metrics).await;
+            }
+        }
+        send_clients_replies(&query, &zenoh_id, &state_map).await;
+    }
+    tracing::warn!("Admin Space queryable was closed!");
+}
+
+// Handle puts and deletes on `clients/<uuid>` to disconnect a client,
+// and on `clients/<uuid>/<entity_type>/<id>` to undeclare one of its entities
+async fn run_admin_space_control(zenoh_runtime: Runtime, state_map: StateMap) {
+    let session = match zenoh::session::init(zenoh_runtime.clone()).await {
+        Ok(session) => session,
+        Err(err) => {
+            tracing::error!("Unable to get Zenoh session from Runtime {err}");
+            return;
+        }
+    };
+
+    let admin_prefix = keformat!(
+        ke_admin_prefix::formatter(),
+        zenoh_id = session.zid().into_keyexpr()
+    )
+    .unwrap();
+
+    let admin_clients_ke = &admin_prefix / unsafe { keyexpr::from_str_unchecked("clients/**") };
+
+    let admin_subscriber = session
+        .declare_subscriber(admin_clients_ke)
+        .await
+        .expect("Failed fo create AdminSpace Subscriber");
+
+    while let Ok(sample) = admin_subscriber.recv_async().await {
+        let key_expr: &keyexpr = sample.key_expr();
+
+        if let Ok(parsed) = ke_admin_client::parse(key_expr) {
+            let read_guard = state_map.read().await;
+            for (sock, remote_state) in read_guard.iter() {
+                if id_intersects(parsed.client_id(), &remote_state.session_id) {
+                    tracing::info!(
+                        "AdminSpace: Disconnecting client {} ({sock})",
+                        remote_state.session_id
+                    );
+                    remote_state
+                        .disconnect(CloseCode::Policy, "Disconnected from the zenoh admin space");
+                }
+            }
+        } else if let Ok(parsed) = ke_admin_client_entity::parse(key_expr) {
+            let mut undeclarations = Vec::new();
+            let read_guard = state_map.read().await;
+            for (sock, remote_state) in read_guard.iter() {
+                if id_intersects(parsed.client_id(), &remote_state.session_id) {
+                    for ctrl_msg in
+                        remote_state.undeclare_msgs(parsed.entity_type(), parsed.entity_id())
+                    {
+                        undeclarations.push((*sock, ctrl_msg));
                     }
                 }
             }
-            Err(_) => {
-                tracing::warn!("Admin Space queryable was closed!");
+            drop(read_guard);
+
+            for (sock, ctrl_msg) in undeclarations {
+                tracing::info!("AdminSpace: {ctrl_msg:?} for client {sock}");
+                if let Err(err) = handle_control_message(ctrl_msg, sock, state_map.clone()).await {
+                    error!("AdminSpace: Undeclaration failed {err}");
+                }
             }
+        } else {
+            tracing::warn!("AdminSpace: No control action on {key_expr}");
         }
     }
+    tracing::warn!("Admin Space subscriber was closed!");
 }
 
-async fn send_reply<T>(reply: T, query: Query, query_ke: OwnedKeyExpr)
+fn id_intersects(pattern: &keyexpr, id: &Uuid) -> bool {
+    let id = id.to_string();
+    keyexpr::new(id.as_str()).is_ok_and(|id| pattern.intersects(id))
+}
+
+async fn send_reply<T>(reply: T, query: &Query, query_ke: OwnedKeyExpr)
 where
     T: Sized + Serialize,
 {
@@ -415,34 +954,39 @@ async fn send_admin_reply(
     key_expr: &keyexpr,
     admin_ref: &AdminRef,
     config: &Config,
+    state_map: &StateMap,
+    metrics: &Metrics,
 ) {
-    let z_bytes: ZBytes = match admin_ref {
-        AdminRef::Version => match serde_json::to_value(RemoteApiPlugin::PLUGIN_LONG_VERSION) {
-            Ok(v) => match serde_json::to_vec(&v) {
-                Ok(value) => ZBytes::from(value),
-                Err(e) => {
-                    tracing::warn!("Error transforming JSON to ZBytes: {}", e);
-                    return;
+    let json_value = match admin_ref {
+        AdminRef::Version => serde_json::to_value(RemoteApiPlugin::PLUGIN_LONG_VERSION),
+        AdminRef::Config => serde_json::to_value(config),
+        AdminRef::Stats => {
+            let read_guard = state_map.read().await;
+            let mut entities = BTreeMap::new();
+            for remote_state in read_guard.values() {
+                for (entity, count) in remote_state.entity_counts() {
+                    *entities.entry(entity).or_insert(0) += count;
                 }
-            },
-            Err(e) => {
-                tracing::error!("INTERNAL ERROR serializing config as JSON: {}", e);
-                return;
             }
-        },
-        AdminRef::Config => match serde_json::to_value(config) {
-            Ok(v) => match serde_json::to_vec(&v) {
-                Ok(value) => ZBytes::from(value),
-                Err(e) => {
-                    tracing::warn!("Error transforming JSON to ZBytes: {}", e);
-                    return;
-                }
-            },
+            serde_json::to_value(AdminSpaceStats {
+                plugin: metrics.stats(),
+                clients: read_guard.len(),
+                entities,
+            })
+        }
+    };
+    let z_bytes: ZBytes = match json_value {
+        Ok(v) => match serde_json::to_vec(&v) {
+            Ok(value) => ZBytes::from(value),
             Err(e) => {
-                tracing::error!("INTERNAL ERROR serializing config as JSON: {}", e);
+                tracing::warn!("Error transforming JSON to ZBytes: {}", e);
                 return;
             }
         },
+        Err(e) => {
+            tracing::error!("INTERNAL ERROR serializing {:?} as JSON: {}", admin_ref, e);
+            return;
+        }
     };
     if let Err(e) = query
         .reply(key_expr.to_owned(), z_bytes)
@@ -453,21 +997,55 @@ async fn send_admin_reply(
     }
 }
 
-struct RemoteAPIPlugin;
-
-#[allow(dead_code)]
-struct RunningPlugin(RemoteAPIPlugin);
+struct RunningPlugin {
+    config_tx: Arc<watch::Sender<LiveConfig>>,
+    shutdown: Arc<Notify>,
+    stopped_rx: flume::Receiver<()>,
+}
 
 impl PluginControl for RunningPlugin {}
 
+// Stopping the plugin drains the connected clients
+impl Drop for RunningPlugin {
+    fn drop(&mut self) {
+        self.shutdown.notify_one();
+        let drain_timeout = Duration::from_millis(self.config_tx.borrow().config.drain_timeout_ms);
+        let wait_for_drain = || {
+            if self
+                .stopped_rx
+                .recv_timeout(drain_timeout + DRAIN_POLL_PERIOD)
+                .is_err()
+            {
+                tracing::warn!("Remote API plugin did not stop within {drain_timeout:?}");
+            }
+        };
+        match tokio::runtime::Handle::try_current() {
+            // Blocking a worker thread is only allowed through `block_in_place`
+            Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+                tokio::task::block_in_place(wait_for_drain)
+            }
+            // The drain runs on this very thread, so it can only be signaled
+            Ok(_) => {}
+            // Outside of any runtime, the drain runs on the global runtime
+            Err(_) => wait_for_drain(),
+        }
+    }
+}
+
 impl RunningPluginTrait for RunningPlugin {
+    // Configuration changes are only validated here, zenohd may still reject them.
+    // Accepted changes are applied live by `apply_config_changes`
     fn config_checker(
         &self,
-        _path: &str,
+        path: &str,
         _current: &serde_json::Map<String, serde_json::Value>,
-        _new: &serde_json::Map<String, serde_json::Value>,
+        new: &serde_json::Map<String, serde_json::Value>,
     ) -> ZResult<Option<serde_json::Map<String, serde_json::Value>>> {
-        bail!("Runtime configuration change not supported");
+        let config: Config = serde_json::from_value(serde_json::Value::Object(new.clone()))
+            .map_err(|e| zerror!("Configuration error on `{}`: {}", path, e))?;
+        LiveConfig::load(config)
+            .map_err(|e| zerror!("Configuration error on `{}`: {}", path, e))?;
+        Ok(None)
     }
 }
 
@@ -478,38 +1056,305 @@ struct RemoteState {
     session_id: Uuid,
     session: Session,
     // PubSub
-    subscribers: HashMap<Uuid, (JoinHandle<()>, OwnedKeyExpr)>,
+    subscribers: HashMap<Uuid, (SubscriberTask, OwnedKeyExpr, HandlerChannel)>,
     publishers: HashMap<Uuid, Publisher<'static>>,
+    matching_listeners: HashMap<Uuid, (MatchingListener<()>, Uuid)>,
+    // Advanced PubSub
+    advanced_subscribers: HashMap<
+        Uuid,
+        (
+            SubscriberTask,
+            SampleMissListener<()>,
+            OwnedKeyExpr,
+            HandlerChannel,
+        ),
+    >,
+    // Advanced publishers do not expose their reliability
+    advanced_publishers: HashMap<Uuid, (AdvancedPublisher<'static>, Reliability)>,
     // Queryable
     queryables: HashMap<Uuid, (Queryable<()>, OwnedKeyExpr)>,
+    queriers: HashMap<Uuid, Querier<'static>>,
     unanswered_queries: Arc<std::sync::RwLock<HashMap<Uuid, Query>>>,
     // Liveliness
-    liveliness_tokens: HashMap<Uuid, LivelinessToken>,
+    liveliness_tokens: HashMap<Uuid, (LivelinessToken, OwnedKeyExpr)>,
     liveliness_subscribers: HashMap<Uuid, (JoinHandle<()>, OwnedKeyExpr)>,
+    // Connection
+    identity: ClientIdentity,
+    liveliness_announcement: Option<LivelinessToken>,
+    compression: Compression,
+    // Statistics
+    stats: Arc<ClientStats>,
+    // Close frame to send to the client to disconnect it
+    disconnect_tx: Sender<CloseFrame<'static>>,
 }
 
 impl RemoteState {
-    fn new(websocket_tx: Sender<RemoteAPIMsg>, session_id: Uuid, session: Session) -> Self {
+    fn new(
+        websocket_tx: Sender<RemoteAPIMsg>,
+        disconnect_tx: Sender<CloseFrame<'static>>,
+        session_id: Uuid,
+        session: Session,
+        identity: ClientIdentity,
+    ) -> Self {
         Self {
             websocket_tx,
             session_id,
             session,
             subscribers: HashMap::new(),
             publishers: HashMap::new(),
+            matching_listeners: HashMap::new(),
+            advanced_subscribers: HashMap::new(),
+            advanced_publishers: HashMap::new(),
             queryables: HashMap::new(),
+            queriers: HashMap::new(),
             unanswered_queries: Arc::new(std::sync::RwLock::new(HashMap::new())),
             liveliness_tokens: HashMap::new(),
             liveliness_subscribers: HashMap::new(),
+            identity,
+            liveliness_announcement: None,
+            compression: Compression::default(),
+            stats: Arc::new(ClientStats::new()),
+            disconnect_tx,
         }
     }
 
+    // Close the WebSocket of the client, which then gets cleaned up
+    fn disconnect(&self, code: CloseCode, reason: &'static str) {
+        let close_frame = CloseFrame {
+            code,
+            reason: reason.into(),
+        };
+        // A close frame already waiting to be sent is enough
+        let _ = self.disconnect_tx.try_send(close_frame);
+    }
+
+    // Control messages undeclaring the entities matching an admin space `<entity_type>/<id>`
+    fn undeclare_msgs(&self, entity_type: &keyexpr, id_pattern: &keyexpr) -> Vec<ControlMsg> {
+        let entities: [(EntityType, Vec<Uuid>, fn(Uuid) -> ControlMsg); 9] = [
+            (
+                EntityType::Publishers,
+                self.publishers.keys().copied().collect(),
+                ControlMsg::UndeclarePublisher,
+            ),
+            (
+                EntityType::Subscribers,
+                self.subscribers.keys().copied().collect(),
+                ControlMsg::UndeclareSubscriber,
+            ),
+            (
+                EntityType::Queryables,
+                self.queryables.keys().copied().collect(),
+                ControlMsg::UndeclareQueryable,
+            ),
+            (
+                EntityType::Queriers,
+                self.queriers.keys().copied().collect(),
+                ControlMsg::UndeclareQuerier,
+            ),
+            (
+                EntityType::MatchingListeners,
+                self.matching_listeners.keys().copied().collect(),
+                ControlMsg::UndeclareMatchingListener,
+            ),
+            (
+                EntityType::AdvancedPublishers,
+                self.advanced_publishers.keys().copied().collect(),
+                ControlMsg::UndeclareAdvancedPublisher,
+            ),
+            (
+                EntityType::AdvancedSubscribers,
+                self.advanced_subscribers.keys().copied().collect(),
+                ControlMsg::UndeclareAdvancedSubscriber,
+            ),
+            (
+                EntityType::LivelinessTokens,
+                self.liveliness_tokens.keys().copied().collect(),
+                |id| ControlMsg::Liveliness(LivelinessMsg::UndeclareToken(id)),
+            ),
+            (
+                EntityType::LivelinessSubscribers,
+                self.liveliness_subscribers.keys().copied().collect(),
+                |id| ControlMsg::Liveliness(LivelinessMsg::UndeclareSubscriber(id)),
+            ),
+        ];
+
+        let mut ctrl_msgs = Vec::new();
+        for (name, ids, undeclare) in entities {
+            // Entity type names are valid key expressions
+            if !entity_type.intersects(unsafe { keyexpr::from_str_unchecked(name.as_str()) }) {
+                continue;
+            }
+            for id in ids {
+                if id_intersects(id_pattern, &id) {
+                    ctrl_msgs.push(undeclare(id));
+                }
+            }
+        }
+        ctrl_msgs
+    }
+
+    // Declared entities and pending queries as `(entity_type, id, node)` of the admin space
+    fn admin_entities(&self) -> Vec<(EntityType, Uuid, AdminSpaceEntity)> {
+        let mut entities = Vec::new();
+
+        for (id, publisher) in self.publishers.iter() {
+            let entity = AdminSpaceEntity {
+                uuid: id.to_string(),
+                key_expr: publisher.key_expr().to_string(),
+                encoding: Some(publisher.encoding().to_string()),
+                congestion_control: Some(format!("{:?}", publisher.congestion_control())),
+                priority: Some(format!("{:?}", publisher.priority())),
+                reliability: Some(format!("{:?}", publisher.reliability())),
+                ..Default::default()
+            };
+            entities.push((EntityType::Publishers, *id, entity));
+        }
+        for (id, (_, key_expr, handler)) in self.subscribers.iter() {
+            let entity = AdminSpaceEntity {
+                uuid: id.to_string(),
+                key_expr: key_expr.to_string(),
+                handler: Some(*handler),
+                ..Default::default()
+            };
+            entities.push((EntityType::Subscribers, *id, entity));
+        }
+        for (id, (_, key_expr)) in self.queryables.iter() {
+            let entity = AdminSpaceEntity {
+                uuid: id.to_string(),
+                key_expr: key_expr.to_string(),
+                ..Default::default()
+            };
+            entities.push((EntityType::Queryables, *id, entity));
+        }
+        for (id, querier) in self.queriers.iter() {
+            let entity = AdminSpaceEntity {
+                uuid: id.to_string(),
+                key_expr: querier.key_expr().to_string(),
+                ..Default::default()
+            };
+            entities.push((EntityType::Queriers, *id, entity));
+        }
+        for (id, (_, publisher_id)) in self.matching_listeners.iter() {
+            let entity = AdminSpaceEntity {
+                uuid: id.to_string(),
+                key_expr: self
+                    .publishers
+                    .get(publisher_id)
+                    .map(|publisher| publisher.key_expr().to_string())
+                    .or_else(|| {
+                        self.advanced_publishers
+                            .get(publisher_id)
+                            .map(|(publisher, _)| publisher.key_expr().to_string())
+                    })
+                    .or_else(|| {
+                        self.queriers
+                            .get(publisher_id)
+                            .map(|querier| querier.key_expr().to_string())
+                    })
+                    .unwrap_or_default(),
+                publisher_id: Some(publisher_id.to_string()),
+                ..Default::default()
+            };
+            entities.push((EntityType::MatchingListeners, *id, entity));
+        }
+        for (id, (publisher, reliability)) in self.advanced_publishers.iter() {
+            let entity = AdminSpaceEntity {
+                uuid: id.to_string(),
+                key_expr: publisher.key_expr().to_string(),
+                encoding: Some(publisher.encoding().to_string()),
+                congestion_control: Some(format!("{:?}", publisher.congestion_control())),
+                priority: Some(format!("{:?}", publisher.priority())),
+                reliability: Some(format!("{:?}", reliability)),
+                ..Default::default()
+            };
+            entities.push((EntityType::AdvancedPublishers, *id, entity));
+        }
+        for (id, (_, _, key_expr, handler)) in self.advanced_subscribers.iter() {
+            let entity = AdminSpaceEntity {
+                uuid: id.to_string(),
+                key_expr: key_expr.to_string(),
+                handler: Some(*handler),
+                ..Default::default()
+            };
+            entities.push((EntityType::AdvancedSubscribers, *id, entity));
+        }
+        for (id, (_, key_expr)) in self.liveliness_tokens.iter() {
+            let entity = AdminSpaceEntity {
+                uuid: id.to_string(),
+                key_expr: key_expr.to_string(),
+                ..Default::default()
+            };
+            entities.push((EntityType::LivelinessTokens, *id, entity));
+        }
+        for (id, (_, key_expr)) in self.liveliness_subscribers.iter() {
+            let entity = AdminSpaceEntity {
+                uuid: id.to_string(),
+                key_expr: key_expr.to_string(),
+                ..Default::default()
+            };
+            entities.push((EntityType::LivelinessSubscribers, *id, entity));
+        }
+        match self.unanswered_queries.read() {
+            Ok(unanswered_queries) => {
+                for (id, query) in unanswered_queries.iter() {
+                    let entity = AdminSpaceEntity {
+                        uuid: id.to_string(),
+                        key_expr: query.key_expr().to_string(),
+                        parameters: Some(query.parameters().to_string()),
+                        ..Default::default()
+                    };
+                    entities.push((EntityType::Queries, *id, entity));
+                }
+            }
+            Err(err) => error!("unanswered_queries RwLock has been poisoned {err:?}"),
+        }
+
+        entities
+    }
+
+    // Number of declared entities per entity type
+    fn entity_counts(&self) -> [(&'static str, usize); 10] {
+        EntityType::ALL.map(|entity_type| {
+            let count = match entity_type {
+                EntityType::Publishers => self.publishers.len(),
+                EntityType::Subscribers => self.subscribers.len(),
+                EntityType::Queryables => self.queryables.len(),
+                EntityType::Queriers => self.queriers.len(),
+                EntityType::MatchingListeners => self.matching_listeners.len(),
+                EntityType::AdvancedPublishers => self.advanced_publishers.len(),
+                EntityType::AdvancedSubscribers => self.advanced_subscribers.len(),
+                EntityType::LivelinessTokens => self.liveliness_tokens.len(),
+                EntityType::LivelinessSubscribers => self.liveliness_subscribers.len(),
+                EntityType::Queries => self
+                    .unanswered_queries
+                    .read()
+                    .map_or(0, |unanswered_queries| unanswered_queries.len()),
+            };
+            (entity_type.as_str(), count)
+        })
+    }
+
     async fn cleanup(self) {
+        for (_, (matching_listener, _)) in self.matching_listeners {
+            if let Err(e) = matching_listener.undeclare().await {
+                error!("{e}")
+            }
+        }
         for (_, publisher) in self.publishers {
             if let Err(e) = publisher.undeclare().await {
                 error!("{e}")
             }
         }
-        for (_, (subscriber, _)) in self.subscribers {
+        for (_, (subscriber, _, _)) in self.subscribers {
+            subscriber.abort();
+        }
+
+        for (_, (publisher, _)) in self.advanced_publishers {
+            if let Err(e) = publisher.undeclare().await {
+                error!("{e}")
+            }
+        }
+        for (_, (subscriber, _, _, _)) in self.advanced_subscribers {
             subscriber.abort();
         }
 
@@ -518,10 +1363,15 @@ impl RemoteState {
                 error!("{e}")
             }
         }
+        for (_, querier) in self.queriers {
+            if let Err(e) = querier.undeclare().await {
+                error!("{e}")
+            }
+        }
 
         drop(self.unanswered_queries);
 
-        for (_, queryable) in self.liveliness_tokens {
+        for (_, (queryable, _)) in self.liveliness_tokens {
             if let Err(e) = queryable.undeclare().await {
                 error!("{e}")
             }
@@ -531,6 +1381,12 @@ impl RemoteState {
             liveliness_subscriber.abort();
         }
 
+        if let Some(token) = self.liveliness_announcement {
+            if let Err(e) = token.undeclare().await {
+                error!("{e}")
+            }
+        }
+
         if let Err(err) = self.session.close().await {
             error!("{err}")
         };
@@ -546,40 +1402,136 @@ impl Streamable for TlsStream<TcpStream> {}
 
 // Listen on the Zenoh Session
 async fn run_websocket_server(
-    ws_port: &String,
     zenoh_runtime: Runtime,
     state_map: StateMap,
-    opt_certs: Option<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>)>,
-) {
-    let mut opt_tls_acceptor: Option<TlsAcceptor> = None;
-
-    if let Some((certs, key)) = opt_certs {
-        let config = rustls::ServerConfig::builder()
-            .with_no_client_auth()
-            .with_single_cert(certs, key)
-            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))
-            .expect("Could not build TLS Configuration from Certficiate/Key Combo :");
-        opt_tls_acceptor = Some(TlsAcceptor::from(Arc::new(config)));
-    }
-
-    let server: TcpListener = match TcpListener::bind(ws_port).await {
+    metrics: Arc<Metrics>,
+    mut live_config: watch::Receiver<LiveConfig>,
+) -> ZResult<()> {
+    let mut current = live_config.borrow_and_update().clone();
+    let mut ws_port = current.config.websocket_port.clone();
+    let mut config_updates = true;
+
+    let mut server: TcpListener = match TcpListener::bind(&ws_port).await {
         Ok(server) => server,
-        Err(err) => {
-            tracing::error!("Unable to start TcpListener {err}");
-            return;
-        }
+        Err(err) => bail!("Unable to start TcpListener on {ws_port}: {err}"),
     };
 
-    while let Ok((tcp_stream, sock_addr)) = server.accept().await {
+    loop {
+        let (tcp_stream, sock_addr) = select!(
+            accepted = server.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(err) => bail!("TcpListener stopped accepting connections {err}"),
+            },
+            changed = live_config.changed(), if config_updates => {
+                if changed.is_err() {
+                    config_updates = false;
+                    continue;
+                }
+                // New settings only apply to new connections
+                current = live_config.borrow_and_update().clone();
+                if current.config.websocket_port != ws_port {
+                    match TcpListener::bind(&current.config.websocket_port).await {
+                        Ok(new_server) => {
+                            tracing::info!(
+                                "Listening on {} instead of {ws_port}",
+                                current.config.websocket_port
+                            );
+                            ws_port = current.config.websocket_port.clone();
+                            server = new_server;
+                        }
+                        Err(err) => tracing::error!(
+                            "Unable to rebind TcpListener on {}, still listening on {ws_port}: {err}",
+                            current.config.websocket_port
+                        ),
+                    }
+                }
+                continue;
+            },
+        );
+
         let state_map = state_map.clone();
         let zenoh_runtime = zenoh_runtime.clone();
-        let opt_tls_acceptor = opt_tls_acceptor.clone();
+        let opt_tls_acceptor = current.tls_acceptor.clone();
+        let metrics = metrics.clone();
+        let client_liveliness = current.config.client_liveliness.clone();
+        let compression = current.config.compression.clone().unwrap_or_default();
+        let live_config_cl = live_config.clone();
 
         let new_websocket = async move {
             let sock_adress = Arc::new(sock_addr);
             let (ws_ch_tx, ws_ch_rx) = flume::unbounded::<RemoteAPIMsg>();
 
-            let mut write_guard = state_map.write().await;
+            let streamable: Box<dyn Streamable> = match &opt_tls_acceptor {
+                Some(acceptor) => match acceptor.accept(tcp_stream).await {
+                    Ok(tls_stream) => Box::new(tls_stream),
+                    Err(err) => {
+                        error!("Could not secure TcpStream -> TlsStream {:?}", err);
+                        metrics.handshake_failed();
+                        return;
+                    }
+                },
+                None => Box::new(tcp_stream),
+            };
+            let deflate_negotiated = Arc::new(OnceLock::new());
+            let streamable = InflateStream::new(streamable, deflate_negotiated.clone());
+
+            // Limits of the current configuration, which also apply to the pending connections
+            let (max_connections, allowed_origins) = {
+                let current = live_config_cl.borrow();
+                let config = &current.config;
+                (config.max_connections, config.allowed_origins.clone())
+            };
+            let connections = state_map.read().await.len();
+
+            let mut identity = ClientIdentity {
+                secure: opt_tls_acceptor.is_some(),
+                ..Default::default()
+            };
+            let on_handshake = |request: &Request, mut response: Response| {
+                let header = |name| {
+                    request
+                        .headers()
+                        .get(name)
+                        .and_then(|value| value.to_str().ok())
+                        .map(str::to_string)
+                };
+                identity.origin = header("origin");
+                identity.user_agent = header("user-agent");
+                if max_connections.is_some_and(|max_connections| connections >= max_connections) {
+                    tracing::warn!("Refusing {sock_addr}: {connections} clients already connected");
+                    return Err(refusal(StatusCode::SERVICE_UNAVAILABLE, "Too many clients"));
+                }
+                // Only browsers send an origin
+                if let (Some(allowed_origins), Some(origin)) = (&allowed_origins, &identity.origin)
+                {
+                    if !allowed_origins.contains(origin) {
+                        tracing::warn!("Refusing {sock_addr}: origin {origin} is not allowed");
+                        return Err(refusal(StatusCode::FORBIDDEN, "Origin not allowed"));
+                    }
+                }
+                if compression.deflate {
+                    if let Some(params) = DeflateParams::negotiate(request) {
+                        response
+                            .headers_mut()
+                            .insert(SEC_WEBSOCKET_EXTENSIONS, params.header());
+                        let _ = deflate_negotiated.set(params);
+                    }
+                }
+                Ok::<_, ErrorResponse>(response)
+            };
+            let ws_stream =
+                match tokio_tungstenite::accept_hdr_async(streamable, on_handshake).await {
+                    Ok(ws_stream) => ws_stream,
+                    Err(err) => {
+                        error!("Error during the websocket handshake occurred {err}");
+                        metrics.handshake_failed();
+                        return;
+                    }
+                };
+            metrics.connection_accepted();
+            let mut deflater = deflate_negotiated
+                .get()
+                .map(|params| Deflater::new(params, &compression));
 
             let session = match zenoh::session::init(zenoh_runtime.clone()).await {
                 Ok(session) => session,
@@ -591,59 +1543,103 @@ async fn run_websocket_server(
             let id = Uuid::new_v4();
             tracing::debug!("Client {sock_addr:?} -> {id}");
 
-            let state: RemoteState = RemoteState::new(ws_ch_tx.clone(), id, session);
-
-            // if remote state exists in map already. Ignore it and reinitialize
-            let _ = write_guard.insert(sock_addr, state);
-            drop(write_guard);
-
-            let streamable: Box<dyn Streamable> = match &opt_tls_acceptor {
-                Some(acceptor) => match acceptor.accept(tcp_stream).await {
-                    Ok(tls_stream) => Box::new(tls_stream),
-                    Err(err) => {
-                        error!("Could not secure TcpStream -> TlsStream {:?}", err);
-                        return;
+            // Announce the client to the other zenoh nodes
+            let liveliness_announcement = match &client_liveliness {
+                Some(client_liveliness) => {
+                    let prefix = client_liveliness.prefix.as_deref();
+                    match declare_client_liveliness(&session, prefix, id).await {
+                        Ok(token) => Some(token),
+                        Err(err) => {
+                            error!("Unable to declare liveliness token of client {id}: {err}");
+                            None
+                        }
                     }
-                },
-                None => Box::new(tcp_stream),
+                }
+                None => None,
             };
 
-            let ws_stream = tokio_tungstenite::accept_async(streamable)
-                .await
-                .expect("Error during the websocket handshake occurred");
+            let (disconnect_tx, disconnect_rx) = flume::bounded::<CloseFrame<'static>>(1);
+            let mut state: RemoteState =
+                RemoteState::new(ws_ch_tx.clone(), disconnect_tx, id, session, identity);
+            state.liveliness_announcement = liveliness_announcement;
+            state.compression = compression;
+            let client_stats = state.stats.clone();
+
+            // if remote state exists in map already. Ignore it and reinitialize
+            let _ = state_map.write().await.insert(sock_addr, state);
 
-            let (ws_tx, ws_rx) = ws_stream.split();
+            let (mut ws_tx, ws_rx) = ws_stream.split();
 
+            let metrics_cl = metrics.clone();
+            let client_stats_cl = client_stats.clone();
             let ch_rx_stream = ws_ch_rx
                 .into_stream()
-                .map(|remote_api_msg| {
-                    let val = serde_json::to_string(&remote_api_msg).unwrap(); // This unwrap should be alright
-                    Ok(Message::Text(val))
+                .map(move |remote_api_msg| {
+                    let val = remote_api_msg.to_json().unwrap(); // This unwrap should be alright
+                    metrics_cl.message_sent(remote_api_msg.label(), val.len());
+                    client_stats_cl.message_sent(val.len());
+                    Ok(match &mut deflater {
+                        Some(deflater) => deflater.message(val),
+                        None => Message::Text(val),
+                    })
                 })
-                .forward(ws_tx);
+                .forward(&mut ws_tx);
 
             let sock_adress_cl = sock_adress.clone();
 
             let state_map_cl_outer = state_map.clone();
+            let metrics_cl = metrics.clone();
+            // Kept to count the messages still queued when the client disconnects
+            let ws_ch_tx_cl = ws_ch_tx.clone();
 
             //  Incomming message from Websocket
-            let incoming_ws = tokio::task::spawn(async move {
+            let mut incoming_ws = tokio::task::spawn(async move {
                 let mut non_close_messages = ws_rx.try_filter(|msg| future::ready(!msg.is_close()));
                 let state_map_cl = state_map_cl_outer.clone();
                 let sock_adress_ref = sock_adress_cl.clone();
                 while let Ok(Some(msg)) = non_close_messages.try_next().await {
-                    if let Some(response) =
-                        handle_message(msg, *sock_adress_ref, state_map_cl.clone()).await
+                    // The access control of the latest configuration applies
+                    let acl = live_config_cl.borrow().acl.clone();
+                    if let Some(response) = handle_message(
+                        msg,
+                        *sock_adress_ref,
+                        state_map_cl.clone(),
+                        &acl,
+                        &metrics_cl,
+                        &client_stats,
+                    )
+                    .await
                     {
                         if let Err(err) = ws_ch_tx.send(response) {
+                            metrics_cl.messages_dropped(1);
+                            client_stats.message_dropped();
                             error!("WS Send Error: {err:?}");
                         };
                     };
                 }
             });
 
-            pin_mut!(ch_rx_stream, incoming_ws);
-            future::select(ch_rx_stream, incoming_ws).await;
+            // The client may also be disconnected from the admin space or on shutdown
+            let close_frame = {
+                // Scoped so that the forwarding task releases `ws_tx`
+                let disconnected = disconnect_rx.recv_async();
+                pin_mut!(ch_rx_stream, disconnected);
+                let closed =
+                    future::select(future::select(ch_rx_stream, &mut incoming_ws), disconnected)
+                        .await;
+                match closed {
+                    Either::Right((Ok(close_frame), _)) => Some(close_frame),
+                    _ => None,
+                }
+            };
+            incoming_ws.abort();
+            metrics.messages_dropped(ws_ch_tx_cl.len());
+
+            if let Some(close_frame) = close_frame {
+                if let Err(err) = ws_tx.send(Message::Close(Some(close_frame))).await {
+                    debug!("Could not send Close frame to {sock_adress}: {err}");
+                }
+            }
 
             // cleanup state
             if let Some(state) = state_map.write().await.remove(sock_adress.as_ref()) {
@@ -657,36 +1653,107 @@ async fn run_websocket_server(
     }
 }
 
+// Declare the liveliness token of a client, under `<prefix>/<uuid>`
+// or `@/<zid>/remote-plugin/clients/<uuid>` without prefix
+async fn declare_client_liveliness(
+    session: &Session,
+    prefix: Option<&str>,
+    client_id: Uuid,
+) -> ZResult<LivelinessToken> {
+    let key_expr = match prefix {
+        Some(prefix) => OwnedKeyExpr::try_from(format!("{prefix}/{client_id}"))?,
+        None => keformat!(
+            ke_admin_client::formatter(),
+            zenoh_id = session.zid().into_keyexpr(),
+            client_id = client_id
+        )?,
+    };
+    session.liveliness().declare_token(key_expr).await
+}
+
+// HTTP response refusing a WebSocket handshake
+fn refusal(status: StatusCode, reason: &str) -> ErrorResponse {
+    let mut response = ErrorResponse::new(Some(reason.to_string()));
+    *response.status_mut() = status;
+    response
+}
+
 async fn handle_message(
     msg: Message,
     sock_addr: SocketAddr,
     state_map: StateMap,
+    acl: &AccessControl,
+    metrics: &Metrics,
+    client_stats: &ClientStats,
 ) -> Option<RemoteAPIMsg> {
     match msg {
-        Message::Text(text) => match serde_json::from_str::<RemoteAPIMsg>(&text) {
-            Ok(msg) => match msg {
-                RemoteAPIMsg::Control(ctrl_msg) => {
-                    match handle_control_message(ctrl_msg, sock_addr, state_map).await {
-                        Ok(ok) => return ok.map(RemoteAPIMsg::Control),
-                        Err(err) => {
-                            tracing::error!(err);
+        Message::Text(text) => match RemoteAPIMsg::from_json(&text) {
+            Ok(msg) => {
+                metrics.message_received(msg.label(), text.len());
+                client_stats.message_received(text.len());
+                match msg {
+                    RemoteAPIMsg::Control(ctrl_msg) => {
+                        if let Some(key_expr) =
+                            ctrl_msg.key_expr().filter(|key_expr| !acl.allows(key_expr))
+                        {
+                            metrics.error("acl");
+                            client_stats.error();
+                            tracing::warn!("Access to `{key_expr}` denied to {sock_addr}");
+                            return Some(RemoteAPIMsg::Control(ControlMsg::Error(format!(
+                                "Access to `{key_expr}` denied"
+                            ))));
+                        }
+                        // Get handlers only return once all replies have been forwarded,
+                        // querier gets once their query is sent
+                        let is_get = matches!(
+                            ctrl_msg,
+                            ControlMsg::Get { .. }
+                                | ControlMsg::QuerierGet { .. }
+                                | ControlMsg::Liveliness(LivelinessMsg::Get { .. })
+                        );
+                        let start = Instant::now();
+                        match handle_control_message(ctrl_msg, sock_addr, state_map).await {
+                            Ok(ok) => {
+                                if is_get {
+                                    metrics.observe_get_duration(start.elapsed());
+                                }
+                                return ok.map(RemoteAPIMsg::Control);
+                            }
+                            Err(err) => {
+                                metrics.error("control");
+                                client_stats.error();
+                                tracing::error!(err);
+                            }
                         }
                     }
-                }
-                RemoteAPIMsg::Data(data_msg) => {
-                    if let Err(err) = handle_data_message(data_msg, sock_addr, state_map).await {
-                        tracing::error!(err);
+                    RemoteAPIMsg::Data(data_msg) => {
+                        if let Err(err) = handle_data_message(data_msg, sock_addr, state_map).await
+                        {
+                            metrics.error("data");
+                            client_stats.error();
+                            tracing::error!(err);
+                        }
                     }
                 }
-            },
+            }
             Err(err) => {
+                metrics.error("deserialize");
+                client_stats.error();
                 tracing::error!(
                     "RemoteAPI: WS Message Cannot be Deserialized to RemoteAPIMsg {}, message: {}",
                     err,
                     text
                 );
+                return Some(RemoteAPIMsg::Control(ControlMsg::Error(format!(
+                    "Message cannot be deserialized: {err}"
+                ))));
             }
         },
+        Message::Binary(_) => {
+            metrics.messages_dropped(1);
+            client_stats.message_dropped();
+            debug!("RemoteAPI: WS Message Not Text");
+        }
         _ => {
             debug!("RemoteAPI: WS Message Not Text");
         }
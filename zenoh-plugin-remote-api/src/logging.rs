@@ -0,0 +1,74 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::sync::OnceLock;
+
+use tracing_subscriber::{
+    fmt, layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter, Registry,
+};
+use zenoh_result::{zerror, ZResult};
+
+// Filter of the logs initialised by the plugin, changed by `set_log_level`
+static LOG_FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// Filter of the logs: `log_level` when set, otherwise `RUST_LOG`, and no logs without either
+pub(crate) fn log_filter(log_level: Option<&str>) -> ZResult<EnvFilter> {
+    match log_level {
+        Some(log_level) => EnvFilter::try_new(log_level)
+            .map_err(|e| zerror!("Invalid log level `{}`: {}", log_level, e).into()),
+        None => Ok(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("off"))),
+    }
+}
+
+/// Initialise the logs as `zenoh_util::try_init_log_from_env` does, with a filter that can be
+/// changed at runtime. Required in case of dynamic lib, otherwise no logs. But it has no effect
+/// in case of static link, the logs being initialised by the host.
+pub(crate) fn try_init_log(log_level: Option<&str>) {
+    let filter = match log_filter(log_level) {
+        Ok(filter) => filter,
+        Err(err) => {
+            eprintln!("{err}");
+            return;
+        }
+    };
+    let (filter, handle) = reload::Layer::new(filter);
+    let layer = fmt::layer()
+        .with_thread_ids(true)
+        .with_thread_names(true)
+        .with_level(true)
+        .with_target(true);
+    if tracing_subscriber::registry()
+        .with(filter)
+        .with(layer)
+        .try_init()
+        .is_ok()
+    {
+        let _ = LOG_FILTER.set(handle);
+    }
+}
+
+/// Change the filter of the logs initialised by `try_init_log`
+pub(crate) fn set_log_level(log_level: Option<&str>) {
+    let Some(handle) = LOG_FILTER.get() else {
+        if log_level.is_some() {
+            tracing::warn!("`log_level` is ignored, the logs being initialised by the host");
+        }
+        return;
+    };
+    match log_filter(log_level).map(|filter| handle.reload(filter)) {
+        Ok(Ok(())) => {}
+        Ok(Err(err)) => tracing::error!("Unable to change the log level: {err}"),
+        Err(err) => tracing::error!("{err}"),
+    }
+}
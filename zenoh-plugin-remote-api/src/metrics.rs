@@ -0,0 +1,544 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+use tracing::{debug, error};
+
+use crate::{spawn_future, StateMap};
+
+// Upper bounds in seconds of the buckets of the Get round trip histogram
+const GET_DURATION_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+const MAX_REQUEST_SIZE: usize = 8192;
+
+/// Aggregate counters of the plugin, rendered in the Prometheus text format
+pub(crate) struct Metrics {
+    started: Instant,
+    connections: AtomicU64,
+    handshakes_failed: AtomicU64,
+    bytes_received: AtomicU64,
+    bytes_sent: AtomicU64,
+    dropped: AtomicU64,
+    messages_received: Mutex<BTreeMap<&'static str, u64>>,
+    messages_sent: Mutex<BTreeMap<&'static str, u64>>,
+    errors: Mutex<BTreeMap<&'static str, u64>>,
+    get_duration: Mutex<Histogram>,
+}
+
+impl Metrics {
+    pub(crate) fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            connections: AtomicU64::new(0),
+            handshakes_failed: AtomicU64::new(0),
+            bytes_received: AtomicU64::new(0),
+            bytes_sent: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            messages_received: Mutex::new(BTreeMap::new()),
+            messages_sent: Mutex::new(BTreeMap::new()),
+            errors: Mutex::new(BTreeMap::new()),
+            get_duration: Mutex::new(Histogram::default()),
+        }
+    }
+
+    pub(crate) fn connection_accepted(&self) {
+        self.connections.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn handshake_failed(&self) {
+        self.handshakes_failed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn message_received(&self, label: &'static str, bytes: usize) {
+        self.bytes_received
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+        increment(&self.messages_received, label);
+    }
+
+    pub(crate) fn message_sent(&self, label: &'static str, bytes: usize) {
+        self.bytes_sent.fetch_add(bytes as u64, Ordering::Relaxed);
+        increment(&self.messages_sent, label);
+    }
+
+    pub(crate) fn messages_dropped(&self, count: usize) {
+        self.dropped.fetch_add(count as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn error(&self, source: &'static str) {
+        increment(&self.errors, source);
+    }
+
+    pub(crate) fn observe_get_duration(&self, duration: Duration) {
+        match self.get_duration.lock() {
+            Ok(mut histogram) => histogram.observe(duration.as_secs_f64()),
+            Err(err) => error!("Metrics Mutex has been poisoned {err:?}"),
+        }
+    }
+
+    /// Snapshot of the aggregate counters, published in the admin space
+    pub(crate) fn stats(&self) -> PluginStats {
+        PluginStats {
+            uptime_secs: self.started.elapsed().as_secs(),
+            connections_total: self.connections.load(Ordering::Relaxed),
+            handshakes_failed: self.handshakes_failed.load(Ordering::Relaxed),
+            messages_received: total(&self.messages_received),
+            messages_sent: total(&self.messages_sent),
+            bytes_received: self.bytes_received.load(Ordering::Relaxed),
+            bytes_sent: self.bytes_sent.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+            errors: total(&self.errors),
+        }
+    }
+
+    pub(crate) async fn render(&self, state_map: &StateMap) -> String {
+        let mut out = String::new();
+
+        write_header(
+            &mut out,
+            "remote_api_connections_total",
+            "counter",
+            "Total number of accepted WebSocket connections",
+        );
+        let _ = writeln!(
+            out,
+            "remote_api_connections_total {}",
+            self.connections.load(Ordering::Relaxed)
+        );
+
+        write_header(
+            &mut out,
+            "remote_api_handshakes_failed_total",
+            "counter",
+            "Total number of connections which failed the TLS or WebSocket handshake",
+        );
+        let _ = writeln!(
+            out,
+            "remote_api_handshakes_failed_total {}",
+            self.handshakes_failed.load(Ordering::Relaxed)
+        );
+
+        write_header(
+            &mut out,
+            "remote_api_bytes_received_total",
+            "counter",
+            "Total number of bytes received from clients",
+        );
+        let _ = writeln!(
+            out,
+            "remote_api_bytes_received_total {}",
+            self.bytes_received.load(Ordering::Relaxed)
+        );
+
+        write_header(
+            &mut out,
+            "remote_api_bytes_sent_total",
+            "counter",
+            "Total number of bytes sent to clients",
+        );
+        let _ = writeln!(
+            out,
+            "remote_api_bytes_sent_total {}",
+            self.bytes_sent.load(Ordering::Relaxed)
+        );
+
+        write_header(
+            &mut out,
+            "remote_api_messages_received_total",
+            "counter",
+            "Number of messages received from clients per message type",
+        );
+        write_labeled(
+            &mut out,
+            "remote_api_messages_received_total",
+            "type",
+            &self.messages_received,
+        );
+
+        write_header(
+            &mut out,
+            "remote_api_messages_sent_total",
+            "counter",
+            "Number of messages sent to clients per message type",
+        );
+        write_labeled(
+            &mut out,
+            "remote_api_messages_sent_total",
+            "type",
+            &self.messages_sent,
+        );
+
+        write_header(
+            &mut out,
+            "remote_api_messages_dropped_total",
+            "counter",
+            "Number of messages dropped instead of being handled or delivered",
+        );
+        let _ = writeln!(
+            out,
+            "remote_api_messages_dropped_total {}",
+            self.dropped.load(Ordering::Relaxed)
+        );
+
+        write_header(
+            &mut out,
+            "remote_api_errors_total",
+            "counter",
+            "Number of errors while handling client messages",
+        );
+        write_labeled(&mut out, "remote_api_errors_total", "source", &self.errors);
+
+        write_header(
+            &mut out,
+            "remote_api_get_duration_seconds",
+            "histogram",
+            "Round trip duration of Get requests, from request to GetFinished",
+        );
+        match self.get_duration.lock() {
+            Ok(histogram) => histogram.render(&mut out, "remote_api_get_duration_seconds"),
+            Err(err) => error!("Metrics Mutex has been poisoned {err:?}"),
+        }
+
+        let read_guard = state_map.read().await;
+
+        write_header(
+            &mut out,
+            "remote_api_clients",
+            "gauge",
+            "Number of connected clients",
+        );
+        let _ = writeln!(out, "remote_api_clients {}", read_guard.len());
+
+        write_header(
+            &mut out,
+            "remote_api_client_queue_depth",
+            "gauge",
+            "Number of messages waiting to be sent on the WebSocket of a client",
+        );
+        for remote_state in read_guard.values() {
+            let _ = writeln!(
+                out,
+                "remote_api_client_queue_depth{{client=\"{}\"}} {}",
+                remote_state.session_id,
+                remote_state.websocket_tx.len()
+            );
+        }
+
+        write_header(
+            &mut out,
+            "remote_api_client_entities",
+            "gauge",
+            "Number of entities declared by a client per entity type",
+        );
+        for remote_state in read_guard.values() {
+            for (entity, count) in remote_state.entity_counts() {
+                let _ = writeln!(
+                    out,
+                    "remote_api_client_entities{{client=\"{}\",entity=\"{entity}\"}} {count}",
+                    remote_state.session_id,
+                );
+            }
+        }
+
+        out
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct PluginStats {
+    uptime_secs: u64,
+    connections_total: u64,
+    handshakes_failed: u64,
+    messages_received: u64,
+    messages_sent: u64,
+    bytes_received: u64,
+    bytes_sent: u64,
+    dropped: u64,
+    errors: u64,
+}
+
+/// Counters of a single client, published in the admin space
+pub(crate) struct ClientStats {
+    connected_at: SystemTime,
+    // Milliseconds since UNIX epoch of the last message received from the client
+    last_activity: AtomicU64,
+    messages_received: AtomicU64,
+    messages_sent: AtomicU64,
+    bytes_received: AtomicU64,
+    bytes_sent: AtomicU64,
+    dropped: AtomicU64,
+    errors: AtomicU64,
+}
+
+impl ClientStats {
+    pub(crate) fn new() -> Self {
+        let connected_at = SystemTime::now();
+        Self {
+            connected_at,
+            last_activity: AtomicU64::new(unix_millis(connected_at)),
+            messages_received: AtomicU64::new(0),
+            messages_sent: AtomicU64::new(0),
+            bytes_received: AtomicU64::new(0),
+            bytes_sent: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            errors: AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn message_received(&self, bytes: usize) {
+        self.last_activity
+            .store(unix_millis(SystemTime::now()), Ordering::Relaxed);
+        self.messages_received.fetch_add(1, Ordering::Relaxed);
+        self.bytes_received
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn message_sent(&self, bytes: usize) {
+        self.messages_sent.fetch_add(1, Ordering::Relaxed);
+        self.bytes_sent.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn message_dropped(&self) {
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn error(&self) {
+        self.errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // Milliseconds since UNIX epoch
+    pub(crate) fn connected_at(&self) -> u64 {
+        unix_millis(self.connected_at)
+    }
+
+    pub(crate) fn stats(&self) -> ClientStatsSnapshot {
+        ClientStatsSnapshot {
+            connected_at: unix_millis(self.connected_at),
+            uptime_secs: self.connected_at.elapsed().unwrap_or_default().as_secs(),
+            last_activity: self.last_activity.load(Ordering::Relaxed),
+            messages_received: self.messages_received.load(Ordering::Relaxed),
+            messages_sent: self.messages_sent.load(Ordering::Relaxed),
+            bytes_received: self.bytes_received.load(Ordering::Relaxed),
+            bytes_sent: self.bytes_sent.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ClientStatsSnapshot {
+    // Milliseconds since UNIX epoch
+    connected_at: u64,
+    uptime_secs: u64,
+    // Milliseconds since UNIX epoch
+    last_activity: u64,
+    messages_received: u64,
+    messages_sent: u64,
+    bytes_received: u64,
+    bytes_sent: u64,
+    dropped: u64,
+    errors: u64,
+}
+
+fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+fn total(counters: &Mutex<BTreeMap<&'static str, u64>>) -> u64 {
+    match counters.lock() {
+        Ok(counters) => counters.values().sum(),
+        Err(err) => {
+            error!("Metrics Mutex has been poisoned {err:?}");
+            0
+        }
+    }
+}
+
+fn increment(counters: &Mutex<BTreeMap<&'static str, u64>>, label: &'static str) {
+    match counters.lock() {
+        Ok(mut counters) => *counters.entry(label).or_insert(0) += 1,
+        Err(err) => error!("Metrics Mutex has been poisoned {err:?}"),
+    }
+}
+
+fn write_header(out: &mut String, name: &str, metric_type: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {metric_type}");
+}
+
+fn write_labeled(
+    out: &mut String,
+    name: &str,
+    label_name: &str,
+    counters: &Mutex<BTreeMap<&'static str, u64>>,
+) {
+    match counters.lock() {
+        Ok(counters) => {
+            for (label, count) in counters.iter() {
+                let _ = writeln!(out, "{name}{{{label_name}=\"{label}\"}} {count}");
+            }
+        }
+        Err(err) => error!("Metrics Mutex has been poisoned {err:?}"),
+    }
+}
+
+#[derive(Default)]
+struct Histogram {
+    buckets: [u64; GET_DURATION_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        for (bucket, upper_bound) in self.buckets.iter_mut().zip(GET_DURATION_BUCKETS) {
+            if value <= upper_bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn render(&self, out: &mut String, name: &str) {
+        for (bucket, upper_bound) in self.buckets.iter().zip(GET_DURATION_BUCKETS) {
+            let _ = writeln!(out, "{name}_bucket{{le=\"{upper_bound}\"}} {bucket}");
+        }
+        let _ = writeln!(out, "{name}_bucket{{le=\"+Inf\"}} {}", self.count);
+        let _ = writeln!(out, "{name}_sum {}", self.sum);
+        let _ = writeln!(out, "{name}_count {}", self.count);
+    }
+}
+
+// Serve the Prometheus metrics over plain HTTP on `GET /metrics`
+pub(crate) async fn run_metrics_server(
+    metrics_port: &String,
+    metrics: Arc<Metrics>,
+    state_map: StateMap,
+) {
+    let server: TcpListener = match TcpListener::bind(metrics_port).await {
+        Ok(server) => server,
+        Err(err) => {
+            tracing::error!("Unable to start Metrics TcpListener {err}");
+            return;
+        }
+    };
+
+    while let Ok((tcp_stream, sock_addr)) = server.accept().await {
+        let metrics = metrics.clone();
+        let state_map = state_map.clone();
+        spawn_future(async move {
+            if let Err(err) = serve_metrics(tcp_stream, metrics, state_map).await {
+                debug!("Metrics request from {sock_addr} failed: {err}");
+            }
+        });
+    }
+}
+
+async fn serve_metrics(
+    mut tcp_stream: TcpStream,
+    metrics: Arc<Metrics>,
+    state_map: StateMap,
+) -> std::io::Result<()> {
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < MAX_REQUEST_SIZE {
+        let n = tcp_stream.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        request.extend_from_slice(&buf[..n]);
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let mut request_line = request
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .split_whitespace();
+    let response = match (request_line.next(), request_line.next()) {
+        (Some("GET"), Some("/metrics")) => {
+            let body = metrics.render(&state_map).await;
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+        }
+        _ => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string(),
+    };
+
+    tcp_stream.write_all(response.as_bytes()).await?;
+    tcp_stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histogram_buckets_are_cumulative() {
+        let mut histogram = Histogram::default();
+        histogram.observe(0.003);
+        histogram.observe(0.2);
+        histogram.observe(20.0);
+
+        let mut out = String::new();
+        histogram.render(&mut out, "test");
+
+        assert!(out.contains("test_bucket{le=\"0.005\"} 1\n"));
+        assert!(out.contains("test_bucket{le=\"0.25\"} 2\n"));
+        assert!(out.contains("test_bucket{le=\"10\"} 2\n"));
+        assert!(out.contains("test_bucket{le=\"+Inf\"} 3\n"));
+        assert!(out.contains("test_count 3\n"));
+    }
+
+    #[test]
+    fn stats_totals_sum_all_labels() {
+        let metrics = Metrics::new();
+        metrics.message_received("ControlMsg::Put", 10);
+        metrics.message_received("ControlMsg::Get", 5);
+        metrics.message_sent("DataMsg::Sample", 20);
+        metrics.error("data");
+        metrics.messages_dropped(3);
+        metrics.connection_accepted();
+        metrics.handshake_failed();
+
+        let stats = metrics.stats();
+        assert_eq!(stats.messages_received, 2);
+        assert_eq!(stats.bytes_received, 15);
+        assert_eq!(stats.messages_sent, 1);
+        assert_eq!(stats.bytes_sent, 20);
+        assert_eq!(stats.errors, 1);
+        assert_eq!(stats.dropped, 3);
+        assert_eq!(stats.connections_total, 1);
+        assert_eq!(stats.handshakes_failed, 1);
+    }
+}
@@ -0,0 +1,1732 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! End to end tests, driving the WebSocket server of an in-process zenoh runtime
+//! with a Rust client speaking `RemoteAPIMsg`.
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use futures::{SinkExt, StreamExt};
+use tokio::{io::AsyncWriteExt, net::TcpStream, sync::RwLock, task::JoinHandle, time::timeout};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{
+        client::IntoClientRequest,
+        http::{header::SEC_WEBSOCKET_EXTENSIONS, HeaderValue, StatusCode},
+        protocol::{frame::coding::CloseCode, Message},
+    },
+    MaybeTlsStream, WebSocketStream,
+};
+use uhlc::Timestamp;
+use uuid::Uuid;
+use zenoh::{
+    bytes::Encoding,
+    internal::runtime::{Runtime, RuntimeBuilder},
+    key_expr::{keyexpr, OwnedKeyExpr},
+    qos::Reliability,
+    sample::SampleKind,
+    Session, Wait,
+};
+use zenoh_ext::{AdvancedPublisherBuilderExt, CacheConfig};
+use zenoh_remote_api_protocol::{
+    B64String, BatchConfigWS, CompressionWS, ControlMsg, DataMsg, EncodingWS, EntityGlobalIdWS,
+    HandlerChannel, HistoryConfigWS, JsonPredicateWS, LivelinessMsg, QueryReplyVariant,
+    QueryReplyWS, QueryableMsg, RemoteAPIMsg, SampleFilterWS, SampleKindWS, SampleWS, SourceInfoWS,
+    ThrottleWS, TimestampWS,
+};
+use zenoh_result::ZResult;
+
+use crate::{
+    apply_config_changes, metrics::Metrics, run_admin_space_control, run_websocket_server,
+    AdminSpaceClient, Config, EntityType, LiveConfig, RemoteAPIRuntime, StateMap,
+};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn ke(key_expr: &str) -> OwnedKeyExpr {
+    keyexpr::new(key_expr).unwrap().to_owned()
+}
+
+fn b64(payload: &str) -> B64String {
+    B64String::encode(payload)
+}
+
+fn from_b64(payload: &B64String) -> String {
+    String::from_utf8(payload.to_bytes().unwrap()).unwrap()
+}
+
+// A peer runtime isolated from the network
+async fn isolated_runtime() -> Runtime {
+    let mut zenoh_config = zenoh::Config::default();
+    zenoh_config.insert_json5("mode", r#""peer""#).unwrap();
+    zenoh_config.insert_json5("listen/endpoints", "[]").unwrap();
+    zenoh_config
+        .insert_json5("scouting/multicast/enabled", "false")
+        .unwrap();
+    zenoh_config
+        .insert_json5("scouting/gossip/enabled", "false")
+        .unwrap();
+    let mut runtime: Runtime = RuntimeBuilder::new(zenoh_config).build().await.unwrap();
+    runtime.start().await.unwrap();
+    runtime
+}
+
+// Configuration serving WebSockets on an ephemeral loopback port
+fn loopback_config() -> LiveConfig {
+    // Reserve a free port, released for the server to bind it
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let config = serde_json::json!({ "websocket_port": format!("127.0.0.1:{port}") });
+    let config: Config = serde_json::from_value(config).unwrap();
+    LiveConfig::load(config).unwrap()
+}
+
+async fn connect(address: &str) -> Client {
+    // The server may not be listening yet
+    for _ in 0..50 {
+        if let Ok((ws, _)) = connect_async(format!("ws://{address}")).await {
+            return Client { ws };
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("Could not connect to {address}");
+}
+
+// An isolated runtime serving WebSockets on an ephemeral loopback port
+struct Harness {
+    address: String,
+    state_map: StateMap,
+    metrics: Arc<Metrics>,
+    // Session on the same runtime, acting as the other zenoh applications
+    session: Session,
+    // Configuration changes, as applied by `apply_config_changes`
+    config_tx: tokio::sync::watch::Sender<LiveConfig>,
+    server: JoinHandle<ZResult<()>>,
+    admin_space_control: JoinHandle<()>,
+}
+
+impl Harness {
+    async fn start() -> Self {
+        let runtime = isolated_runtime().await;
+        let live_config = loopback_config();
+        let address = live_config.config.websocket_port.clone();
+        let (config_tx, config_rx) = tokio::sync::watch::channel(live_config);
+
+        let state_map: StateMap = Arc::new(RwLock::new(HashMap::new()));
+        let metrics = Arc::new(Metrics::new());
+        let server = tokio::spawn(run_websocket_server(
+            runtime.clone(),
+            state_map.clone(),
+            metrics.clone(),
+            config_rx,
+        ));
+        // As with `admin_space_control: true`
+        let admin_space_control =
+            tokio::spawn(run_admin_space_control(runtime.clone(), state_map.clone()));
+        let session = zenoh::session::init(runtime).await.unwrap();
+
+        Harness {
+            address,
+            state_map,
+            metrics,
+            session,
+            config_tx,
+            server,
+            admin_space_control,
+        }
+    }
+
+    // Replace the settings of the configuration, other than the WebSocket port
+    fn reconfigure(&self, settings: serde_json::Value) {
+        let mut config = settings;
+        config["websocket_port"] = self.address.clone().into();
+        let config: Config = serde_json::from_value(config).unwrap();
+        self.config_tx
+            .send_replace(LiveConfig::load(config).unwrap());
+    }
+
+    async fn connect(&self) -> Client {
+        connect(&self.address).await
+    }
+
+    async fn connect_rust_client(&self) -> zenoh_remote_api_client::Client {
+        // The server may not be listening yet
+        for _ in 0..50 {
+            let url = format!("ws://{}", self.address);
+            if let Ok(client) = zenoh_remote_api_client::Client::connect(&url).await {
+                return client;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("Could not connect to {}", self.address);
+    }
+
+    async fn wait_for_clients(&self, count: usize) {
+        timeout(TIMEOUT, async {
+            while self.state_map.read().await.len() != count {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        })
+        .await
+        .expect("Unexpected number of clients");
+    }
+}
+
+impl Drop for Harness {
+    fn drop(&mut self) {
+        self.server.abort();
+        self.admin_space_control.abort();
+    }
+}
+
+struct Client {
+    ws: WebSocketStream<MaybeTlsStream<TcpStream>>,
+}
+
+// Options of the subscribers declared by the tests, none by default
+#[derive(Default)]
+struct SubscriberOptions {
+    transcode_to: Option<EncodingWS>,
+    compression: Option<CompressionWS>,
+    batch: Option<BatchConfigWS>,
+    throttle: Option<ThrottleWS>,
+    filters: Option<Vec<SampleFilterWS>>,
+}
+
+impl SubscriberOptions {
+    fn declare(self, key_expr: &str, id: Uuid) -> RemoteAPIMsg {
+        RemoteAPIMsg::Control(ControlMsg::DeclareSubscriber {
+            key_expr: ke(key_expr),
+            handler: HandlerChannel::Fifo(16),
+            id,
+            transcode_to: self.transcode_to,
+            compression: self.compression,
+            batch: self.batch,
+            throttle: self.throttle,
+            filters: self.filters,
+        })
+    }
+}
+
+impl Client {
+    async fn send(&mut self, msg: RemoteAPIMsg) {
+        let text = serde_json::to_string(&msg).unwrap();
+        self.ws.send(Message::Text(text)).await.unwrap();
+    }
+
+    async fn recv(&mut self) -> RemoteAPIMsg {
+        loop {
+            let msg = timeout(TIMEOUT, self.ws.next())
+                .await
+                .expect("No message received")
+                .expect("WebSocket closed")
+                .unwrap();
+            if let Message::Text(text) = msg {
+                return serde_json::from_str(&text).unwrap();
+            }
+        }
+    }
+
+    async fn open_session(&mut self) -> Uuid {
+        self.send(RemoteAPIMsg::Control(ControlMsg::OpenSession))
+            .await;
+        match self.recv().await {
+            RemoteAPIMsg::Control(ControlMsg::Session(id)) => id,
+            msg => panic!("Expected Session, got {msg:?}"),
+        }
+    }
+
+    // Messages are handled in order, so the previous ones are processed once the session is returned
+    async fn sync(&mut self) {
+        self.open_session().await;
+    }
+
+    async fn declare_subscriber(&mut self, key_expr: &str, options: SubscriberOptions) -> Uuid {
+        let id = Uuid::new_v4();
+        self.send(options.declare(key_expr, id)).await;
+        match self.recv().await {
+            RemoteAPIMsg::Control(ControlMsg::Subscriber(sub_id)) => assert_eq!(sub_id, id),
+            msg => panic!("Expected Subscriber, got {msg:?}"),
+        }
+        id
+    }
+
+    async fn expect_matching_status(&mut self, id: Uuid) -> bool {
+        match self.recv().await {
+            RemoteAPIMsg::Data(DataMsg::MatchingStatus {
+                id: listener_id,
+                matching,
+            }) => {
+                assert_eq!(listener_id, id);
+                matching
+            }
+            msg => panic!("Expected MatchingStatus, got {msg:?}"),
+        }
+    }
+
+    async fn expect_sample(&mut self, id: Uuid) -> SampleWS {
+        match self.recv().await {
+            RemoteAPIMsg::Data(DataMsg::Sample(sample, sub_id)) => {
+                assert_eq!(sub_id, id);
+                sample
+            }
+            msg => panic!("Expected Sample, got {msg:?}"),
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn put_and_subscribe() {
+    let harness = Harness::start().await;
+    let mut client = harness.connect().await;
+    client.open_session().await;
+
+    let id = client
+        .declare_subscriber("test/sub", SubscriberOptions::default())
+        .await;
+
+    harness
+        .session
+        .put("test/sub", "from zenoh")
+        .encoding(Encoding::TEXT_PLAIN.with_schema("utf-8"))
+        .await
+        .unwrap();
+    let sample = client.expect_sample(id).await;
+    assert_eq!(sample.key_expr().as_str(), "test/sub");
+    assert_eq!(from_b64(sample.value()), "from zenoh");
+    assert_eq!(sample.encoding(), &EncodingWS::new(4, Some("utf-8".into())));
+
+    let subscriber = harness
+        .session
+        .declare_subscriber("test/put")
+        .await
+        .unwrap();
+    client
+        .send(RemoteAPIMsg::Control(ControlMsg::Put {
+            key_expr: ke("test/put"),
+            payload: b64("from client"),
+            encoding: Some("my/encoding;v2".parse().unwrap()),
+            congestion_control: None,
+            priority: None,
+            express: None,
+            attachment: None,
+            timestamp: None,
+            source_info: None,
+        }))
+        .await;
+    let sample = timeout(TIMEOUT, subscriber.recv_async())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(sample.key_expr().as_str(), "test/put");
+    assert_eq!(sample.payload().try_to_string().unwrap(), "from client");
+    assert_eq!(sample.encoding(), &Encoding::from("my/encoding;v2"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn undeserializable_message_is_reported() {
+    let harness = Harness::start().await;
+    let mut client = harness.connect().await;
+    client.open_session().await;
+
+    let put = serde_json::json!({
+        "Control": {
+            "Put": {
+                "key_expr": "test/invalid",
+                "payload": b64("from client"),
+                "encoding": "unknown(x)",
+            }
+        }
+    });
+    client
+        .ws
+        .send(Message::Text(put.to_string()))
+        .await
+        .unwrap();
+    match client.recv().await {
+        RemoteAPIMsg::Control(ControlMsg::Error(reason)) => assert!(reason.contains("unknown(x)")),
+        msg => panic!("Expected Error, got {msg:?}"),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn publisher_put_and_delete() {
+    let harness = Harness::start().await;
+    let mut client = harness.connect().await;
+    client.open_session().await;
+
+    let subscriber = harness
+        .session
+        .declare_subscriber("test/publisher")
+        .await
+        .unwrap();
+    let id = Uuid::new_v4();
+    client
+        .send(RemoteAPIMsg::Control(ControlMsg::DeclarePublisher {
+            key_expr: ke("test/publisher"),
+            encoding: None,
+            congestion_control: None,
+            priority: None,
+            reliability: None,
+            express: None,
+            id,
+        }))
+        .await;
+
+    // Timestamps and source info set by the client are kept on the samples
+    let timestamp = || TimestampWS::new(1_700_000_000, 1 << 31, "33".into());
+    let source_info = || SourceInfoWS::new(EntityGlobalIdWS::new("a1b2".into(), 3), 42);
+    client
+        .send(RemoteAPIMsg::Data(DataMsg::PublisherPut {
+            id,
+            payload: b64("put"),
+            attachment: None,
+            encoding: None,
+            timestamp: Some(timestamp()),
+            source_info: Some(source_info()),
+        }))
+        .await;
+    client
+        .send(RemoteAPIMsg::Data(DataMsg::PublisherDelete {
+            id,
+            attachment: Some(b64("unit=C")),
+            timestamp: Some(timestamp()),
+            source_info: None,
+        }))
+        .await;
+
+    let sample = timeout(TIMEOUT, subscriber.recv_async())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(sample.kind(), SampleKind::Put);
+    assert_eq!(sample.payload().try_to_string().unwrap(), "put");
+    assert_eq!(
+        sample.timestamp(),
+        Some(&Timestamp::try_from(timestamp()).unwrap())
+    );
+    let sample_source_info = SourceInfoWS::from(sample.source_info().unwrap());
+    assert_eq!(sample_source_info.id().zid(), "a1b2");
+    assert_eq!(sample_source_info.id().eid(), 3);
+    assert_eq!(sample_source_info.sn(), 42);
+
+    let sample = timeout(TIMEOUT, subscriber.recv_async())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(sample.kind(), SampleKind::Delete);
+    assert_eq!(
+        sample.attachment().unwrap().try_to_string().unwrap(),
+        "unit=C"
+    );
+    assert_eq!(
+        sample.timestamp(),
+        Some(&Timestamp::try_from(timestamp()).unwrap())
+    );
+    assert!(sample.source_info().is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn transcoded_subscriber() {
+    let harness = Harness::start().await;
+    let mut client = harness.connect().await;
+    client.open_session().await;
+
+    let id = client
+        .declare_subscriber(
+            "test/transcode",
+            SubscriberOptions {
+                transcode_to: Some(EncodingWS::from(&Encoding::APPLICATION_JSON)),
+                ..Default::default()
+            },
+        )
+        .await;
+
+    let value = serde_json::json!({"temperature": 21.5, "unit": "C"});
+    let mut cbor = Vec::new();
+    ciborium::into_writer(&value, &mut cbor).unwrap();
+    harness
+        .session
+        .put("test/transcode", cbor)
+        .encoding(Encoding::APPLICATION_CBOR)
+        .await
+        .unwrap();
+    // Payloads which cannot be transcoded are forwarded untouched
+    harness.session.put("test/transcode", "raw").await.unwrap();
+
+    let sample = client.expect_sample(id).await;
+    assert_eq!(sample.encoding(), &EncodingWS::new(5, None));
+    let payload = sample.value().to_bytes().unwrap();
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(&payload).unwrap(),
+        value
+    );
+    let sample = client.expect_sample(id).await;
+    assert_eq!(sample.encoding(), &EncodingWS::default());
+    assert_eq!(from_b64(sample.value()), "raw");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn compressed_subscriber() {
+    let harness = Harness::start().await;
+    let mut client = harness.connect().await;
+    client.open_session().await;
+
+    let id = client
+        .declare_subscriber(
+            "test/compress",
+            SubscriberOptions {
+                compression: Some(CompressionWS::Zstd),
+                ..Default::default()
+            },
+        )
+        .await;
+
+    let large = "compressible ".repeat(200);
+    harness
+        .session
+        .put("test/compress", large.clone())
+        .encoding(Encoding::TEXT_PLAIN)
+        .await
+        .unwrap();
+    // Payloads below the size threshold are sent uncompressed
+    harness.session.put("test/compress", "small").await.unwrap();
+
+    let sample = client.expect_sample(id).await;
+    assert_eq!(sample.compression(), Some(CompressionWS::Zstd));
+    assert_eq!(sample.encoding(), &EncodingWS::new(4, None));
+    let payload = sample.value().to_bytes().unwrap();
+    assert!(payload.len() < large.len());
+    assert_eq!(
+        zstd::decode_all(payload.as_slice()).unwrap(),
+        large.as_bytes()
+    );
+    let sample = client.expect_sample(id).await;
+    assert_eq!(sample.compression(), None);
+    assert_eq!(from_b64(sample.value()), "small");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn permessage_deflate_is_negotiated() {
+    let harness = Harness::start().await;
+    let _ = harness.connect().await;
+
+    let (_, response) = connect_async(format!("ws://{}", harness.address))
+        .await
+        .unwrap();
+    assert_eq!(response.headers().get(SEC_WEBSOCKET_EXTENSIONS), None);
+
+    // As browsers offer it
+    let mut request = format!("ws://{}", harness.address)
+        .into_client_request()
+        .unwrap();
+    request.headers_mut().insert(
+        SEC_WEBSOCKET_EXTENSIONS,
+        HeaderValue::from_static("permessage-deflate; client_max_window_bits"),
+    );
+    let (ws, response) = connect_async(request).await.unwrap();
+    assert_eq!(
+        response.headers().get(SEC_WEBSOCKET_EXTENSIONS),
+        Some(&HeaderValue::from_static("permessage-deflate"))
+    );
+    // Messages below the size threshold are not deflated
+    let mut client = Client { ws };
+    client.open_session().await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn batched_subscriber() {
+    let harness = Harness::start().await;
+    let mut client = harness.connect().await;
+    client.open_session().await;
+
+    let id = client
+        .declare_subscriber(
+            "test/batch",
+            SubscriberOptions {
+                batch: Some(BatchConfigWS {
+                    max_size: 3,
+                    max_latency: 100,
+                }),
+                ..Default::default()
+            },
+        )
+        .await;
+
+    // A full batch is sent at once, the remaining samples after the maximum latency
+    for i in 0..4 {
+        harness
+            .session
+            .put("test/batch", i.to_string())
+            .await
+            .unwrap();
+    }
+    let mut values = Vec::new();
+    for expected_len in [3, 1] {
+        match client.recv().await {
+            RemoteAPIMsg::Data(DataMsg::Samples(samples, sub_id)) => {
+                assert_eq!(sub_id, id);
+                assert_eq!(samples.len(), expected_len);
+                values.extend(samples.iter().map(|sample| from_b64(sample.value())));
+            }
+            msg => panic!("Expected Samples, got {msg:?}"),
+        }
+    }
+    assert_eq!(values, ["0", "1", "2", "3"]);
+
+    // Undeclaring the subscriber sends its pending samples
+    let id = client
+        .declare_subscriber(
+            "test/batch/undeclare",
+            SubscriberOptions {
+                batch: Some(BatchConfigWS {
+                    max_size: 16,
+                    max_latency: 60_000,
+                }),
+                ..Default::default()
+            },
+        )
+        .await;
+    harness
+        .session
+        .put("test/batch/undeclare", "pending")
+        .await
+        .unwrap();
+    client.sync().await;
+    client
+        .send(RemoteAPIMsg::Control(ControlMsg::UndeclareSubscriber(id)))
+        .await;
+    match client.recv().await {
+        RemoteAPIMsg::Data(DataMsg::Samples(samples, sub_id)) => {
+            assert_eq!(sub_id, id);
+            assert_eq!(samples.len(), 1);
+            assert_eq!(from_b64(samples[0].value()), "pending");
+        }
+        msg => panic!("Expected Samples, got {msg:?}"),
+    }
+
+    // Batches must hold at least one sample
+    let options = SubscriberOptions {
+        batch: Some(BatchConfigWS {
+            max_size: 0,
+            max_latency: 100,
+        }),
+        ..Default::default()
+    };
+    client
+        .send(options.declare("test/batch", Uuid::new_v4()))
+        .await;
+    client.sync().await;
+    assert_eq!(
+        harness
+            .state_map
+            .read()
+            .await
+            .values()
+            .next()
+            .unwrap()
+            .subscribers
+            .len(),
+        1
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn throttled_subscriber() {
+    let harness = Harness::start().await;
+    let mut client = harness.connect().await;
+    client.open_session().await;
+
+    let id = client
+        .declare_subscriber(
+            "test/throttle/*",
+            SubscriberOptions {
+                throttle: Some(ThrottleWS::KeepLatest { period: 100 }),
+                ..Default::default()
+            },
+        )
+        .await;
+
+    // Only the latest sample of each key expression is sent
+    for (key_expr, value) in [
+        ("test/throttle/a", "1"),
+        ("test/throttle/b", "1"),
+        ("test/throttle/a", "2"),
+    ] {
+        harness.session.put(key_expr, value).await.unwrap();
+    }
+    for (key_expr, value) in [("test/throttle/a", "2"), ("test/throttle/b", "1")] {
+        let sample = client.expect_sample(id).await;
+        assert_eq!(sample.key_expr().as_str(), key_expr);
+        assert_eq!(from_b64(sample.value()), value);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn undeclare_busy_subscriber() {
+    let harness = Harness::start().await;
+    let mut client = harness.connect().await;
+    client.open_session().await;
+
+    let id = client
+        .declare_subscriber(
+            "test/busy",
+            SubscriberOptions {
+                throttle: Some(ThrottleWS::KeepLatest { period: 100 }),
+                ..Default::default()
+            },
+        )
+        .await;
+    let publisher = harness
+        .session
+        .declare_publisher("test/busy")
+        .await
+        .unwrap();
+    assert!(publisher.matching_status().await.unwrap().matching());
+
+    // The subscriber keeps receiving samples while it is undeclared
+    let session = harness.session.clone();
+    let putting = tokio::spawn(async move {
+        loop {
+            session.put("test/busy", "busy").await.unwrap();
+            tokio::task::yield_now().await;
+        }
+    });
+    client
+        .send(RemoteAPIMsg::Control(ControlMsg::UndeclareSubscriber(id)))
+        .await;
+    timeout(TIMEOUT, async {
+        while publisher.matching_status().await.unwrap().matching() {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("Busy subscriber not undeclared");
+    putting.abort();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn filtered_subscriber() {
+    let harness = Harness::start().await;
+    let mut client = harness.connect().await;
+    client.open_session().await;
+
+    let id = client
+        .declare_subscriber(
+            "test/filter",
+            SubscriberOptions {
+                filters: Some(vec![
+                    SampleFilterWS::Kind(SampleKindWS::Put),
+                    SampleFilterWS::Json {
+                        path: "$.temperature".into(),
+                        predicate: JsonPredicateWS::Gt(30.0),
+                    },
+                ]),
+                ..Default::default()
+            },
+        )
+        .await;
+
+    for temperature in [21, 35] {
+        harness
+            .session
+            .put(
+                "test/filter",
+                format!(r#"{{"temperature": {temperature}}}"#),
+            )
+            .encoding(Encoding::APPLICATION_JSON)
+            .await
+            .unwrap();
+    }
+    harness.session.delete("test/filter").await.unwrap();
+    harness
+        .session
+        .put("test/filter", r#"{"temperature": 40}"#)
+        .encoding(Encoding::APPLICATION_JSON)
+        .await
+        .unwrap();
+
+    // Only the puts above 30 degrees are received
+    for expected in [r#"{"temperature": 35}"#, r#"{"temperature": 40}"#] {
+        let sample = client.expect_sample(id).await;
+        assert_eq!(from_b64(sample.value()), expected);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn advanced_subscriber() {
+    let harness = Harness::start().await;
+    let mut client = harness.connect().await;
+    client.open_session().await;
+
+    // Cached samples of advanced publishers are retrieved as history
+    let publisher = harness
+        .session
+        .declare_publisher("test/advanced")
+        .cache(CacheConfig::default().max_samples(1))
+        .publisher_detection()
+        .await
+        .unwrap();
+    publisher.put("cached").await.unwrap();
+
+    let id = Uuid::new_v4();
+    client
+        .send(RemoteAPIMsg::Control(
+            ControlMsg::DeclareAdvancedSubscriber {
+                key_expr: ke("test/advanced"),
+                handler: HandlerChannel::Fifo(16),
+                id,
+                history: Some(HistoryConfigWS {
+                    detect_late_publishers: None,
+                    max_samples: None,
+                    max_age: Some(60_000),
+                }),
+                recovery: None,
+                subscriber_detection: None,
+            },
+        ))
+        .await;
+    match client.recv().await {
+        RemoteAPIMsg::Control(ControlMsg::Subscriber(sub_id)) => assert_eq!(sub_id, id),
+        msg => panic!("Expected Subscriber, got {msg:?}"),
+    }
+    let sample = client.expect_sample(id).await;
+    assert_eq!(from_b64(sample.value()), "cached");
+
+    publisher.put("live").await.unwrap();
+    let sample = client.expect_sample(id).await;
+    assert_eq!(sample.key_expr().as_str(), "test/advanced");
+    assert_eq!(from_b64(sample.value()), "live");
+
+    client
+        .send(RemoteAPIMsg::Control(
+            ControlMsg::UndeclareAdvancedSubscriber(id),
+        ))
+        .await;
+    client.sync().await;
+    assert!(harness
+        .state_map
+        .read()
+        .await
+        .values()
+        .all(|state| state.advanced_subscribers.is_empty()));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn matching_listener() {
+    let harness = Harness::start().await;
+    let mut client = harness.connect().await;
+    client.open_session().await;
+
+    let publisher_id = Uuid::new_v4();
+    client
+        .send(RemoteAPIMsg::Control(ControlMsg::DeclarePublisher {
+            key_expr: ke("test/matching/publisher"),
+            encoding: None,
+            congestion_control: None,
+            priority: None,
+            reliability: None,
+            express: None,
+            id: publisher_id,
+        }))
+        .await;
+    let advanced_publisher_id = Uuid::new_v4();
+    client
+        .send(RemoteAPIMsg::Control(
+            ControlMsg::DeclareAdvancedPublisher {
+                key_expr: ke("test/matching/advanced"),
+                encoding: None,
+                congestion_control: None,
+                priority: None,
+                reliability: None,
+                express: None,
+                cache: None,
+                sample_miss_detection: None,
+                publisher_detection: None,
+                id: advanced_publisher_id,
+            },
+        ))
+        .await;
+
+    // The status of each publisher flips when a subscriber appears, then disappears
+    for (key_expr, publisher_id) in [
+        ("test/matching/publisher", publisher_id),
+        ("test/matching/advanced", advanced_publisher_id),
+    ] {
+        let id = Uuid::new_v4();
+        client
+            .send(RemoteAPIMsg::Control(ControlMsg::DeclareMatchingListener {
+                id,
+                publisher_id,
+            }))
+            .await;
+        client.sync().await;
+
+        let subscriber = harness.session.declare_subscriber(key_expr).await.unwrap();
+        assert!(client.expect_matching_status(id).await);
+        subscriber.undeclare().await.unwrap();
+        assert!(!client.expect_matching_status(id).await);
+    }
+
+    // The status of a querier flips when a queryable appears, which then answers its queries
+    let querier_id = Uuid::new_v4();
+    client
+        .send(RemoteAPIMsg::Control(ControlMsg::DeclareQuerier {
+            key_expr: ke("test/matching/querier"),
+            id: querier_id,
+            consolidation: None,
+            congestion_control: None,
+            priority: None,
+            express: None,
+            timeout: Some(TIMEOUT.as_millis() as u64),
+        }))
+        .await;
+    let id = Uuid::new_v4();
+    client
+        .send(RemoteAPIMsg::Control(ControlMsg::DeclareMatchingListener {
+            id,
+            publisher_id: querier_id,
+        }))
+        .await;
+    client.sync().await;
+
+    let queryable = harness
+        .session
+        .declare_queryable("test/matching/querier")
+        .callback(|query| {
+            query
+                .reply(query.key_expr().clone(), "from zenoh")
+                .wait()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    assert!(client.expect_matching_status(id).await);
+
+    let get_id = Uuid::new_v4();
+    client
+        .send(RemoteAPIMsg::Control(ControlMsg::QuerierGet {
+            querier_id,
+            id: get_id,
+            parameters: None,
+            handler: HandlerChannel::Fifo(16),
+            encoding: None,
+            payload: None,
+            attachment: None,
+        }))
+        .await;
+    match client.recv().await {
+        RemoteAPIMsg::Data(DataMsg::GetReply(reply)) => {
+            assert_eq!(reply.query_uuid(), get_id);
+            let sample = reply.into_result().unwrap();
+            assert_eq!(from_b64(sample.value()), "from zenoh");
+        }
+        msg => panic!("Expected GetReply, got {msg:?}"),
+    }
+    assert!(matches!(
+        client.recv().await,
+        RemoteAPIMsg::Control(ControlMsg::GetFinished { id }) if id == get_id
+    ));
+
+    queryable.undeclare().await.unwrap();
+    assert!(!client.expect_matching_status(id).await);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn pending_querier_get_does_not_block_other_clients() {
+    let harness = Harness::start().await;
+    let mut client = harness.connect().await;
+    client.open_session().await;
+
+    let queryable = harness
+        .session
+        .declare_queryable("test/querier/pending")
+        .await
+        .unwrap();
+    let querier_id = Uuid::new_v4();
+    client
+        .send(RemoteAPIMsg::Control(ControlMsg::DeclareQuerier {
+            key_expr: ke("test/querier/pending"),
+            id: querier_id,
+            consolidation: None,
+            congestion_control: None,
+            priority: None,
+            express: None,
+            timeout: Some(TIMEOUT.as_millis() as u64),
+        }))
+        .await;
+    let get_id = Uuid::new_v4();
+    client
+        .send(RemoteAPIMsg::Control(ControlMsg::QuerierGet {
+            querier_id,
+            id: get_id,
+            parameters: None,
+            handler: HandlerChannel::Fifo(16),
+            encoding: None,
+            payload: None,
+            attachment: None,
+        }))
+        .await;
+    let query = timeout(TIMEOUT, queryable.recv_async())
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Another client is served while the query is unanswered
+    let mut other_client = harness.connect().await;
+    timeout(TIMEOUT / 2, other_client.open_session())
+        .await
+        .expect("Blocked by the pending querier get");
+
+    query
+        .reply("test/querier/pending", "from zenoh")
+        .await
+        .unwrap();
+    drop(query);
+    match client.recv().await {
+        RemoteAPIMsg::Data(DataMsg::GetReply(reply)) => {
+            assert_eq!(reply.query_uuid(), get_id);
+            let sample = reply.into_result().unwrap();
+            assert_eq!(from_b64(sample.value()), "from zenoh");
+        }
+        msg => panic!("Expected GetReply, got {msg:?}"),
+    }
+    assert!(matches!(
+        client.recv().await,
+        RemoteAPIMsg::Control(ControlMsg::GetFinished { id }) if id == get_id
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_and_queryable() {
+    let harness = Harness::start().await;
+    let mut client = harness.connect().await;
+    client.open_session().await;
+
+    // Client queryable, queried from zenoh
+    let queryable_id = Uuid::new_v4();
+    client
+        .send(RemoteAPIMsg::Control(ControlMsg::DeclareQueryable {
+            key_expr: ke("test/client_queryable"),
+            id: queryable_id,
+            complete: true,
+        }))
+        .await;
+    client.sync().await;
+
+    let replies = harness.session.get("test/client_queryable").await.unwrap();
+    let query = match client.recv().await {
+        RemoteAPIMsg::Data(DataMsg::Queryable(QueryableMsg::Query {
+            queryable_uuid,
+            query,
+        })) => {
+            assert_eq!(queryable_uuid, queryable_id);
+            query
+        }
+        msg => panic!("Expected Query, got {msg:?}"),
+    };
+    client
+        .send(RemoteAPIMsg::Data(DataMsg::Queryable(
+            QueryableMsg::Reply {
+                reply: QueryReplyWS::new(
+                    query.query_uuid(),
+                    QueryReplyVariant::Reply {
+                        key_expr: query.key_expr().clone(),
+                        payload: b64("from client"),
+                    },
+                ),
+            },
+        )))
+        .await;
+    let reply = timeout(TIMEOUT, replies.recv_async())
+        .await
+        .unwrap()
+        .unwrap();
+    let sample = reply.result().unwrap();
+    assert_eq!(sample.key_expr().as_str(), "test/client_queryable");
+    assert_eq!(sample.payload().try_to_string().unwrap(), "from client");
+
+    // Zenoh queryable, queried from the client
+    let queryable = harness
+        .session
+        .declare_queryable("test/zenoh_queryable")
+        .await
+        .unwrap();
+    tokio::spawn(async move {
+        while let Ok(query) = queryable.recv_async().await {
+            let key_expr = query.key_expr().clone();
+            query.reply(key_expr, "from zenoh").await.unwrap();
+        }
+    });
+
+    let get_id = Uuid::new_v4();
+    client
+        .send(RemoteAPIMsg::Control(ControlMsg::Get {
+            key_expr: ke("test/zenoh_queryable"),
+            parameters: None,
+            handler: HandlerChannel::Fifo(16),
+            id: get_id,
+            consolidation: None,
+            congestion_control: None,
+            priority: None,
+            express: None,
+            encoding: None,
+            payload: None,
+            attachment: None,
+        }))
+        .await;
+    match client.recv().await {
+        RemoteAPIMsg::Data(DataMsg::GetReply(reply)) => {
+            assert_eq!(reply.query_uuid(), get_id);
+            let sample = reply.into_result().unwrap();
+            assert_eq!(sample.key_expr().as_str(), "test/zenoh_queryable");
+            assert_eq!(from_b64(sample.value()), "from zenoh");
+        }
+        msg => panic!("Expected GetReply, got {msg:?}"),
+    }
+    assert!(matches!(
+        client.recv().await,
+        RemoteAPIMsg::Control(ControlMsg::GetFinished { id }) if id == get_id
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn liveliness() {
+    let harness = Harness::start().await;
+    let mut client = harness.connect().await;
+    client.open_session().await;
+
+    client
+        .send(RemoteAPIMsg::Control(ControlMsg::Liveliness(
+            LivelinessMsg::DeclareToken {
+                key_expr: ke("test/liveliness/client"),
+                id: Uuid::new_v4(),
+            },
+        )))
+        .await;
+    client.sync().await;
+
+    let replies = harness
+        .session
+        .liveliness()
+        .get("test/liveliness/**")
+        .timeout(TIMEOUT)
+        .await
+        .unwrap();
+    let reply = timeout(TIMEOUT, replies.recv_async())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        reply.result().unwrap().key_expr().as_str(),
+        "test/liveliness/client"
+    );
+
+    // Liveliness tokens of zenoh are seen by the client
+    let token = harness
+        .session
+        .liveliness()
+        .declare_token("test/liveliness/zenoh")
+        .await
+        .unwrap();
+    let subscriber_id = Uuid::new_v4();
+    client
+        .send(RemoteAPIMsg::Control(ControlMsg::Liveliness(
+            LivelinessMsg::DeclareSubscriber {
+                key_expr: ke("test/liveliness/zenoh"),
+                id: subscriber_id,
+                history: true,
+            },
+        )))
+        .await;
+    let sample = client.expect_sample(subscriber_id).await;
+    assert_eq!(sample.key_expr().as_str(), "test/liveliness/zenoh");
+    token.undeclare().await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn cleanup_on_disconnect() {
+    let harness = Harness::start().await;
+    let liveliness_subscriber = harness
+        .session
+        .liveliness()
+        .declare_subscriber("test/cleanup/**")
+        .await
+        .unwrap();
+
+    let mut client = harness.connect().await;
+    client.open_session().await;
+    client
+        .send(RemoteAPIMsg::Control(ControlMsg::Liveliness(
+            LivelinessMsg::DeclareToken {
+                key_expr: ke("test/cleanup/client"),
+                id: Uuid::new_v4(),
+            },
+        )))
+        .await;
+    client
+        .declare_subscriber("test/cleanup/sub", SubscriberOptions::default())
+        .await;
+    harness.wait_for_clients(1).await;
+
+    let publisher = harness
+        .session
+        .declare_publisher("test/cleanup/sub")
+        .await
+        .unwrap();
+    assert!(publisher.matching_status().await.unwrap().matching());
+    let sample = timeout(TIMEOUT, liveliness_subscriber.recv_async())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(sample.kind(), SampleKind::Put);
+
+    // Closing the WebSocket undeclares everything the client declared
+    client.ws.close(None).await.unwrap();
+    harness.wait_for_clients(0).await;
+
+    let sample = timeout(TIMEOUT, liveliness_subscriber.recv_async())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(sample.kind(), SampleKind::Delete);
+    assert_eq!(sample.key_expr().as_str(), "test/cleanup/client");
+
+    // Subscribers are undeclared asynchronously
+    timeout(TIMEOUT, async {
+        while publisher.matching_status().await.unwrap().matching() {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("Subscriber of the client not undeclared");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn failed_handshakes_are_not_accepted_connections() {
+    let harness = Harness::start().await;
+    let mut client = harness.connect().await;
+    client.sync().await;
+
+    // A plain TCP client which never completes the WebSocket handshake
+    let mut tcp_stream = TcpStream::connect(&harness.address).await.unwrap();
+    tcp_stream
+        .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .await
+        .unwrap();
+    tcp_stream.shutdown().await.unwrap();
+
+    timeout(TIMEOUT, async {
+        loop {
+            let stats = serde_json::to_value(harness.metrics.stats()).unwrap();
+            if stats["handshakes_failed"] == 1 {
+                assert_eq!(stats["connections_total"], 1);
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("Failed handshake not counted");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn stats_and_admin_space_share_entity_types() {
+    let harness = Harness::start().await;
+    let mut client = harness.connect().await;
+    client
+        .declare_subscriber("test/entities/sub", SubscriberOptions::default())
+        .await;
+
+    let read_guard = harness.state_map.read().await;
+    let (sock_addr, remote_state) = read_guard.iter().next().unwrap();
+    let counts: HashMap<&str, usize> = remote_state.entity_counts().into_iter().collect();
+    let admin_client = AdminSpaceClient::from((sock_addr, remote_state));
+
+    assert_eq!(counts.len(), admin_client.entities.len());
+    for (entity_type, entities) in admin_client.entities.iter() {
+        assert_eq!(counts[entity_type], entities.len(), "{entity_type}");
+    }
+    assert_eq!(counts["subscribers"], 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn admin_space_publishers_have_reliability() {
+    let harness = Harness::start().await;
+    let mut client = harness.connect().await;
+    client
+        .send(RemoteAPIMsg::Control(ControlMsg::DeclarePublisher {
+            key_expr: ke("test/entities/publisher"),
+            encoding: None,
+            congestion_control: None,
+            priority: None,
+            reliability: Some(Reliability::BestEffort),
+            express: None,
+            id: Uuid::new_v4(),
+        }))
+        .await;
+    client
+        .send(RemoteAPIMsg::Control(
+            ControlMsg::DeclareAdvancedPublisher {
+                key_expr: ke("test/entities/advanced"),
+                encoding: None,
+                congestion_control: None,
+                priority: None,
+                reliability: Some(Reliability::BestEffort),
+                express: None,
+                cache: None,
+                sample_miss_detection: None,
+                publisher_detection: None,
+                id: Uuid::new_v4(),
+            },
+        ))
+        .await;
+    client.sync().await;
+
+    let read_guard = harness.state_map.read().await;
+    let remote_state = read_guard.values().next().unwrap();
+    let publishers: Vec<_> = remote_state
+        .admin_entities()
+        .into_iter()
+        .filter(|(entity_type, _, _)| {
+            matches!(
+                entity_type,
+                EntityType::Publishers | EntityType::AdvancedPublishers
+            )
+        })
+        .collect();
+    assert_eq!(publishers.len(), 2);
+    for (entity_type, _, entity) in publishers {
+        assert_eq!(
+            entity.reliability.as_deref(),
+            Some("BestEffort"),
+            "{}",
+            entity_type.as_str()
+        );
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn admin_space_control() {
+    let harness = Harness::start().await;
+    let mut client = harness.connect().await;
+    let client_id = client.open_session().await;
+    let sub_id = client
+        .declare_subscriber("test/admin/sub", SubscriberOptions::default())
+        .await;
+
+    let publisher = harness
+        .session
+        .declare_publisher("test/admin/sub")
+        .await
+        .unwrap();
+    assert!(publisher.matching_status().await.unwrap().matching());
+
+    // Wait for the admin space subscriber before putting on its key expressions
+    let admin_client_ke = format!(
+        "@/{}/remote-plugin/clients/{client_id}",
+        harness.session.zid()
+    );
+    let admin_publisher = harness
+        .session
+        .declare_publisher(format!("{admin_client_ke}/subscribers/{sub_id}"))
+        .await
+        .unwrap();
+    timeout(TIMEOUT, async {
+        while !admin_publisher.matching_status().await.unwrap().matching() {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("Admin space subscriber not declared");
+
+    // A put on an entity undeclares it
+    admin_publisher.put("").await.unwrap();
+    timeout(TIMEOUT, async {
+        while publisher.matching_status().await.unwrap().matching() {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("Subscriber not undeclared from the admin space");
+    assert_eq!(harness.state_map.read().await.len(), 1);
+
+    // A put on the client disconnects it
+    harness.session.put(admin_client_ke, "").await.unwrap();
+    let msg = timeout(TIMEOUT, client.ws.next())
+        .await
+        .expect("Client not disconnected")
+        .expect("WebSocket closed without a close frame")
+        .unwrap();
+    match msg {
+        Message::Close(Some(close_frame)) => assert_eq!(close_frame.code, CloseCode::Policy),
+        msg => panic!("Expected Close, got {msg:?}"),
+    }
+    harness.wait_for_clients(0).await;
+}
+
+#[tokio::test]
+async fn config_changes_are_applied_once_notified() {
+    let config = |port: &str| serde_json::json!({ "websocket_port": port });
+    let live_config = LiveConfig::load(serde_json::from_value(config("127.0.0.1:1")).unwrap());
+    let (config_tx, mut config_rx) = tokio::sync::watch::channel(live_config.unwrap());
+    let config_tx = Arc::new(config_tx);
+    let (notifications_tx, notifications_rx) = flume::unbounded::<Arc<str>>();
+    let task = tokio::spawn(apply_config_changes(
+        notifications_rx,
+        "plugins/remote_api".to_string(),
+        move || Some(config("127.0.0.1:2")),
+        Arc::downgrade(&config_tx),
+    ));
+
+    // Changes of other plugins are ignored
+    notifications_tx
+        .send("plugins/other/websocket_port".into())
+        .unwrap();
+    notifications_tx
+        .send("plugins/remote_api/websocket_port".into())
+        .unwrap();
+    timeout(TIMEOUT, config_rx.changed())
+        .await
+        .expect("Configuration change not applied")
+        .unwrap();
+    assert_eq!(config_rx.borrow().config.websocket_port, "127.0.0.1:2");
+
+    // Once the plugin is stopped, changes are not applied anymore
+    drop(config_tx);
+    notifications_tx.send("plugins/remote_api".into()).unwrap();
+    timeout(TIMEOUT, task)
+        .await
+        .expect("Configuration changes still applied")
+        .unwrap();
+}
+
+#[test]
+fn invalid_settings_are_rejected() {
+    let load = |settings: serde_json::Value| {
+        let mut config = settings;
+        config["websocket_port"] = "127.0.0.1:1".into();
+        LiveConfig::load(serde_json::from_value(config).unwrap())
+    };
+    assert!(load(serde_json::json!({ "acl": { "deny": ["test//acl"] } })).is_err());
+    assert!(load(serde_json::json!({ "log_level": "test=nope" })).is_err());
+    assert!(load(serde_json::json!({ "log_level": "test=debug" })).is_ok());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn access_control_is_applied_live() {
+    let harness = Harness::start().await;
+    let mut client = harness.connect().await;
+    client.open_session().await;
+    client
+        .declare_subscriber("test/acl/secret", SubscriberOptions::default())
+        .await;
+
+    harness.reconfigure(serde_json::json!({ "acl": { "deny": ["test/acl/secret/**"] } }));
+    client
+        .send(SubscriberOptions::default().declare("test/acl/secret/key", Uuid::new_v4()))
+        .await;
+    match client.recv().await {
+        RemoteAPIMsg::Control(ControlMsg::Error(err)) => {
+            assert!(err.contains("test/acl/secret/key"), "{err}")
+        }
+        msg => panic!("Expected Error, got {msg:?}"),
+    }
+
+    // Other key expressions are still allowed, and the declared subscribers kept
+    client
+        .declare_subscriber("test/acl/public", SubscriberOptions::default())
+        .await;
+    let state_map = harness.state_map.read().await;
+    assert_eq!(state_map.values().next().unwrap().subscribers.len(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn connection_limits_are_applied_live() {
+    let harness = Harness::start().await;
+    let mut client = harness.connect().await;
+    client.sync().await;
+    let url = format!("ws://{}", harness.address);
+    let refused = |connected: Result<_, tokio_tungstenite::tungstenite::Error>| match connected {
+        Err(tokio_tungstenite::tungstenite::Error::Http(response)) => response.status(),
+        Err(err) => panic!("Unexpected error {err}"),
+        Ok(_) => panic!("Connection accepted"),
+    };
+
+    harness.reconfigure(serde_json::json!({ "max_connections": 1 }));
+    assert_eq!(
+        refused(connect_async(url.as_str()).await),
+        StatusCode::SERVICE_UNAVAILABLE
+    );
+    // The connected client is kept
+    client.sync().await;
+
+    harness.reconfigure(serde_json::json!({ "allowed_origins": ["https://example.com"] }));
+    let from = |origin: &'static str| {
+        let mut request = url.as_str().into_client_request().unwrap();
+        request
+            .headers_mut()
+            .insert("origin", HeaderValue::from_static(origin));
+        request
+    };
+    assert_eq!(
+        refused(connect_async(from("https://other.com")).await),
+        StatusCode::FORBIDDEN
+    );
+    let _allowed = connect_async(from("https://example.com")).await.unwrap();
+    // Clients which are not browsers send no origin
+    let _not_a_browser = connect_async(url.as_str()).await.unwrap();
+    harness.wait_for_clients(3).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn shutdown_drains_clients() {
+    let live_config = loopback_config();
+    let address = live_config.config.websocket_port.clone();
+    let (_config_tx, config_rx) = tokio::sync::watch::channel(live_config);
+    let shutdown = Arc::new(tokio::sync::Notify::new());
+    let remote_api_runtime =
+        RemoteAPIRuntime::new(isolated_runtime().await, config_rx, shutdown.clone());
+    let state_map = remote_api_runtime.state_map.clone();
+    let running = tokio::spawn(remote_api_runtime.run());
+
+    let mut client = connect(&address).await;
+    client.sync().await;
+    assert_eq!(state_map.read().await.len(), 1);
+
+    // Every client is sent a close frame with the reason of the disconnection
+    shutdown.notify_one();
+    let msg = timeout(TIMEOUT, client.ws.next())
+        .await
+        .expect("Client not disconnected")
+        .expect("WebSocket closed without a close frame")
+        .unwrap();
+    match msg {
+        Message::Close(Some(close_frame)) => {
+            assert_eq!(close_frame.code, CloseCode::Away);
+            assert_eq!(close_frame.reason, "Remote API plugin is shutting down");
+        }
+        msg => panic!("Expected Close, got {msg:?}"),
+    }
+
+    // The clients are cleaned up, and new connections are refused
+    timeout(TIMEOUT, running)
+        .await
+        .expect("Drain did not complete")
+        .unwrap()
+        .unwrap();
+    assert!(state_map.read().await.is_empty());
+    assert!(connect_async(format!("ws://{address}")).await.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn unavailable_websocket_port() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    let config: Config =
+        serde_json::from_value(serde_json::json!({ "websocket_port": address })).unwrap();
+
+    let result = timeout(TIMEOUT, crate::run(isolated_runtime().await, config, None))
+        .await
+        .expect("Server kept running without a listener");
+    assert!(result.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn rust_client_put_and_subscribe() {
+    let harness = Harness::start().await;
+    let client = harness.connect_rust_client().await;
+
+    let subscriber = client
+        .declare_subscriber("test/client/sub", None)
+        .await
+        .unwrap();
+    harness
+        .session
+        .put("test/client/sub", "from zenoh")
+        .await
+        .unwrap();
+    let sample = timeout(TIMEOUT, subscriber.recv_async())
+        .await
+        .expect("No sample received")
+        .unwrap();
+    assert_eq!(sample.key_expr().as_str(), "test/client/sub");
+    assert_eq!(from_b64(sample.value()), "from zenoh");
+    assert_eq!(sample.compression(), None);
+
+    let compressed_subscriber = client
+        .declare_subscriber("test/client/compress", Some(CompressionWS::Lz4))
+        .await
+        .unwrap();
+    let large = "compressible ".repeat(200);
+    harness
+        .session
+        .put("test/client/compress", large.clone())
+        .await
+        .unwrap();
+    let sample = timeout(TIMEOUT, compressed_subscriber.recv_async())
+        .await
+        .expect("No sample received")
+        .unwrap();
+    assert_eq!(sample.compression(), Some(CompressionWS::Lz4));
+    let payload = sample.value().to_bytes().unwrap();
+    assert_eq!(
+        lz4_flex::decompress_size_prepended(&payload).unwrap(),
+        large.as_bytes()
+    );
+
+    let zenoh_subscriber = harness
+        .session
+        .declare_subscriber("test/client/put/**")
+        .await
+        .unwrap();
+    client
+        .put("test/client/put/session", "from client")
+        .unwrap();
+    let publisher = client
+        .declare_publisher("test/client/put/publisher")
+        .unwrap();
+    publisher.put("from publisher").unwrap();
+    publisher.delete().unwrap();
+
+    for (key_expr, kind, payload) in [
+        ("test/client/put/session", SampleKind::Put, "from client"),
+        (
+            "test/client/put/publisher",
+            SampleKind::Put,
+            "from publisher",
+        ),
+        ("test/client/put/publisher", SampleKind::Delete, ""),
+    ] {
+        let sample = timeout(TIMEOUT, zenoh_subscriber.recv_async())
+            .await
+            .expect("No sample received")
+            .unwrap();
+        assert_eq!(sample.key_expr().as_str(), key_expr);
+        assert_eq!(sample.kind(), kind);
+        assert_eq!(sample.payload().try_to_string().unwrap(), payload);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn rust_client_get_and_queryable() {
+    let harness = Harness::start().await;
+    let client = harness.connect_rust_client().await;
+
+    let _zenoh_queryable = harness
+        .session
+        .declare_queryable("test/client/zenoh")
+        .callback(|query| {
+            query
+                .reply("test/client/zenoh", "from zenoh")
+                .wait()
+                .unwrap();
+        })
+        .await
+        .unwrap();
+    let replies = client.get("test/client/zenoh", None).unwrap();
+    let reply = timeout(TIMEOUT, replies.recv_async())
+        .await
+        .expect("No reply received")
+        .unwrap();
+    assert_eq!(from_b64(reply.result().unwrap().value()), "from zenoh");
+    // The channel of replies is closed once the Get is finished
+    assert!(timeout(TIMEOUT, replies.recv_async())
+        .await
+        .expect("Get not finished")
+        .is_err());
+
+    let queryable = client
+        .declare_queryable("test/client/queryable", true)
+        .unwrap();
+    let querier = harness
+        .session
+        .declare_querier("test/client/queryable")
+        .await
+        .unwrap();
+    timeout(TIMEOUT, async {
+        while !querier.matching_status().await.unwrap().matching() {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("Queryable of the client not declared");
+
+    let zenoh_replies = querier.get().await.unwrap();
+    let query = timeout(TIMEOUT, queryable.recv_async())
+        .await
+        .expect("No query received")
+        .unwrap();
+    assert_eq!(query.query().key_expr().as_str(), "test/client/queryable");
+    query.reply("test/client/queryable", "from client").unwrap();
+    let reply = timeout(TIMEOUT, zenoh_replies.recv_async())
+        .await
+        .expect("No reply received")
+        .unwrap();
+    let sample = reply.result().unwrap();
+    assert_eq!(sample.payload().try_to_string().unwrap(), "from client");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn rust_client_liveliness() {
+    let harness = Harness::start().await;
+    let client = harness.connect_rust_client().await;
+
+    let _token = client
+        .liveliness()
+        .declare_token("test/client/liveliness/client")
+        .unwrap();
+    let zenoh_token = harness
+        .session
+        .liveliness()
+        .declare_token("test/client/liveliness/zenoh")
+        .await
+        .unwrap();
+
+    // With history, the existing tokens are received
+    let subscriber = client
+        .liveliness()
+        .declare_subscriber("test/client/liveliness/zenoh", true)
+        .unwrap();
+    let sample = timeout(TIMEOUT, subscriber.recv_async())
+        .await
+        .expect("No liveliness sample received")
+        .unwrap();
+    assert_eq!(sample.key_expr().as_str(), "test/client/liveliness/zenoh");
+    assert_eq!(sample.kind(), SampleKindWS::Put);
+
+    zenoh_token.undeclare().await.unwrap();
+    let sample = timeout(TIMEOUT, subscriber.recv_async())
+        .await
+        .expect("No liveliness sample received")
+        .unwrap();
+    assert_eq!(sample.kind(), SampleKindWS::Delete);
+
+    // The token of the client is declared by the time the replies are received
+    let replies = client
+        .liveliness()
+        .get("test/client/liveliness/client", None)
+        .unwrap();
+    let reply = timeout(TIMEOUT, replies.recv_async())
+        .await
+        .expect("No liveliness reply received")
+        .unwrap();
+    assert_eq!(
+        reply.result().unwrap().key_expr().as_str(),
+        "test/client/liveliness/client"
+    );
+}
@@ -0,0 +1,224 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{collections::HashMap, mem, time::Duration};
+
+use tokio::time::Instant;
+use zenoh::{key_expr::KeyExpr, sample::Sample};
+use zenoh_remote_api_protocol::ThrottleWS;
+use zenoh_result::{bail, ZResult};
+
+/// Limits the rate of the samples forwarded to a subscriber
+#[derive(Debug)]
+pub(crate) enum Throttle {
+    MaxFrequency {
+        period: Duration,
+        last_sent: HashMap<KeyExpr<'static>, Instant>,
+    },
+    KeepLatest {
+        period: Duration,
+        // Latest sample of each key expression, in the order of their first sample
+        latest: Vec<Sample>,
+        positions: HashMap<KeyExpr<'static>, usize>,
+        deadline: Option<Instant>,
+    },
+    Window {
+        period: Duration,
+        latest: Option<Sample>,
+        deadline: Option<Instant>,
+    },
+}
+
+impl Throttle {
+    pub(crate) fn new(throttle: ThrottleWS) -> ZResult<Self> {
+        let (ThrottleWS::MaxFrequency { period }
+        | ThrottleWS::KeepLatest { period }
+        | ThrottleWS::Window { period }) = throttle;
+        if period == 0 {
+            bail!("Throttling period of {throttle:?} must not be zero");
+        }
+        let period = Duration::from_millis(period);
+        Ok(match throttle {
+            ThrottleWS::MaxFrequency { .. } => Throttle::MaxFrequency {
+                period,
+                last_sent: HashMap::new(),
+            },
+            ThrottleWS::KeepLatest { .. } => Throttle::KeepLatest {
+                period,
+                latest: Vec::new(),
+                positions: HashMap::new(),
+                deadline: None,
+            },
+            ThrottleWS::Window { .. } => Throttle::Window {
+                period,
+                latest: None,
+                deadline: None,
+            },
+        })
+    }
+
+    /// The sample if it is forwarded right away, None if it is dropped or held until the deadline
+    pub(crate) fn push(&mut self, sample: Sample, now: Instant) -> Option<Sample> {
+        match self {
+            Throttle::MaxFrequency { period, last_sent } => {
+                let limited = last_sent
+                    .get(sample.key_expr())
+                    .is_some_and(|last| now < *last + *period);
+                if limited {
+                    return None;
+                }
+                // Forget the key expressions which are no longer limited, so the map stays bounded
+                last_sent.retain(|_, last| now < *last + *period);
+                last_sent.insert(sample.key_expr().clone(), now);
+                Some(sample)
+            }
+            Throttle::KeepLatest {
+                period,
+                latest,
+                positions,
+                deadline,
+            } => {
+                match positions.get(sample.key_expr()) {
+                    Some(&position) => latest[position] = sample,
+                    None => {
+                        positions.insert(sample.key_expr().clone(), latest.len());
+                        latest.push(sample);
+                    }
+                }
+                deadline.get_or_insert(now + *period);
+                None
+            }
+            Throttle::Window {
+                period,
+                latest,
+                deadline,
+            } => {
+                *latest = Some(sample);
+                deadline.get_or_insert(now + *period);
+                None
+            }
+        }
+    }
+
+    /// Time the held samples must be sent at
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        match self {
+            Throttle::MaxFrequency { .. } => None,
+            Throttle::KeepLatest { deadline, .. } | Throttle::Window { deadline, .. } => *deadline,
+        }
+    }
+
+    /// The held samples, if their deadline has passed
+    pub(crate) fn take_due(&mut self, now: Instant) -> Vec<Sample> {
+        match self.deadline() {
+            Some(deadline) if deadline <= now => self.take_all(),
+            _ => Vec::new(),
+        }
+    }
+
+    pub(crate) fn take_all(&mut self) -> Vec<Sample> {
+        match self {
+            Throttle::MaxFrequency { .. } => Vec::new(),
+            Throttle::KeepLatest {
+                latest,
+                positions,
+                deadline,
+                ..
+            } => {
+                positions.clear();
+                *deadline = None;
+                mem::take(latest)
+            }
+            Throttle::Window {
+                latest, deadline, ..
+            } => {
+                *deadline = None;
+                latest.take().into_iter().collect()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zenoh::sample::SampleBuilder;
+
+    use super::*;
+
+    const PERIOD: Duration = Duration::from_millis(100);
+
+    fn sample(key_expr: &'static str, payload: &str) -> Sample {
+        SampleBuilder::put(KeyExpr::try_from(key_expr).unwrap(), payload.to_string()).into()
+    }
+
+    fn payloads(samples: Vec<Sample>) -> Vec<String> {
+        samples
+            .iter()
+            .map(|sample| sample.payload().try_to_string().unwrap().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn zero_period() {
+        assert!(Throttle::new(ThrottleWS::Window { period: 0 }).is_err());
+        assert!(Throttle::new(ThrottleWS::Window { period: 1 }).is_ok());
+    }
+
+    #[test]
+    fn max_frequency() {
+        let mut throttle = Throttle::new(ThrottleWS::MaxFrequency { period: 100 }).unwrap();
+        let now = Instant::now();
+
+        assert!(throttle.push(sample("a", "1"), now).is_some());
+        assert!(throttle.push(sample("b", "1"), now).is_some());
+        assert!(throttle.push(sample("a", "2"), now + PERIOD / 2).is_none());
+        assert!(throttle.push(sample("a", "3"), now + PERIOD).is_some());
+        let Throttle::MaxFrequency { last_sent, .. } = &throttle else {
+            unreachable!()
+        };
+        assert_eq!(last_sent.len(), 1);
+        assert_eq!(throttle.deadline(), None);
+        assert!(throttle.take_all().is_empty());
+    }
+
+    #[test]
+    fn keep_latest() {
+        let mut throttle = Throttle::new(ThrottleWS::KeepLatest { period: 100 }).unwrap();
+        let now = Instant::now();
+
+        for (key_expr, payload) in [("a", "1"), ("b", "1"), ("a", "2"), ("b", "2"), ("c", "1")] {
+            assert!(throttle.push(sample(key_expr, payload), now).is_none());
+        }
+        assert_eq!(throttle.deadline(), Some(now + PERIOD));
+        assert!(throttle.take_due(now + PERIOD / 2).is_empty());
+        assert_eq!(payloads(throttle.take_due(now + PERIOD)), ["2", "2", "1"]);
+        assert_eq!(throttle.deadline(), None);
+
+        assert!(throttle.push(sample("a", "3"), now + PERIOD * 2).is_none());
+        assert_eq!(throttle.deadline(), Some(now + PERIOD * 3));
+        assert_eq!(payloads(throttle.take_all()), ["3"]);
+    }
+
+    #[test]
+    fn window() {
+        let mut throttle = Throttle::new(ThrottleWS::Window { period: 100 }).unwrap();
+        let now = Instant::now();
+
+        assert!(throttle.push(sample("a", "1"), now).is_none());
+        assert!(throttle.push(sample("b", "2"), now + PERIOD / 2).is_none());
+        assert_eq!(throttle.deadline(), Some(now + PERIOD));
+        assert_eq!(payloads(throttle.take_due(now + PERIOD)), ["2"]);
+        assert!(throttle.take_all().is_empty());
+    }
+}
@@ -0,0 +1,202 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use ciborium::Value;
+use tracing::warn;
+use zenoh::{
+    bytes::Encoding,
+    sample::{Sample, SampleKind},
+};
+use zenoh_result::{zerror, ZResult};
+
+// MessagePack has no zenoh id, it is recognised by the names of its custom encodings
+const MESSAGE_PACK_NAMES: [&str; 3] = [
+    "application/msgpack",
+    "application/x-msgpack",
+    "application/vnd.msgpack",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Json,
+    Cbor,
+    MessagePack,
+}
+
+impl Format {
+    // Format of an encoding, whatever its schema. Zenoh keeps the names it does not know,
+    // like the MessagePack ones, as the schema of `zenoh/bytes`
+    fn of(encoding: &Encoding) -> Option<Format> {
+        let encoding = encoding.to_string();
+        let encoding = encoding.strip_prefix("zenoh/bytes;").unwrap_or(&encoding);
+        let name = encoding.split_once(';').map_or(encoding, |(name, _)| name);
+        match name {
+            "application/json" | "text/json" => Some(Format::Json),
+            "application/cbor" => Some(Format::Cbor),
+            name if MESSAGE_PACK_NAMES.contains(&name) => Some(Format::MessagePack),
+            _ => None,
+        }
+    }
+
+    fn decode(self, payload: &[u8]) -> ZResult<Value> {
+        let value = match self {
+            Format::Json => serde_json::from_slice(payload).map_err(|e| zerror!("{e}"))?,
+            Format::Cbor => ciborium::from_reader(payload).map_err(|e| zerror!("{e}"))?,
+            Format::MessagePack => rmp_serde::from_slice(payload).map_err(|e| zerror!("{e}"))?,
+        };
+        Ok(value)
+    }
+
+    fn encode(self, value: &Value) -> ZResult<Vec<u8>> {
+        let payload = match self {
+            Format::Json => serde_json::to_vec(value).map_err(|e| zerror!("{e}"))?,
+            Format::Cbor => {
+                let mut payload = Vec::new();
+                ciborium::into_writer(value, &mut payload).map_err(|e| zerror!("{e}"))?;
+                payload
+            }
+            Format::MessagePack => rmp_serde::to_vec(value).map_err(|e| zerror!("{e}"))?,
+        };
+        Ok(payload)
+    }
+}
+
+/// Converts the payloads of the samples forwarded to a subscriber to the encoding it asked for
+#[derive(Debug, Clone)]
+pub(crate) struct Transcoder {
+    encoding: Encoding,
+    format: Format,
+}
+
+impl Transcoder {
+    pub(crate) fn new(encoding: Encoding) -> ZResult<Self> {
+        let format =
+            Format::of(&encoding).ok_or_else(|| zerror!("Cannot transcode to {encoding}"))?;
+        Ok(Transcoder { encoding, format })
+    }
+
+    // Payload converted from the `from` encoding, None if it is not supported
+    fn transcode(&self, payload: &[u8], from: &Encoding) -> ZResult<Option<Vec<u8>>> {
+        let Some(from_format) = Format::of(from) else {
+            return Ok(None);
+        };
+        if from_format == self.format {
+            return Ok(Some(payload.to_vec()));
+        }
+        let value = from_format.decode(payload)?;
+        self.format.encode(&value).map(Some)
+    }
+
+    pub(crate) fn encoding(&self) -> &Encoding {
+        &self.encoding
+    }
+
+    /// Payload of the sample converted to the encoding of the transcoder, None when the
+    /// sample is forwarded untouched: already in this encoding, in an unsupported one, or
+    /// failing to convert
+    pub(crate) fn transcode_sample(&self, sample: &Sample) -> Option<Vec<u8>> {
+        if sample.kind() == SampleKind::Delete || sample.encoding() == &self.encoding {
+            return None;
+        }
+        match self.transcode(&sample.payload().to_bytes(), sample.encoding()) {
+            Ok(payload) => payload,
+            Err(err) => {
+                warn!(
+                    "Could not transcode sample on {} from {} to {}: {err}",
+                    sample.key_expr(),
+                    sample.encoding(),
+                    self.encoding
+                );
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn msgpack() -> Encoding {
+        Encoding::from("application/msgpack")
+    }
+
+    #[test]
+    fn formats() {
+        assert_eq!(Format::of(&Encoding::APPLICATION_JSON), Some(Format::Json));
+        assert_eq!(
+            Format::of(&Encoding::TEXT_JSON.with_schema("v1")),
+            Some(Format::Json)
+        );
+        assert_eq!(Format::of(&Encoding::APPLICATION_CBOR), Some(Format::Cbor));
+        assert_eq!(Format::of(&msgpack()), Some(Format::MessagePack));
+        assert_eq!(Format::of(&Encoding::APPLICATION_PROTOBUF), None);
+        assert_eq!(Format::of(&Encoding::ZENOH_BYTES), None);
+
+        assert!(Transcoder::new(Encoding::APPLICATION_JSON).is_ok());
+        assert!(Transcoder::new(Encoding::TEXT_PLAIN).is_err());
+    }
+
+    #[test]
+    fn to_json() {
+        let value = json!({"temperature": 21.5, "unit": "C", "samples": [1, 2, 3]});
+        let mut cbor = Vec::new();
+        ciborium::into_writer(&value, &mut cbor).unwrap();
+        let msgpack_payload = rmp_serde::to_vec_named(&value).unwrap();
+
+        let transcoder = Transcoder::new(Encoding::APPLICATION_JSON).unwrap();
+        for (payload, from) in [
+            (cbor, Encoding::APPLICATION_CBOR),
+            (msgpack_payload, msgpack()),
+        ] {
+            let json_payload = transcoder.transcode(&payload, &from).unwrap().unwrap();
+            assert_eq!(
+                serde_json::from_slice::<serde_json::Value>(&json_payload).unwrap(),
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn from_json() {
+        let value = json!({"id": 7, "tags": ["a", "b"], "ok": true});
+        let json_payload = serde_json::to_vec(&value).unwrap();
+
+        let transcoder = Transcoder::new(Encoding::APPLICATION_CBOR).unwrap();
+        let cbor = transcoder
+            .transcode(&json_payload, &Encoding::APPLICATION_JSON)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            ciborium::from_reader::<serde_json::Value, _>(cbor.as_slice()).unwrap(),
+            value
+        );
+    }
+
+    #[test]
+    fn unsupported_and_invalid() {
+        let transcoder = Transcoder::new(Encoding::APPLICATION_JSON).unwrap();
+        assert_eq!(
+            transcoder
+                .transcode(b"raw", &Encoding::ZENOH_BYTES)
+                .unwrap(),
+            None
+        );
+        assert!(transcoder
+            .transcode(b"\xff\xff", &Encoding::APPLICATION_CBOR)
+            .is_err());
+    }
+}
@@ -0,0 +1,569 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Async Rust client of the Zenoh remote API, speaking `RemoteAPIMsg` over a WebSocket.
+//!
+//! It runs on tokio on native targets, and on the browser `WebSocket` on `wasm32-unknown-unknown`.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard, PoisonError, Weak},
+    time::Duration,
+};
+
+use flume::{Receiver, Sender};
+use futures::{
+    future::{self, Either},
+    pin_mut,
+};
+use uuid::Uuid;
+use zenoh_keyexpr::{keyexpr, OwnedKeyExpr};
+pub use zenoh_remote_api_protocol as protocol;
+pub use zenoh_remote_api_protocol::{
+    B64String, CompressionWS, EncodingWS, QueryWS, ReplyErrorWS, ReplyWS, SampleKindWS, SampleWS,
+};
+use zenoh_remote_api_protocol::{
+    ControlMsg, DataMsg, HandlerChannel, LivelinessMsg, QueryReplyVariant, QueryReplyWS,
+    QueryableMsg, RemoteAPIMsg,
+};
+
+mod transport;
+use transport::Transport;
+
+pub type Error = Box<dyn std::error::Error + Send + Sync>;
+
+// Channel buffering samples and replies on the plugin side, before they are sent on the WebSocket
+const HANDLER: HandlerChannel = HandlerChannel::Fifo(256);
+// The plugin doesn't reply to a declaration it failed
+const DECLARATION_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn to_key_expr(key_expr: &str) -> Result<OwnedKeyExpr, Error> {
+    Ok(keyexpr::new(key_expr)?.to_owned())
+}
+
+fn b64(payload: impl AsRef<[u8]>) -> B64String {
+    B64String::encode(payload)
+}
+
+// Where the messages received from the plugin are forwarded
+#[derive(Default)]
+struct Routes {
+    session: Option<Sender<Uuid>>,
+    declarations: HashMap<Uuid, Sender<()>>,
+    samples: HashMap<Uuid, Sender<SampleWS>>,
+    replies: HashMap<Uuid, Sender<ReplyWS>>,
+    queries: HashMap<Uuid, Sender<QueryWS>>,
+}
+
+struct Inner {
+    outgoing: Sender<String>,
+    routes: Mutex<Routes>,
+}
+
+impl Inner {
+    fn send(&self, msg: RemoteAPIMsg) -> Result<(), Error> {
+        let text = msg.to_json()?;
+        self.outgoing
+            .send(text)
+            .map_err(|_| "RemoteAPI client: WebSocket closed".into())
+    }
+
+    fn control(&self, msg: ControlMsg) -> Result<(), Error> {
+        self.send(RemoteAPIMsg::Control(msg))
+    }
+
+    fn routes(&self) -> MutexGuard<'_, Routes> {
+        self.routes.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn route(&self, msg: RemoteAPIMsg) {
+        let mut routes = self.routes();
+        match msg {
+            RemoteAPIMsg::Control(ControlMsg::Session(id)) => {
+                if let Some(session_tx) = routes.session.take() {
+                    let _ = session_tx.send(id);
+                }
+            }
+            RemoteAPIMsg::Control(ControlMsg::Error(reason)) => {
+                tracing::error!("RemoteAPI client: Message rejected by the plugin: {reason}");
+            }
+            RemoteAPIMsg::Control(ControlMsg::Subscriber(id)) => {
+                if let Some(declared_tx) = routes.declarations.remove(&id) {
+                    let _ = declared_tx.send(());
+                }
+            }
+            RemoteAPIMsg::Control(ControlMsg::GetFinished { id }) => {
+                // Ends the stream of replies
+                routes.replies.remove(&id);
+            }
+            RemoteAPIMsg::Data(DataMsg::Sample(sample, id)) => {
+                if let Some(sample_tx) = routes.samples.get(&id) {
+                    let _ = sample_tx.send(sample);
+                }
+            }
+            RemoteAPIMsg::Data(DataMsg::Samples(samples, id)) => {
+                if let Some(sample_tx) = routes.samples.get(&id) {
+                    for sample in samples {
+                        let _ = sample_tx.send(sample);
+                    }
+                }
+            }
+            RemoteAPIMsg::Data(DataMsg::GetReply(reply)) => {
+                if let Some(reply_tx) = routes.replies.get(&reply.query_uuid()) {
+                    let _ = reply_tx.send(reply);
+                }
+            }
+            RemoteAPIMsg::Data(DataMsg::Queryable(QueryableMsg::Query {
+                queryable_uuid,
+                query,
+            })) => {
+                if let Some(query_tx) = routes.queries.get(&queryable_uuid) {
+                    let _ = query_tx.send(query);
+                }
+            }
+            msg => tracing::debug!("RemoteAPI client: Ignoring message {msg:?}"),
+        }
+    }
+}
+
+async fn dispatch(inner: Weak<Inner>, incoming: Receiver<Option<String>>) {
+    while let Ok(Some(text)) = incoming.recv_async().await {
+        let msg = match RemoteAPIMsg::from_json(&text) {
+            Ok(msg) => msg,
+            Err(err) => {
+                tracing::error!("RemoteAPI client: WS Message Cannot be Deserialized {err}");
+                continue;
+            }
+        };
+        let Some(inner) = inner.upgrade() else {
+            return;
+        };
+        inner.route(msg);
+    }
+    // Pending declarations, subscribers, gets and queryables end with the connection
+    if let Some(inner) = inner.upgrade() {
+        *inner.routes() = Routes::default();
+    }
+}
+
+/// A session opened on the remote API of a zenoh node.
+#[derive(Clone)]
+pub struct Client {
+    inner: Arc<Inner>,
+    session_id: Uuid,
+}
+
+impl Client {
+    /// Connects to the remote API at `url`, e.g. `ws://localhost:10000`, and opens a session.
+    pub async fn connect(url: &str) -> Result<Client, Error> {
+        let Transport { outgoing, incoming } = transport::connect(url).await?;
+        let inner = Arc::new(Inner {
+            outgoing,
+            routes: Mutex::default(),
+        });
+        transport::spawn(dispatch(Arc::downgrade(&inner), incoming));
+
+        let (session_tx, session_rx) = flume::bounded(1);
+        inner.routes().session = Some(session_tx);
+        inner.control(ControlMsg::OpenSession)?;
+        let session_id = session_rx
+            .recv_async()
+            .await
+            .map_err(|_| "RemoteAPI client: Connection closed before the session was opened")?;
+
+        Ok(Client { inner, session_id })
+    }
+
+    /// The id of the session on the plugin side.
+    pub fn session_id(&self) -> Uuid {
+        self.session_id
+    }
+
+    /// Closes the session, undeclaring everything that was declared with it.
+    pub fn close(self) -> Result<(), Error> {
+        self.inner.control(ControlMsg::CloseSession)
+    }
+
+    pub fn put(&self, key_expr: &str, payload: impl AsRef<[u8]>) -> Result<(), Error> {
+        self.inner.control(ControlMsg::Put {
+            key_expr: to_key_expr(key_expr)?,
+            payload: b64(payload),
+            encoding: None,
+            congestion_control: None,
+            priority: None,
+            express: None,
+            attachment: None,
+            timestamp: None,
+            source_info: None,
+        })
+    }
+
+    pub fn delete(&self, key_expr: &str) -> Result<(), Error> {
+        self.inner.control(ControlMsg::Delete {
+            key_expr: to_key_expr(key_expr)?,
+            congestion_control: None,
+            priority: None,
+            express: None,
+            attachment: None,
+            timestamp: None,
+            source_info: None,
+        })
+    }
+
+    /// Queries `key_expr`, the returned channel being closed once all replies are received.
+    pub fn get(
+        &self,
+        key_expr: &str,
+        parameters: Option<&str>,
+    ) -> Result<Receiver<ReplyWS>, Error> {
+        let key_expr = to_key_expr(key_expr)?;
+        let id = Uuid::new_v4();
+        let (reply_tx, reply_rx) = flume::unbounded();
+        self.inner.routes().replies.insert(id, reply_tx);
+        self.inner.control(ControlMsg::Get {
+            key_expr,
+            parameters: parameters.map(str::to_string),
+            handler: HANDLER,
+            id,
+            consolidation: None,
+            congestion_control: None,
+            priority: None,
+            express: None,
+            encoding: None,
+            payload: None,
+            attachment: None,
+        })?;
+        Ok(reply_rx)
+    }
+
+    pub fn declare_publisher(&self, key_expr: &str) -> Result<Publisher, Error> {
+        let id = Uuid::new_v4();
+        self.inner.control(ControlMsg::DeclarePublisher {
+            key_expr: to_key_expr(key_expr)?,
+            encoding: None,
+            congestion_control: None,
+            priority: None,
+            reliability: None,
+            express: None,
+            id,
+        })?;
+        Ok(Publisher {
+            inner: self.inner.clone(),
+            id,
+        })
+    }
+
+    /// Declares a subscriber, returned once the plugin has declared it,
+    /// failing if it wasn't declared within 10 seconds.
+    /// With a `compression`, the payloads above the size threshold of the plugin are received compressed.
+    pub async fn declare_subscriber(
+        &self,
+        key_expr: &str,
+        compression: Option<CompressionWS>,
+    ) -> Result<Subscriber, Error> {
+        let key_expr = to_key_expr(key_expr)?;
+        let id = Uuid::new_v4();
+        let (declared_tx, declared_rx) = flume::bounded(1);
+        let subscriber = self.subscriber(id, ControlMsg::UndeclareSubscriber);
+        self.inner.routes().declarations.insert(id, declared_tx);
+        self.inner.control(ControlMsg::DeclareSubscriber {
+            key_expr,
+            handler: HANDLER,
+            id,
+            transcode_to: None,
+            compression,
+            batch: None,
+            throttle: None,
+            filters: None,
+        })?;
+        let declared = declared_rx.recv_async();
+        let timed_out = transport::sleep(DECLARATION_TIMEOUT);
+        pin_mut!(declared, timed_out);
+        match future::select(declared, timed_out).await {
+            Either::Left((Ok(()), _)) => Ok(subscriber),
+            Either::Left((Err(_), _)) => {
+                Err("RemoteAPI client: Connection closed before the subscriber was declared".into())
+            }
+            Either::Right(_) => {
+                self.inner.routes().declarations.remove(&id);
+                Err(format!(
+                    "RemoteAPI client: Subscriber not declared within {DECLARATION_TIMEOUT:?}"
+                )
+                .into())
+            }
+        }
+    }
+
+    pub fn declare_queryable(&self, key_expr: &str, complete: bool) -> Result<Queryable, Error> {
+        let key_expr = to_key_expr(key_expr)?;
+        let id = Uuid::new_v4();
+        let (query_tx, query_rx) = flume::unbounded();
+        self.inner.routes().queries.insert(id, query_tx);
+        let queryable = Queryable {
+            inner: self.inner.clone(),
+            id,
+            queries: query_rx,
+        };
+        self.inner.control(ControlMsg::DeclareQueryable {
+            key_expr,
+            id,
+            complete,
+        })?;
+        Ok(queryable)
+    }
+
+    pub fn liveliness(&self) -> Liveliness<'_> {
+        Liveliness { client: self }
+    }
+
+    // Registered before the declaration so that no sample is missed
+    fn subscriber(&self, id: Uuid, undeclare: fn(Uuid) -> ControlMsg) -> Subscriber {
+        let (sample_tx, sample_rx) = flume::unbounded();
+        self.inner.routes().samples.insert(id, sample_tx);
+        Subscriber {
+            inner: self.inner.clone(),
+            id,
+            samples: sample_rx,
+            undeclare,
+        }
+    }
+}
+
+/// Liveliness operations of a [`Client`].
+pub struct Liveliness<'a> {
+    client: &'a Client,
+}
+
+impl Liveliness<'_> {
+    pub fn declare_token(&self, key_expr: &str) -> Result<LivelinessToken, Error> {
+        let id = Uuid::new_v4();
+        self.client
+            .inner
+            .control(ControlMsg::Liveliness(LivelinessMsg::DeclareToken {
+                key_expr: to_key_expr(key_expr)?,
+                id,
+            }))?;
+        Ok(LivelinessToken {
+            inner: self.client.inner.clone(),
+            id,
+        })
+    }
+
+    /// Subscribes to the liveliness tokens matching `key_expr`, `history` also returning the existing ones.
+    pub fn declare_subscriber(&self, key_expr: &str, history: bool) -> Result<Subscriber, Error> {
+        let key_expr = to_key_expr(key_expr)?;
+        let id = Uuid::new_v4();
+        let subscriber = self.client.subscriber(id, |id| {
+            ControlMsg::Liveliness(LivelinessMsg::UndeclareSubscriber(id))
+        });
+        self.client
+            .inner
+            .control(ControlMsg::Liveliness(LivelinessMsg::DeclareSubscriber {
+                key_expr,
+                id,
+                history,
+            }))?;
+        Ok(subscriber)
+    }
+
+    /// Queries the liveliness tokens matching `key_expr`, the returned channel being closed once all replies are received.
+    pub fn get(
+        &self,
+        key_expr: &str,
+        timeout: Option<Duration>,
+    ) -> Result<Receiver<ReplyWS>, Error> {
+        let key_expr = to_key_expr(key_expr)?;
+        let id = Uuid::new_v4();
+        let (reply_tx, reply_rx) = flume::unbounded();
+        self.client.inner.routes().replies.insert(id, reply_tx);
+        self.client
+            .inner
+            .control(ControlMsg::Liveliness(LivelinessMsg::Get {
+                key_expr,
+                id,
+                timeout: timeout.map(|timeout| timeout.as_millis() as u64),
+            }))?;
+        Ok(reply_rx)
+    }
+}
+
+/// A publisher, undeclared when dropped.
+pub struct Publisher {
+    inner: Arc<Inner>,
+    id: Uuid,
+}
+
+impl Publisher {
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub fn put(&self, payload: impl AsRef<[u8]>) -> Result<(), Error> {
+        self.inner.send(RemoteAPIMsg::Data(DataMsg::PublisherPut {
+            id: self.id,
+            payload: b64(payload),
+            attachment: None,
+            encoding: None,
+            timestamp: None,
+            source_info: None,
+        }))
+    }
+
+    pub fn delete(&self) -> Result<(), Error> {
+        self.inner
+            .send(RemoteAPIMsg::Data(DataMsg::PublisherDelete {
+                id: self.id,
+                attachment: None,
+                timestamp: None,
+                source_info: None,
+            }))
+    }
+
+    pub fn undeclare(self) {}
+}
+
+impl Drop for Publisher {
+    fn drop(&mut self) {
+        let _ = self.inner.control(ControlMsg::UndeclarePublisher(self.id));
+    }
+}
+
+/// A subscriber, undeclared when dropped.
+pub struct Subscriber {
+    inner: Arc<Inner>,
+    id: Uuid,
+    samples: Receiver<SampleWS>,
+    undeclare: fn(Uuid) -> ControlMsg,
+}
+
+impl Subscriber {
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Receives the next sample, failing once the connection is closed.
+    pub async fn recv_async(&self) -> Result<SampleWS, Error> {
+        self.samples
+            .recv_async()
+            .await
+            .map_err(|_| "RemoteAPI client: Subscriber closed".into())
+    }
+
+    pub fn receiver(&self) -> &Receiver<SampleWS> {
+        &self.samples
+    }
+
+    pub fn undeclare(self) {}
+}
+
+impl Drop for Subscriber {
+    fn drop(&mut self) {
+        self.inner.routes().samples.remove(&self.id);
+        let _ = self.inner.control((self.undeclare)(self.id));
+    }
+}
+
+/// A queryable, undeclared when dropped.
+pub struct Queryable {
+    inner: Arc<Inner>,
+    id: Uuid,
+    queries: Receiver<QueryWS>,
+}
+
+impl Queryable {
+    pub fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Receives the next query, failing once the connection is closed.
+    pub async fn recv_async(&self) -> Result<Query, Error> {
+        let query = self
+            .queries
+            .recv_async()
+            .await
+            .map_err(|_| "RemoteAPI client: Queryable closed")?;
+        Ok(Query {
+            inner: self.inner.clone(),
+            query,
+        })
+    }
+
+    pub fn undeclare(self) {}
+}
+
+impl Drop for Queryable {
+    fn drop(&mut self) {
+        self.inner.routes().queries.remove(&self.id);
+        let _ = self.inner.control(ControlMsg::UndeclareQueryable(self.id));
+    }
+}
+
+/// A query received by a [`Queryable`], answered by one of its reply methods.
+pub struct Query {
+    inner: Arc<Inner>,
+    query: QueryWS,
+}
+
+impl Query {
+    pub fn query(&self) -> &QueryWS {
+        &self.query
+    }
+
+    pub fn reply(self, key_expr: &str, payload: impl AsRef<[u8]>) -> Result<(), Error> {
+        self.send_reply(QueryReplyVariant::Reply {
+            key_expr: to_key_expr(key_expr)?,
+            payload: b64(payload),
+        })
+    }
+
+    pub fn reply_err(self, payload: impl AsRef<[u8]>) -> Result<(), Error> {
+        self.send_reply(QueryReplyVariant::ReplyErr {
+            payload: b64(payload),
+        })
+    }
+
+    pub fn reply_delete(self, key_expr: &str) -> Result<(), Error> {
+        self.send_reply(QueryReplyVariant::ReplyDelete {
+            key_expr: to_key_expr(key_expr)?,
+        })
+    }
+
+    fn send_reply(self, result: QueryReplyVariant) -> Result<(), Error> {
+        self.inner.send(RemoteAPIMsg::Data(DataMsg::Queryable(
+            QueryableMsg::Reply {
+                reply: QueryReplyWS::new(self.query.query_uuid(), result),
+            },
+        )))
+    }
+}
+
+/// A liveliness token, undeclared when dropped.
+pub struct LivelinessToken {
+    inner: Arc<Inner>,
+    id: Uuid,
+}
+
+impl LivelinessToken {
+    pub fn undeclare(self) {}
+}
+
+impl Drop for LivelinessToken {
+    fn drop(&mut self) {
+        let _ = self
+            .inner
+            .control(ControlMsg::Liveliness(LivelinessMsg::UndeclareToken(
+                self.id,
+            )));
+    }
+}
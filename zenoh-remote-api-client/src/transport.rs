@@ -0,0 +1,161 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! WebSocket transports: tokio-tungstenite on native targets, the browser `WebSocket` on wasm32.
+//! Both exchange text frames through channels, `None` being received once the WebSocket is closed.
+
+use std::{future::Future, time::Duration};
+
+use flume::{Receiver, Sender};
+
+use crate::Error;
+
+pub(crate) struct Transport {
+    pub(crate) outgoing: Sender<String>,
+    pub(crate) incoming: Receiver<Option<String>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn spawn<F>(task: F)
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    tokio::spawn(task);
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn spawn<F>(task: F)
+where
+    F: Future<Output = ()> + 'static,
+{
+    wasm_bindgen_futures::spawn_local(task);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await;
+}
+
+// The global `setTimeout`, available in windows and workers
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_name = setTimeout)]
+    fn set_timeout(handler: &js_sys::Function, timeout: i32);
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn sleep(duration: Duration) {
+    let timeout = i32::try_from(duration.as_millis()).unwrap_or(i32::MAX);
+    let promise = js_sys::Promise::new(&mut |resolve, _| set_timeout(&resolve, timeout));
+    let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn connect(url: &str) -> Result<Transport, Error> {
+    use futures::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::protocol::Message;
+
+    let (ws_stream, _) = tokio_tungstenite::connect_async(url).await?;
+    let (mut ws_tx, mut ws_rx) = ws_stream.split();
+    let (outgoing, outgoing_rx) = flume::unbounded::<String>();
+    let (incoming_tx, incoming) = flume::unbounded();
+
+    spawn(async move {
+        while let Ok(text) = outgoing_rx.recv_async().await {
+            if let Err(err) = ws_tx.send(Message::Text(text)).await {
+                tracing::error!("RemoteAPI client: WS Send Error: {err}");
+                break;
+            }
+        }
+        let _ = ws_tx.close().await;
+    });
+
+    spawn(async move {
+        while let Some(Ok(msg)) = ws_rx.next().await {
+            match msg {
+                Message::Text(text) => {
+                    if incoming_tx.send(Some(text)).is_err() {
+                        break;
+                    }
+                }
+                Message::Close(_) => break,
+                _ => tracing::debug!("RemoteAPI client: WS Message Not Text"),
+            }
+        }
+        let _ = incoming_tx.send(None);
+    });
+
+    Ok(Transport { outgoing, incoming })
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn connect(url: &str) -> Result<Transport, Error> {
+    use wasm_bindgen::{closure::Closure, JsCast};
+    use web_sys::{CloseEvent, ErrorEvent, Event, MessageEvent, WebSocket};
+
+    let ws = WebSocket::new(url).map_err(|err| format!("Could not open WebSocket: {err:?}"))?;
+    let (outgoing, outgoing_rx) = flume::unbounded::<String>();
+    let (incoming_tx, incoming) = flume::unbounded();
+    let (opened_tx, opened_rx) = flume::bounded::<Result<(), String>>(1);
+
+    let opened_tx_cl = opened_tx.clone();
+    let on_open = Closure::<dyn FnMut(Event)>::new(move |_: Event| {
+        let _ = opened_tx_cl.try_send(Ok(()));
+    });
+    let on_error = Closure::<dyn FnMut(ErrorEvent)>::new(move |event: ErrorEvent| {
+        let _ = opened_tx.try_send(Err(event.message()));
+    });
+    let incoming_tx_cl = incoming_tx.clone();
+    let on_message =
+        Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+            match event.data().as_string() {
+                Some(text) => {
+                    let _ = incoming_tx_cl.send(Some(text));
+                }
+                None => tracing::debug!("RemoteAPI client: WS Message Not Text"),
+            }
+        });
+    let on_close = Closure::<dyn FnMut(CloseEvent)>::new(move |_: CloseEvent| {
+        let _ = incoming_tx.send(None);
+    });
+    ws.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+    ws.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+    ws.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    ws.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+
+    opened_rx
+        .recv_async()
+        .await
+        .map_err(|_| "WebSocket closed before opening".to_string())?
+        .map_err(|err| format!("Could not open WebSocket: {err}"))?;
+
+    spawn(async move {
+        while let Ok(text) = outgoing_rx.recv_async().await {
+            if let Err(err) = ws.send_with_str(&text) {
+                tracing::error!("RemoteAPI client: WS Send Error: {err:?}");
+                break;
+            }
+        }
+        // The callbacks must not be called once dropped
+        ws.set_onopen(None);
+        ws.set_onerror(None);
+        ws.set_onmessage(None);
+        ws.set_onclose(None);
+        let _ = ws.close();
+        drop((on_open, on_error, on_message, on_close));
+    });
+
+    Ok(Transport { outgoing, incoming })
+}
@@ -0,0 +1,112 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use base64::{prelude::BASE64_STANDARD, DecodeError, Engine};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::RemoteAPIMsg;
+
+/// Bytes encoded in standard Base64, as payloads and attachments are sent in JSON
+#[derive(TS)]
+#[ts(export)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct B64String(String);
+
+// The String is expected to be already Base64 encoded
+impl From<String> for B64String {
+    fn from(value: String) -> Self {
+        B64String(value)
+    }
+}
+
+impl B64String {
+    pub fn encode(bytes: impl AsRef<[u8]>) -> Self {
+        B64String(BASE64_STANDARD.encode(bytes))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, DecodeError> {
+        BASE64_STANDARD.decode(&self.0)
+    }
+
+    pub fn b64_to_bytes(self) -> Result<Vec<u8>, DecodeError> {
+        BASE64_STANDARD.decode(self.0)
+    }
+}
+
+impl RemoteAPIMsg {
+    /// Encode the message as sent in a WebSocket text frame
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Decode a message received in a WebSocket text frame
+    pub fn from_json(text: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ControlMsg;
+
+    #[test]
+    fn test_b64_serializing() {
+        let bytes: Vec<u8> = std::iter::repeat(245).take(100).collect();
+
+        let b64_string = BASE64_STANDARD.encode(bytes.clone());
+
+        #[derive(Debug, Serialize, Deserialize)]
+        struct RawBytes {
+            bytes: Vec<u8>,
+        }
+        #[derive(Debug, Serialize, Deserialize)]
+        struct B64Encoded {
+            b64_string: String,
+        }
+
+        let json_bytes = serde_json::to_string(&RawBytes { bytes }).unwrap();
+        let json_b64 = serde_json::to_string(&B64Encoded { b64_string }).unwrap();
+        assert!(json_b64.len() < json_bytes.len())
+    }
+
+    #[test]
+    fn b64_round_trip() {
+        let b64 = B64String::encode([1, 2, 3]);
+        assert_eq!(b64.as_str(), "AQID");
+        assert_eq!(b64.to_bytes().unwrap(), vec![1, 2, 3]);
+        assert!(B64String::from("not base64!".to_string())
+            .b64_to_bytes()
+            .is_err());
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let json = RemoteAPIMsg::Control(ControlMsg::OpenSession)
+            .to_json()
+            .unwrap();
+        assert_eq!(json, r#"{"Control":"OpenSession"}"#);
+        assert!(matches!(
+            RemoteAPIMsg::from_json(&json).unwrap(),
+            RemoteAPIMsg::Control(ControlMsg::OpenSession)
+        ));
+        assert!(RemoteAPIMsg::from_json(r#"{"Control":"Unknown"}"#).is_err());
+    }
+}
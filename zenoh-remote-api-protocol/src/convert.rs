@@ -0,0 +1,218 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Conversions from the zenoh API types, with the `zenoh` feature
+
+use std::str::FromStr;
+
+use uuid::Uuid;
+use zenoh::{
+    bytes::Encoding,
+    query::{Query, Reply, ReplyError},
+    sample::{Sample, SampleKind, SourceInfo},
+    session::{EntityGlobalId, ZenohId},
+};
+use zenoh_buffers::ZSlice;
+
+use crate::{
+    B64String, EncodingWS, EntityGlobalIdWS, QueryWS, ReplyErrorWS, ReplyWS, SampleKindWS,
+    SampleWS, SourceInfoWS, TimestampWS,
+};
+
+// Through the protocol representation of zenoh, as its id and schema are only reachable there
+impl From<&Encoding> for EncodingWS {
+    fn from(encoding: &Encoding) -> Self {
+        let encoding = zenoh_protocol::core::Encoding::from(encoding.clone());
+        EncodingWS::new(
+            encoding.id,
+            encoding
+                .schema
+                .map(|schema| String::from_utf8_lossy(&schema).into_owned()),
+        )
+    }
+}
+
+impl From<EncodingWS> for Encoding {
+    fn from(encoding: EncodingWS) -> Self {
+        zenoh_protocol::core::Encoding {
+            id: encoding.id(),
+            schema: encoding
+                .schema()
+                .map(|schema| ZSlice::from(schema.as_bytes().to_vec())),
+        }
+        .into()
+    }
+}
+
+impl From<(&Query, Uuid)> for QueryWS {
+    fn from((q, uuid): (&Query, Uuid)) -> Self {
+        let payload = q.payload().map(|x| B64String::encode(x.to_bytes()));
+        let attachment = q.attachment().map(|x| B64String::encode(x.to_bytes()));
+
+        QueryWS::new(
+            uuid,
+            q.key_expr().to_owned().into(),
+            q.parameters().to_string(),
+            q.encoding().map(EncodingWS::from),
+            attachment,
+            payload,
+        )
+    }
+}
+
+impl From<(Reply, Uuid)> for ReplyWS {
+    fn from((reply, uuid): (Reply, Uuid)) -> Self {
+        match reply.result() {
+            Ok(sample) => ReplyWS::new(uuid, Ok(SampleWS::from(sample))),
+            Err(err) => ReplyWS::new(uuid, Err(ReplyErrorWS::from(err))),
+        }
+    }
+}
+
+impl From<ReplyError> for ReplyErrorWS {
+    fn from(r_e: ReplyError) -> Self {
+        ReplyErrorWS::from(&r_e)
+    }
+}
+
+impl From<&ReplyError> for ReplyErrorWS {
+    fn from(r_e: &ReplyError) -> Self {
+        ReplyErrorWS::new(
+            B64String::encode(r_e.payload().to_bytes()),
+            EncodingWS::from(r_e.encoding()),
+        )
+    }
+}
+
+impl From<&EntityGlobalId> for EntityGlobalIdWS {
+    fn from(id: &EntityGlobalId) -> Self {
+        EntityGlobalIdWS::new(id.zid().to_string(), id.eid())
+    }
+}
+
+impl From<&SourceInfo> for SourceInfoWS {
+    fn from(source_info: &SourceInfo) -> Self {
+        SourceInfoWS::new(
+            EntityGlobalIdWS::from(source_info.source_id()),
+            source_info.source_sn(),
+        )
+    }
+}
+
+impl TryFrom<SourceInfoWS> for SourceInfo {
+    type Error = zenoh::Error;
+
+    fn try_from(source_info: SourceInfoWS) -> Result<Self, Self::Error> {
+        let zid = ZenohId::from_str(source_info.id().zid())?;
+        Ok(SourceInfo::new(
+            EntityGlobalId::new(zid, source_info.id().eid()),
+            source_info.sn(),
+        ))
+    }
+}
+
+impl From<SampleKind> for SampleKindWS {
+    fn from(sk: SampleKind) -> Self {
+        match sk {
+            SampleKind::Put => SampleKindWS::Put,
+            SampleKind::Delete => SampleKindWS::Delete,
+        }
+    }
+}
+
+impl SampleWS {
+    /// The sample with its payload replaced, e.g. once transcoded to another encoding
+    pub fn with_payload(s: &Sample, payload: impl AsRef<[u8]>, encoding: &Encoding) -> Self {
+        sample_ws(s, B64String::encode(payload), EncodingWS::from(encoding))
+    }
+}
+
+impl From<&Sample> for SampleWS {
+    fn from(s: &Sample) -> Self {
+        sample_ws(
+            s,
+            B64String::encode(s.payload().to_bytes()),
+            EncodingWS::from(s.encoding()),
+        )
+    }
+}
+
+fn sample_ws(s: &Sample, value: B64String, encoding: EncodingWS) -> SampleWS {
+    let mut sample_ws = SampleWS::new(
+        s.key_expr().to_owned().into(),
+        value,
+        s.kind().into(),
+        encoding,
+    );
+    sample_ws.timestamp = s.timestamp().map(TimestampWS::from);
+    sample_ws.priority = s.priority().into();
+    sample_ws.congestion_control = s.congestion_control();
+    sample_ws.reliability = s.reliability();
+    sample_ws.express = s.express();
+    sample_ws.attachement = s.attachment().map(|x| B64String::encode(x.to_bytes()));
+    sample_ws.source_info = s.source_info().map(SourceInfoWS::from);
+    sample_ws
+}
+
+impl From<Sample> for SampleWS {
+    fn from(s: Sample) -> Self {
+        SampleWS::from(&s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn source_info_round_trip() {
+        let source_info_ws: SourceInfoWS =
+            serde_json::from_str(r#"{"id":{"zid":"a1b2","eid":3},"sn":42}"#).unwrap();
+        let source_info = SourceInfo::try_from(source_info_ws).unwrap();
+        let source_info_ws = SourceInfoWS::from(&source_info);
+
+        assert_eq!(source_info_ws.id().zid(), "a1b2");
+        assert_eq!(source_info_ws.id().eid(), 3);
+        assert_eq!(source_info_ws.sn(), 42);
+    }
+
+    #[test]
+    fn encoding_round_trip() {
+        for encoding in [
+            Encoding::ZENOH_BYTES,
+            Encoding::TEXT_PLAIN.with_schema("utf-8"),
+            Encoding::APPLICATION_PROTOBUF,
+            Encoding::from("my/encoding;v2"),
+        ] {
+            let encoding_ws = EncodingWS::from(&encoding);
+            assert_eq!(encoding_ws.to_string(), encoding.to_string());
+            assert_eq!(
+                encoding_ws.to_string().parse::<EncodingWS>().unwrap(),
+                encoding_ws
+            );
+            assert_eq!(Encoding::from(encoding_ws), encoding);
+        }
+    }
+
+    #[test]
+    fn unknown_encoding_names_match_zenoh() {
+        for s in ["my/encoding", "my/encoding;v2"] {
+            assert_eq!(
+                s.parse::<EncodingWS>().unwrap(),
+                EncodingWS::from(&Encoding::from(s)),
+                "{s}"
+            );
+        }
+    }
+}
@@ -0,0 +1,289 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Encodings of the payloads, sent as the numeric id of zenoh plus an optional schema
+//! (`{"id":4,"schema":"utf-8"}`), and accepted as well in the string form of zenoh
+//! (`"text/plain;utf-8"`).
+
+use std::{fmt, str::FromStr};
+
+use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema};
+use serde::{
+    de::{self, value::MapAccessDeserializer, MapAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+use serde_json::json;
+use ts_rs::TS;
+use zenoh_result::{zerror, ZResult};
+
+const SCHEMA_SEP: char = ';';
+
+// Encodings known by zenoh, indexed by their id
+const KNOWN_ENCODINGS: [&str; 53] = [
+    "zenoh/bytes",
+    "zenoh/string",
+    "zenoh/serialized",
+    "application/octet-stream",
+    "text/plain",
+    "application/json",
+    "text/json",
+    "application/cdr",
+    "application/cbor",
+    "application/yaml",
+    "text/yaml",
+    "text/json5",
+    "application/python-serialized-object",
+    "application/protobuf",
+    "application/java-serialized-object",
+    "application/openmetrics-text",
+    "image/png",
+    "image/jpeg",
+    "image/gif",
+    "image/bmp",
+    "image/webp",
+    "application/xml",
+    "application/x-www-form-urlencoded",
+    "text/html",
+    "text/xml",
+    "text/css",
+    "text/javascript",
+    "text/markdown",
+    "text/csv",
+    "application/sql",
+    "application/coap-payload",
+    "application/json-patch+json",
+    "application/json-seq",
+    "application/jsonpath",
+    "application/jwt",
+    "application/mp4",
+    "application/soap+xml",
+    "application/yang",
+    "audio/aac",
+    "audio/flac",
+    "audio/mp4",
+    "audio/ogg",
+    "audio/vorbis",
+    "video/h261",
+    "video/h263",
+    "video/h264",
+    "video/h265",
+    "video/h266",
+    "video/mp4",
+    "video/ogg",
+    "video/raw",
+    "video/vp8",
+    "video/vp9",
+];
+
+#[derive(TS)]
+#[ts(export)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct EncodingWS {
+    id: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    schema: Option<String>,
+}
+
+impl EncodingWS {
+    pub fn new(id: u16, schema: Option<String>) -> Self {
+        EncodingWS { id, schema }
+    }
+
+    pub fn id(&self) -> u16 {
+        self.id
+    }
+
+    pub fn schema(&self) -> Option<&str> {
+        self.schema.as_deref()
+    }
+
+    /// Name of the encoding if zenoh knows its id
+    pub fn name(&self) -> Option<&'static str> {
+        KNOWN_ENCODINGS.get(usize::from(self.id)).copied()
+    }
+}
+
+// Same string as zenoh: `<name>[;<schema>]`, and `unknown(<id>)[;<schema>]` for the ids
+// zenoh does not know
+impl fmt::Display for EncodingWS {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.id, self.name(), self.schema()) {
+            (_, Some(name), None) => f.write_str(name),
+            (_, Some(name), Some(schema)) => write!(f, "{name}{SCHEMA_SEP}{schema}"),
+            (id, None, None) => write!(f, "unknown({id})"),
+            (id, None, Some(schema)) => write!(f, "unknown({id}){SCHEMA_SEP}{schema}"),
+        }
+    }
+}
+
+// Parsed as zenoh does, an unknown name giving `zenoh/bytes` with the whole string as schema,
+// except for `unknown(<id>)` which must hold a valid id
+impl FromStr for EncodingWS {
+    type Err = zenoh_result::Error;
+
+    fn from_str(s: &str) -> ZResult<Self> {
+        if s.is_empty() {
+            return Ok(EncodingWS::default());
+        }
+        let (name, schema) = s.split_once(SCHEMA_SEP).unwrap_or((s, ""));
+        let schema = (!schema.is_empty()).then(|| schema.to_string());
+
+        if let Some(id) = KNOWN_ENCODINGS.iter().position(|known| *known == name) {
+            return Ok(EncodingWS::new(id as u16, schema));
+        }
+        if let Some(id) = name
+            .strip_prefix("unknown(")
+            .and_then(|id| id.strip_suffix(')'))
+        {
+            let id = id
+                .parse::<u16>()
+                .map_err(|err| zerror!("Invalid encoding id in {s:?}: {err}"))?;
+            return Ok(EncodingWS::new(id, schema));
+        }
+        Ok(EncodingWS::new(0, Some(s.to_string())))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EncodingFields {
+    id: u16,
+    #[serde(default)]
+    schema: Option<String>,
+}
+
+struct EncodingVisitor;
+
+impl<'de> Visitor<'de> for EncodingVisitor {
+    type Value = EncodingWS;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an encoding as {\"id\": <u16>, \"schema\": <string>} or as a string")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<EncodingWS, E> {
+        EncodingWS::from_str(value).map_err(E::custom)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<EncodingWS, A::Error> {
+        let fields = EncodingFields::deserialize(MapAccessDeserializer::new(map))?;
+        Ok(EncodingWS::new(fields.id, fields.schema))
+    }
+}
+
+impl<'de> Deserialize<'de> for EncodingWS {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        d.deserialize_any(EncodingVisitor)
+    }
+}
+
+impl JsonSchema for EncodingWS {
+    fn schema_name() -> String {
+        "EncodingWS".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        serde_json::from_value(json!({
+            "anyOf": [
+                {
+                    "type": "object",
+                    "required": ["id"],
+                    "properties": {
+                        "id": { "type": "integer", "minimum": 0, "maximum": u16::MAX },
+                        "schema": { "type": ["string", "null"] },
+                    },
+                    "additionalProperties": false,
+                },
+                { "type": "string" },
+            ]
+        }))
+        .expect("Encoding schema should deserialize")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn structured_round_trip() {
+        for (encoding, json) in [
+            (EncodingWS::new(0, None), r#"{"id":0}"#),
+            (
+                EncodingWS::new(4, Some("utf-8".into())),
+                r#"{"id":4,"schema":"utf-8"}"#,
+            ),
+            (
+                EncodingWS::new(0, Some("my/encoding;v2".into())),
+                r#"{"id":0,"schema":"my/encoding;v2"}"#,
+            ),
+            (
+                EncodingWS::new(1000, Some("v1".into())),
+                r#"{"id":1000,"schema":"v1"}"#,
+            ),
+        ] {
+            assert_eq!(serde_json::to_string(&encoding).unwrap(), json);
+            assert_eq!(serde_json::from_str::<EncodingWS>(json).unwrap(), encoding);
+        }
+    }
+
+    #[test]
+    fn strings() {
+        for (s, encoding) in [
+            ("", EncodingWS::new(0, None)),
+            ("zenoh/bytes", EncodingWS::new(0, None)),
+            ("text/plain;utf-8", EncodingWS::new(4, Some("utf-8".into()))),
+            ("video/vp9", EncodingWS::new(52, None)),
+            (
+                "zenoh/bytes;my/encoding;v2",
+                EncodingWS::new(0, Some("my/encoding;v2".into())),
+            ),
+            ("unknown(1000);v1", EncodingWS::new(1000, Some("v1".into()))),
+        ] {
+            assert_eq!(s.parse::<EncodingWS>().unwrap(), encoding, "{s}");
+            assert_eq!(
+                serde_json::from_value::<EncodingWS>(json!(s)).unwrap(),
+                encoding
+            );
+            if !s.is_empty() {
+                assert_eq!(encoding.to_string(), s);
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_names() {
+        // Kept as the schema of `zenoh/bytes`, as zenoh does
+        let encoding: EncodingWS = "my/encoding;v2".parse().unwrap();
+        assert_eq!(encoding, EncodingWS::new(0, Some("my/encoding;v2".into())));
+        assert_eq!(encoding.to_string(), "zenoh/bytes;my/encoding;v2");
+    }
+
+    #[test]
+    fn invalid_encodings() {
+        for json in [
+            r#""unknown(x)""#,
+            r#""unknown(65536);v1""#,
+            r#"{"id":-1}"#,
+            r#"{"id":65536}"#,
+            r#"{"schema":"utf-8"}"#,
+            r#"{"id":4,"schema":1}"#,
+            r#"{"id":4,"name":"text/plain"}"#,
+            "4",
+        ] {
+            assert!(serde_json::from_str::<EncodingWS>(json).is_err(), "{json}");
+        }
+    }
+}
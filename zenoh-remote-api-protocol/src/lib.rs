@@ -0,0 +1,38 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Messages of the Zenoh remote API, exchanged as JSON over WebSockets between the
+//! `zenoh-plugin-remote-api` plugin and its clients.
+//!
+//! The `zenoh` feature adds the conversions from the zenoh API types, used by the plugin.
+//! Without it, the crate does not depend on zenoh and builds for `wasm32-unknown-unknown`.
+
+mod codec;
+#[cfg(feature = "zenoh")]
+mod convert;
+pub mod encoding;
+mod messages;
+pub mod qos;
+pub mod schema;
+mod types;
+
+pub use codec::B64String;
+pub use encoding::EncodingWS;
+pub use messages::*;
+pub use types::*;
+pub use zenoh_keyexpr::OwnedKeyExpr;
+pub use zenoh_protocol::{
+    core::{CongestionControl, Priority, Reliability},
+    zenoh::query::ConsolidationMode,
+};
@@ -0,0 +1,635 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+use zenoh_keyexpr::OwnedKeyExpr;
+use zenoh_protocol::{
+    core::{CongestionControl, Priority, Reliability},
+    zenoh::query::ConsolidationMode,
+};
+
+use crate::{
+    qos::{
+        deserialize_congestion_control, deserialize_consolidation_mode, deserialize_priority,
+        deserialize_reliability, serialize_congestion_control, serialize_consolidation_mode,
+        serialize_priority, serialize_reliability, CongestionControlWS, ConsolidationModeWS,
+        PriorityWS, ReliabilityWS,
+    },
+    B64String, CompressionWS, EncodingWS, EntityGlobalIdWS, OwnedKeyExprWrapper, QueryReplyWS,
+    QueryWS, ReplyWS, SampleKindWS, SampleWS, SourceInfoWS, TimestampWS,
+};
+
+// ██████  ███████ ███    ███  ██████  ████████ ███████      █████  ██████  ██     ███    ███ ███████ ███████ ███████  █████   ██████  ███████
+// ██   ██ ██      ████  ████ ██    ██    ██    ██          ██   ██ ██   ██ ██     ████  ████ ██      ██      ██      ██   ██ ██       ██
+// ██████  █████   ██ ████ ██ ██    ██    ██    █████       ███████ ██████  ██     ██ ████ ██ █████   ███████ ███████ ███████ ██   ███ █████
+// ██   ██ ██      ██  ██  ██ ██    ██    ██    ██          ██   ██ ██      ██     ██  ██  ██ ██           ██      ██ ██   ██ ██    ██ ██
+// ██   ██ ███████ ██      ██  ██████     ██    ███████     ██   ██ ██      ██     ██      ██ ███████ ███████ ███████ ██   ██  ██████  ███████
+
+#[derive(TS)]
+#[ts(export)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub enum RemoteAPIMsg {
+    Data(DataMsg),
+    Control(ControlMsg),
+}
+
+#[derive(TS)]
+#[ts(export)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub enum DataMsg {
+    // Client -> SVR
+    PublisherPut {
+        id: Uuid,
+        payload: B64String,
+        attachment: Option<B64String>,
+        encoding: Option<EncodingWS>,
+        #[ts(optional)]
+        timestamp: Option<TimestampWS>,
+        #[ts(optional)]
+        source_info: Option<SourceInfoWS>,
+    },
+    PublisherDelete {
+        id: Uuid,
+        #[ts(optional)]
+        attachment: Option<B64String>,
+        #[ts(optional)]
+        timestamp: Option<TimestampWS>,
+        #[ts(optional)]
+        source_info: Option<SourceInfoWS>,
+    },
+    // SVR -> Client
+    // Subscriber
+    Sample(SampleWS, Uuid),
+    Samples(Vec<SampleWS>, Uuid),
+    // GetReply
+    GetReply(ReplyWS),
+    // Matching Listener
+    MatchingStatus {
+        id: Uuid,
+        matching: bool,
+    },
+    // Advanced Subscriber
+    SampleMiss {
+        id: Uuid,
+        source: EntityGlobalIdWS,
+        nb: u32,
+    },
+    // Bidirectional
+    Queryable(QueryableMsg),
+}
+
+#[derive(TS)]
+#[ts(export)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub enum QueryableMsg {
+    // SVR -> Client
+    // UUID of original queryable
+    Query {
+        queryable_uuid: Uuid,
+        query: QueryWS,
+    },
+    // Client -> SVR
+    Reply {
+        reply: QueryReplyWS,
+    },
+}
+
+//  ██████  ██████  ███    ██ ████████ ██████   ██████  ██          ███    ███ ███████ ███████ ███████  █████   ██████  ███████
+// ██      ██    ██ ████   ██    ██    ██   ██ ██    ██ ██          ████  ████ ██      ██      ██      ██   ██ ██       ██
+// ██      ██    ██ ██ ██  ██    ██    ██████  ██    ██ ██          ██ ████ ██ █████   ███████ ███████ ███████ ██   ███ █████
+// ██      ██    ██ ██  ██ ██    ██    ██   ██ ██    ██ ██          ██  ██  ██ ██           ██      ██ ██   ██ ██    ██ ██
+//  ██████  ██████  ██   ████    ██    ██   ██  ██████  ███████     ██      ██ ███████ ███████ ███████ ██   ██  ██████  ███████
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub enum ControlMsg {
+    // Session
+    OpenSession,
+    CloseSession,
+    Session(Uuid),
+    // Reason why a message of the client could not be handled
+    Error(String),
+
+    // Session Action Messages
+    Get {
+        #[ts(as = "OwnedKeyExprWrapper")]
+        key_expr: OwnedKeyExpr,
+        parameters: Option<String>,
+        handler: HandlerChannel,
+        id: Uuid,
+        // Parameters
+        #[schemars(with = "Option<ConsolidationModeWS>")]
+        #[serde(
+            deserialize_with = "deserialize_consolidation_mode",
+            serialize_with = "serialize_consolidation_mode",
+            default
+        )]
+        #[ts(optional, as = "Option<ConsolidationModeWS>")]
+        consolidation: Option<ConsolidationMode>,
+        // timeout: Option<ConsolidationMode>,
+        #[schemars(with = "Option<CongestionControlWS>")]
+        #[serde(
+            deserialize_with = "deserialize_congestion_control",
+            serialize_with = "serialize_congestion_control",
+            default
+        )]
+        #[ts(optional, as = "Option<CongestionControlWS>")]
+        congestion_control: Option<CongestionControl>,
+        #[schemars(with = "Option<PriorityWS>")]
+        #[serde(
+            deserialize_with = "deserialize_priority",
+            serialize_with = "serialize_priority",
+            default
+        )]
+        #[ts(optional, as = "Option<PriorityWS>")]
+        priority: Option<Priority>,
+        #[ts(type = "boolean | undefined")]
+        express: Option<bool>,
+        #[ts(optional)]
+        encoding: Option<EncodingWS>,
+        #[ts(type = "string | undefined")]
+        payload: Option<B64String>,
+        #[ts(type = "string | undefined")]
+        attachment: Option<B64String>,
+    },
+    GetFinished {
+        id: Uuid,
+    },
+    Put {
+        #[ts(as = "OwnedKeyExprWrapper")]
+        key_expr: OwnedKeyExpr,
+        payload: B64String,
+        //
+        #[ts(optional)]
+        encoding: Option<EncodingWS>,
+        #[schemars(with = "Option<CongestionControlWS>")]
+        #[serde(
+            deserialize_with = "deserialize_congestion_control",
+            serialize_with = "serialize_congestion_control",
+            default
+        )]
+        #[ts(optional, as = "Option<CongestionControlWS>")]
+        congestion_control: Option<CongestionControl>,
+        #[schemars(with = "Option<PriorityWS>")]
+        #[serde(
+            deserialize_with = "deserialize_priority",
+            serialize_with = "serialize_priority",
+            default
+        )]
+        #[ts(optional, as = "Option<PriorityWS>")]
+        priority: Option<Priority>,
+        #[ts(type = "boolean | undefined")]
+        express: Option<bool>,
+        #[ts(type = "string | undefined")]
+        attachment: Option<B64String>,
+        #[ts(optional)]
+        timestamp: Option<TimestampWS>,
+        #[ts(optional)]
+        source_info: Option<SourceInfoWS>,
+    },
+    Delete {
+        #[ts(as = "OwnedKeyExprWrapper")]
+        key_expr: OwnedKeyExpr,
+        //
+        #[schemars(with = "Option<CongestionControlWS>")]
+        #[serde(
+            deserialize_with = "deserialize_congestion_control",
+            serialize_with = "serialize_congestion_control",
+            default
+        )]
+        #[ts(optional, as = "Option<CongestionControlWS>")]
+        congestion_control: Option<CongestionControl>,
+        #[schemars(with = "Option<PriorityWS>")]
+        #[serde(
+            deserialize_with = "deserialize_priority",
+            serialize_with = "serialize_priority",
+            default
+        )]
+        #[ts(optional, as = "Option<PriorityWS>")]
+        priority: Option<Priority>,
+        #[ts(type = "boolean | undefined")]
+        express: Option<bool>,
+        #[ts(type = "string | undefined")]
+        attachment: Option<B64String>,
+        #[ts(optional)]
+        timestamp: Option<TimestampWS>,
+        #[ts(optional)]
+        source_info: Option<SourceInfoWS>,
+    },
+    // Subscriber
+    DeclareSubscriber {
+        #[ts(as = "OwnedKeyExprWrapper")]
+        key_expr: OwnedKeyExpr,
+        handler: HandlerChannel,
+        id: Uuid,
+        // Encoding the payloads are converted to before being forwarded
+        #[ts(optional)]
+        transcode_to: Option<EncodingWS>,
+        // Compression of the forwarded payloads above the configured size threshold
+        #[ts(optional)]
+        compression: Option<CompressionWS>,
+        // Samples sent in `DataMsg::Samples` batches instead of one message each
+        #[ts(optional)]
+        batch: Option<BatchConfigWS>,
+        // Rate limit of the samples, applied before batching
+        #[ts(optional)]
+        throttle: Option<ThrottleWS>,
+        // Samples forwarded only if they match all the filters, applied before throttling
+        #[ts(optional)]
+        filters: Option<Vec<SampleFilterWS>>,
+    },
+    Subscriber(Uuid),
+    UndeclareSubscriber(Uuid),
+
+    // Publisher
+    DeclarePublisher {
+        #[ts(as = "OwnedKeyExprWrapper")]
+        key_expr: OwnedKeyExpr,
+        #[ts(optional)]
+        encoding: Option<EncodingWS>,
+        #[schemars(with = "Option<CongestionControlWS>")]
+        #[serde(
+            deserialize_with = "deserialize_congestion_control",
+            serialize_with = "serialize_congestion_control",
+            default
+        )]
+        #[ts(optional, as = "Option<CongestionControlWS>")]
+        congestion_control: Option<CongestionControl>,
+        #[schemars(with = "Option<PriorityWS>")]
+        #[serde(
+            deserialize_with = "deserialize_priority",
+            serialize_with = "serialize_priority",
+            default
+        )]
+        #[ts(optional, as = "Option<PriorityWS>")]
+        priority: Option<Priority>,
+        #[schemars(with = "Option<ReliabilityWS>")]
+        #[serde(
+            deserialize_with = "deserialize_reliability",
+            serialize_with = "serialize_reliability",
+            default
+        )]
+        #[ts(optional, as = "Option<ReliabilityWS>")]
+        reliability: Option<Reliability>,
+        #[ts(type = "boolean | undefined")]
+        express: Option<bool>,
+        id: Uuid,
+    },
+    UndeclarePublisher(Uuid),
+    // Matching Listener
+    DeclareMatchingListener {
+        id: Uuid,
+        // id of a publisher, an advanced publisher or a querier
+        publisher_id: Uuid,
+    },
+    UndeclareMatchingListener(Uuid),
+    // Queryable
+    DeclareQueryable {
+        #[ts(as = "OwnedKeyExprWrapper")]
+        key_expr: OwnedKeyExpr,
+        id: Uuid,
+        complete: bool,
+    },
+    UndeclareQueryable(Uuid),
+    // Querier
+    DeclareQuerier {
+        #[ts(as = "OwnedKeyExprWrapper")]
+        key_expr: OwnedKeyExpr,
+        id: Uuid,
+        #[schemars(with = "Option<ConsolidationModeWS>")]
+        #[serde(
+            deserialize_with = "deserialize_consolidation_mode",
+            serialize_with = "serialize_consolidation_mode",
+            default
+        )]
+        #[ts(optional, as = "Option<ConsolidationModeWS>")]
+        consolidation: Option<ConsolidationMode>,
+        #[schemars(with = "Option<CongestionControlWS>")]
+        #[serde(
+            deserialize_with = "deserialize_congestion_control",
+            serialize_with = "serialize_congestion_control",
+            default
+        )]
+        #[ts(optional, as = "Option<CongestionControlWS>")]
+        congestion_control: Option<CongestionControl>,
+        #[schemars(with = "Option<PriorityWS>")]
+        #[serde(
+            deserialize_with = "deserialize_priority",
+            serialize_with = "serialize_priority",
+            default
+        )]
+        #[ts(optional, as = "Option<PriorityWS>")]
+        priority: Option<Priority>,
+        #[ts(type = "boolean | undefined")]
+        express: Option<bool>,
+        // timeout in Milliseconds
+        #[ts(type = "number | undefined")]
+        timeout: Option<u64>,
+    },
+    UndeclareQuerier(Uuid),
+    // Replies are sent like those of `Get`, ending with `GetFinished`
+    QuerierGet {
+        querier_id: Uuid,
+        id: Uuid,
+        parameters: Option<String>,
+        handler: HandlerChannel,
+        #[ts(optional)]
+        encoding: Option<EncodingWS>,
+        #[ts(type = "string | undefined")]
+        payload: Option<B64String>,
+        #[ts(type = "string | undefined")]
+        attachment: Option<B64String>,
+    },
+
+    // Advanced Publisher
+    DeclareAdvancedPublisher {
+        #[ts(as = "OwnedKeyExprWrapper")]
+        key_expr: OwnedKeyExpr,
+        #[ts(optional)]
+        encoding: Option<EncodingWS>,
+        #[schemars(with = "Option<CongestionControlWS>")]
+        #[serde(
+            deserialize_with = "deserialize_congestion_control",
+            serialize_with = "serialize_congestion_control",
+            default
+        )]
+        #[ts(optional, as = "Option<CongestionControlWS>")]
+        congestion_control: Option<CongestionControl>,
+        #[schemars(with = "Option<PriorityWS>")]
+        #[serde(
+            deserialize_with = "deserialize_priority",
+            serialize_with = "serialize_priority",
+            default
+        )]
+        #[ts(optional, as = "Option<PriorityWS>")]
+        priority: Option<Priority>,
+        #[schemars(with = "Option<ReliabilityWS>")]
+        #[serde(
+            deserialize_with = "deserialize_reliability",
+            serialize_with = "serialize_reliability",
+            default
+        )]
+        #[ts(optional, as = "Option<ReliabilityWS>")]
+        reliability: Option<Reliability>,
+        #[ts(type = "boolean | undefined")]
+        express: Option<bool>,
+        #[ts(optional)]
+        cache: Option<CacheConfigWS>,
+        #[ts(optional)]
+        sample_miss_detection: Option<MissDetectionConfigWS>,
+        #[ts(type = "boolean | undefined")]
+        publisher_detection: Option<bool>,
+        id: Uuid,
+    },
+    UndeclareAdvancedPublisher(Uuid),
+    // Advanced Subscriber
+    DeclareAdvancedSubscriber {
+        #[ts(as = "OwnedKeyExprWrapper")]
+        key_expr: OwnedKeyExpr,
+        handler: HandlerChannel,
+        id: Uuid,
+        #[ts(optional)]
+        history: Option<HistoryConfigWS>,
+        #[ts(optional)]
+        recovery: Option<RecoveryConfigWS>,
+        #[ts(type = "boolean | undefined")]
+        subscriber_detection: Option<bool>,
+    },
+    UndeclareAdvancedSubscriber(Uuid),
+
+    // Liveliness
+    Liveliness(LivelinessMsg),
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub struct CacheConfigWS {
+    #[ts(type = "number | undefined")]
+    pub max_samples: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub struct MissDetectionConfigWS {
+    // heartbeat period in Milliseconds
+    #[ts(type = "number | undefined")]
+    pub heartbeat: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub struct HistoryConfigWS {
+    #[ts(type = "boolean | undefined")]
+    pub detect_late_publishers: Option<bool>,
+    #[ts(type = "number | undefined")]
+    pub max_samples: Option<usize>,
+    // max age in Milliseconds
+    #[ts(type = "number | undefined")]
+    pub max_age: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub struct RecoveryConfigWS {
+    // period of the recovery queries in Milliseconds,
+    // recovery is triggered by publisher heartbeats if unset
+    #[ts(type = "number | undefined")]
+    pub periodic_queries: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub struct BatchConfigWS {
+    // maximum number of samples in a batch
+    pub max_size: usize,
+    // maximum time a sample waits for its batch to be sent, in Milliseconds
+    #[ts(type = "number")]
+    pub max_latency: u64,
+}
+
+// Periods in Milliseconds
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub enum ThrottleWS {
+    // at most one sample per key expression and period, the others are dropped
+    MaxFrequency {
+        #[ts(type = "number")]
+        period: u64,
+    },
+    // the latest sample of each key expression, sent at most once per period
+    KeepLatest {
+        #[ts(type = "number")]
+        period: u64,
+    },
+    // the latest sample of each period, whatever its key expression
+    Window {
+        #[ts(type = "number")]
+        period: u64,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub enum SampleFilterWS {
+    Kind(SampleKindWS),
+    // the attachment, read as `<key>=<value>;...` parameters, holds the key, with this value if set
+    Attachment {
+        key: String,
+        #[ts(optional)]
+        value: Option<String>,
+    },
+    // the value at the path of the JSON payload, as `$.a.b[0]`, matches the predicate
+    Json {
+        path: String,
+        predicate: JsonPredicateWS,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub enum JsonPredicateWS {
+    Exists,
+    Eq(#[ts(type = "unknown")] serde_json::Value),
+    Ne(#[ts(type = "unknown")] serde_json::Value),
+    Lt(f64),
+    Le(f64),
+    Gt(f64),
+    Ge(f64),
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub enum LivelinessMsg {
+    DeclareToken {
+        #[ts(as = "OwnedKeyExprWrapper")]
+        key_expr: OwnedKeyExpr,
+        id: Uuid,
+    },
+    UndeclareToken(Uuid),
+    DeclareSubscriber {
+        #[ts(as = "OwnedKeyExprWrapper")]
+        key_expr: OwnedKeyExpr,
+        id: Uuid,
+        history: bool,
+    },
+    UndeclareSubscriber(Uuid),
+    Get {
+        #[ts(as = "OwnedKeyExprWrapper")]
+        key_expr: OwnedKeyExpr,
+        id: Uuid,
+        // timeout in Milliseconds
+        #[ts(type = "number | undefined")]
+        timeout: Option<u64>,
+    },
+}
+
+impl RemoteAPIMsg {
+    /// Name of the message variant, used to label metrics
+    pub fn label(&self) -> &'static str {
+        match self {
+            RemoteAPIMsg::Data(data_msg) => data_msg.label(),
+            RemoteAPIMsg::Control(ctrl_msg) => ctrl_msg.label(),
+        }
+    }
+}
+
+impl DataMsg {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DataMsg::PublisherPut { .. } => "DataMsg::PublisherPut",
+            DataMsg::PublisherDelete { .. } => "DataMsg::PublisherDelete",
+            DataMsg::Sample(..) => "DataMsg::Sample",
+            DataMsg::Samples(..) => "DataMsg::Samples",
+            DataMsg::GetReply(_) => "DataMsg::GetReply",
+            DataMsg::MatchingStatus { .. } => "DataMsg::MatchingStatus",
+            DataMsg::SampleMiss { .. } => "DataMsg::SampleMiss",
+            DataMsg::Queryable(QueryableMsg::Query { .. }) => "QueryableMsg::Query",
+            DataMsg::Queryable(QueryableMsg::Reply { .. }) => "QueryableMsg::Reply",
+        }
+    }
+}
+
+impl ControlMsg {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ControlMsg::OpenSession => "ControlMsg::OpenSession",
+            ControlMsg::CloseSession => "ControlMsg::CloseSession",
+            ControlMsg::Session(_) => "ControlMsg::Session",
+            ControlMsg::Error(_) => "ControlMsg::Error",
+            ControlMsg::Get { .. } => "ControlMsg::Get",
+            ControlMsg::GetFinished { .. } => "ControlMsg::GetFinished",
+            ControlMsg::Put { .. } => "ControlMsg::Put",
+            ControlMsg::Delete { .. } => "ControlMsg::Delete",
+            ControlMsg::DeclareSubscriber { .. } => "ControlMsg::DeclareSubscriber",
+            ControlMsg::Subscriber(_) => "ControlMsg::Subscriber",
+            ControlMsg::UndeclareSubscriber(_) => "ControlMsg::UndeclareSubscriber",
+            ControlMsg::DeclarePublisher { .. } => "ControlMsg::DeclarePublisher",
+            ControlMsg::UndeclarePublisher(_) => "ControlMsg::UndeclarePublisher",
+            ControlMsg::DeclareMatchingListener { .. } => "ControlMsg::DeclareMatchingListener",
+            ControlMsg::UndeclareMatchingListener(_) => "ControlMsg::UndeclareMatchingListener",
+            ControlMsg::DeclareQueryable { .. } => "ControlMsg::DeclareQueryable",
+            ControlMsg::UndeclareQueryable(_) => "ControlMsg::UndeclareQueryable",
+            ControlMsg::DeclareQuerier { .. } => "ControlMsg::DeclareQuerier",
+            ControlMsg::UndeclareQuerier(_) => "ControlMsg::UndeclareQuerier",
+            ControlMsg::QuerierGet { .. } => "ControlMsg::QuerierGet",
+            ControlMsg::DeclareAdvancedPublisher { .. } => "ControlMsg::DeclareAdvancedPublisher",
+            ControlMsg::UndeclareAdvancedPublisher(_) => "ControlMsg::UndeclareAdvancedPublisher",
+            ControlMsg::DeclareAdvancedSubscriber { .. } => "ControlMsg::DeclareAdvancedSubscriber",
+            ControlMsg::UndeclareAdvancedSubscriber(_) => "ControlMsg::UndeclareAdvancedSubscriber",
+            ControlMsg::Liveliness(LivelinessMsg::DeclareToken { .. }) => {
+                "LivelinessMsg::DeclareToken"
+            }
+            ControlMsg::Liveliness(LivelinessMsg::UndeclareToken(_)) => {
+                "LivelinessMsg::UndeclareToken"
+            }
+            ControlMsg::Liveliness(LivelinessMsg::DeclareSubscriber { .. }) => {
+                "LivelinessMsg::DeclareSubscriber"
+            }
+            ControlMsg::Liveliness(LivelinessMsg::UndeclareSubscriber(_)) => {
+                "LivelinessMsg::UndeclareSubscriber"
+            }
+            ControlMsg::Liveliness(LivelinessMsg::Get { .. }) => "LivelinessMsg::Get",
+        }
+    }
+
+    /// Key expression the message declares an entity on or accesses, if any
+    pub fn key_expr(&self) -> Option<&OwnedKeyExpr> {
+        match self {
+            ControlMsg::Get { key_expr, .. }
+            | ControlMsg::Put { key_expr, .. }
+            | ControlMsg::Delete { key_expr, .. }
+            | ControlMsg::DeclareSubscriber { key_expr, .. }
+            | ControlMsg::DeclarePublisher { key_expr, .. }
+            | ControlMsg::DeclareQueryable { key_expr, .. }
+            | ControlMsg::DeclareQuerier { key_expr, .. }
+            | ControlMsg::DeclareAdvancedPublisher { key_expr, .. }
+            | ControlMsg::DeclareAdvancedSubscriber { key_expr, .. }
+            | ControlMsg::Liveliness(
+                LivelinessMsg::DeclareToken { key_expr, .. }
+                | LivelinessMsg::DeclareSubscriber { key_expr, .. }
+                | LivelinessMsg::Get { key_expr, .. },
+            ) => Some(key_expr),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, TS)]
+pub enum HandlerChannel {
+    Fifo(usize),
+    Ring(usize),
+}
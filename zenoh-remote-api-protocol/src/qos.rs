@@ -0,0 +1,499 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Serde helpers of the QoS settings, sent by their names ("RealTime", "Block", "BestEffort",
+//! "Latest"...), and accepted either by their names or by their `u8` representation.
+
+use std::{fmt, marker::PhantomData};
+
+use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema};
+use serde::{
+    de::{self, Visitor},
+    Deserializer, Serializer,
+};
+use serde_json::json;
+use ts_rs::TS;
+use zenoh_protocol::{
+    core::{CongestionControl, Priority, Reliability},
+    zenoh::query::ConsolidationMode,
+};
+
+pub(crate) trait QosName: Sized {
+    const TYPE: &'static str;
+
+    fn name(&self) -> &'static str;
+
+    fn from_name(name: &str) -> Option<Self>;
+
+    fn from_u8(value: u8) -> Option<Self>;
+}
+
+impl QosName for ConsolidationMode {
+    const TYPE: &'static str = "ConsolidationMode";
+
+    fn name(&self) -> &'static str {
+        match self {
+            ConsolidationMode::Auto => "Auto",
+            ConsolidationMode::None => "None",
+            ConsolidationMode::Monotonic => "Monotonic",
+            ConsolidationMode::Latest => "Latest",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Auto" => Some(ConsolidationMode::Auto),
+            "None" => Some(ConsolidationMode::None),
+            "Monotonic" => Some(ConsolidationMode::Monotonic),
+            "Latest" => Some(ConsolidationMode::Latest),
+            _ => None,
+        }
+    }
+
+    fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(ConsolidationMode::Auto),
+            1 => Some(ConsolidationMode::None),
+            2 => Some(ConsolidationMode::Monotonic),
+            3 => Some(ConsolidationMode::Latest),
+            _ => None,
+        }
+    }
+}
+
+impl QosName for CongestionControl {
+    const TYPE: &'static str = "CongestionControl";
+
+    fn name(&self) -> &'static str {
+        // BlockFirst only exists with the unstable feature of zenoh, and isn't part of the
+        // protocol: it is sent as Block, which it behaves like until the first message is sent
+        match *self as u8 {
+            0 => "Drop",
+            _ => "Block",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Drop" => Some(CongestionControl::Drop),
+            "Block" => Some(CongestionControl::Block),
+            _ => None,
+        }
+    }
+
+    fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(CongestionControl::Drop),
+            1 => Some(CongestionControl::Block),
+            _ => None,
+        }
+    }
+}
+
+impl QosName for Priority {
+    const TYPE: &'static str = "Priority";
+
+    fn name(&self) -> &'static str {
+        match self {
+            Priority::Control => "Control",
+            Priority::RealTime => "RealTime",
+            Priority::InteractiveHigh => "InteractiveHigh",
+            Priority::InteractiveLow => "InteractiveLow",
+            Priority::DataHigh => "DataHigh",
+            Priority::Data => "Data",
+            Priority::DataLow => "DataLow",
+            Priority::Background => "Background",
+        }
+    }
+
+    // Control is reserved to zenoh
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "RealTime" => Some(Priority::RealTime),
+            "InteractiveHigh" => Some(Priority::InteractiveHigh),
+            "InteractiveLow" => Some(Priority::InteractiveLow),
+            "DataHigh" => Some(Priority::DataHigh),
+            "Data" => Some(Priority::Data),
+            "DataLow" => Some(Priority::DataLow),
+            "Background" => Some(Priority::Background),
+            _ => None,
+        }
+    }
+
+    fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            1 => Some(Priority::RealTime),
+            2 => Some(Priority::InteractiveHigh),
+            3 => Some(Priority::InteractiveLow),
+            4 => Some(Priority::DataHigh),
+            5 => Some(Priority::Data),
+            6 => Some(Priority::DataLow),
+            7 => Some(Priority::Background),
+            _ => None,
+        }
+    }
+}
+
+impl QosName for Reliability {
+    const TYPE: &'static str = "Reliability";
+
+    fn name(&self) -> &'static str {
+        match self {
+            Reliability::BestEffort => "BestEffort",
+            Reliability::Reliable => "Reliable",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "BestEffort" => Some(Reliability::BestEffort),
+            "Reliable" => Some(Reliability::Reliable),
+            _ => None,
+        }
+    }
+
+    // As sent by the TypeScript bindings, the reverse of the zenoh representation
+    fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Reliability::Reliable),
+            1 => Some(Reliability::BestEffort),
+            _ => None,
+        }
+    }
+}
+
+struct QosVisitor<T>(PhantomData<T>);
+
+impl<'de, T: QosName> Visitor<'de> for QosVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a {} name or number", T::TYPE)
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<T, E> {
+        u8::try_from(value)
+            .ok()
+            .and_then(T::from_u8)
+            .ok_or_else(|| E::custom(format!("Value not valid for {} Enum {:?}", T::TYPE, value)))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<T, E> {
+        u8::try_from(value)
+            .ok()
+            .and_then(T::from_u8)
+            .ok_or_else(|| E::custom(format!("Value not valid for {} Enum {:?}", T::TYPE, value)))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<T, E> {
+        T::from_name(value)
+            .ok_or_else(|| E::custom(format!("Value not valid for {} Enum {:?}", T::TYPE, value)))
+    }
+}
+
+struct OptionQosVisitor<T>(PhantomData<T>);
+
+impl<'de, T: QosName> Visitor<'de> for OptionQosVisitor<T> {
+    type Value = Option<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a {} name or number", T::TYPE)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Option<T>, E> {
+        Ok(None)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Option<T>, E> {
+        Ok(None)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, d: D) -> Result<Option<T>, D::Error> {
+        deserialize_qos(d).map(Some)
+    }
+}
+
+pub(crate) fn deserialize_qos<'de, T: QosName, D: Deserializer<'de>>(d: D) -> Result<T, D::Error> {
+    d.deserialize_any(QosVisitor(PhantomData))
+}
+
+pub(crate) fn serialize_qos<T: QosName, S: Serializer>(value: &T, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(value.name())
+}
+
+fn deserialize_option<'de, T: QosName, D: Deserializer<'de>>(d: D) -> Result<Option<T>, D::Error> {
+    d.deserialize_option(OptionQosVisitor(PhantomData))
+}
+
+fn serialize_option<T: QosName, S: Serializer>(value: &Option<T>, s: S) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(value) => s.serialize_str(value.name()),
+        None => s.serialize_none(),
+    }
+}
+
+pub fn deserialize_consolidation_mode<'de, D>(d: D) -> Result<Option<ConsolidationMode>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_option(d)
+}
+
+pub fn serialize_consolidation_mode<S>(
+    consolidation_mode: &Option<ConsolidationMode>,
+    s: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serialize_option(consolidation_mode, s)
+}
+
+pub fn deserialize_congestion_control<'de, D>(d: D) -> Result<Option<CongestionControl>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_option(d)
+}
+
+pub fn serialize_congestion_control<S>(
+    congestion_control: &Option<CongestionControl>,
+    s: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serialize_option(congestion_control, s)
+}
+
+pub fn deserialize_priority<'de, D>(d: D) -> Result<Option<Priority>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_option(d)
+}
+
+pub fn serialize_priority<S>(priority: &Option<Priority>, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serialize_option(priority, s)
+}
+
+pub fn deserialize_reliability<'de, D>(d: D) -> Result<Option<Reliability>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_option(d)
+}
+
+pub fn serialize_reliability<S>(reliability: &Option<Reliability>, s: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serialize_option(reliability, s)
+}
+
+// Types of the TypeScript bindings and of the JSON Schema only: the names, as sent, or the numbers
+
+fn name_or_number_schema(names: &[&str], min: u8, max: u8) -> Schema {
+    serde_json::from_value(json!({
+        "anyOf": [
+            { "type": "string", "enum": names },
+            { "type": "integer", "minimum": min, "maximum": max },
+        ]
+    }))
+    .expect("QoS schema should deserialize")
+}
+
+#[allow(dead_code)]
+#[derive(TS)]
+#[ts(export)]
+pub enum ConsolidationModeWS {
+    Auto,
+    None,
+    Monotonic,
+    Latest,
+}
+
+impl JsonSchema for ConsolidationModeWS {
+    fn schema_name() -> String {
+        "ConsolidationModeWS".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        name_or_number_schema(&["Auto", "None", "Monotonic", "Latest"], 0, 3)
+    }
+}
+
+#[allow(dead_code)]
+#[derive(TS)]
+#[ts(export)]
+pub enum CongestionControlWS {
+    Drop,
+    Block,
+}
+
+impl JsonSchema for CongestionControlWS {
+    fn schema_name() -> String {
+        "CongestionControlWS".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        name_or_number_schema(&["Drop", "Block"], 0, 1)
+    }
+}
+
+#[allow(dead_code)]
+#[derive(TS)]
+#[ts(export)]
+pub enum PriorityWS {
+    RealTime,
+    InteractiveHigh,
+    InteractiveLow,
+    DataHigh,
+    Data,
+    DataLow,
+    Background,
+}
+
+impl JsonSchema for PriorityWS {
+    fn schema_name() -> String {
+        "PriorityWS".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        name_or_number_schema(
+            &[
+                "RealTime",
+                "InteractiveHigh",
+                "InteractiveLow",
+                "DataHigh",
+                "Data",
+                "DataLow",
+                "Background",
+            ],
+            1,
+            7,
+        )
+    }
+}
+
+#[allow(dead_code)]
+#[derive(TS)]
+#[ts(export)]
+pub enum ReliabilityWS {
+    BestEffort,
+    Reliable,
+}
+
+impl JsonSchema for ReliabilityWS {
+    fn schema_name() -> String {
+        "ReliabilityWS".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        name_or_number_schema(&["BestEffort", "Reliable"], 0, 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Qos {
+        #[serde(
+            deserialize_with = "deserialize_consolidation_mode",
+            serialize_with = "serialize_consolidation_mode",
+            default
+        )]
+        consolidation: Option<ConsolidationMode>,
+        #[serde(
+            deserialize_with = "deserialize_congestion_control",
+            serialize_with = "serialize_congestion_control",
+            default
+        )]
+        congestion_control: Option<CongestionControl>,
+        #[serde(
+            deserialize_with = "deserialize_priority",
+            serialize_with = "serialize_priority",
+            default
+        )]
+        priority: Option<Priority>,
+        #[serde(
+            deserialize_with = "deserialize_reliability",
+            serialize_with = "serialize_reliability",
+            default
+        )]
+        reliability: Option<Reliability>,
+    }
+
+    #[test]
+    fn names_and_numbers() {
+        let expected = Qos {
+            consolidation: Some(ConsolidationMode::Latest),
+            congestion_control: Some(CongestionControl::Block),
+            priority: Some(Priority::RealTime),
+            reliability: Some(Reliability::BestEffort),
+        };
+        let names: Qos = serde_json::from_str(
+            r#"{"consolidation":"Latest","congestion_control":"Block","priority":"RealTime","reliability":"BestEffort"}"#,
+        )
+        .unwrap();
+        assert_eq!(names, expected);
+        let numbers: Qos = serde_json::from_str(
+            r#"{"consolidation":3,"congestion_control":1,"priority":1,"reliability":1}"#,
+        )
+        .unwrap();
+        assert_eq!(numbers, expected);
+
+        assert_eq!(
+            serde_json::to_string(&expected).unwrap(),
+            r#"{"consolidation":"Latest","congestion_control":"Block","priority":"RealTime","reliability":"BestEffort"}"#
+        );
+    }
+
+    #[test]
+    fn missing_and_null() {
+        let none = Qos {
+            consolidation: None,
+            congestion_control: None,
+            priority: None,
+            reliability: None,
+        };
+        assert_eq!(serde_json::from_str::<Qos>("{}").unwrap(), none);
+        assert_eq!(
+            serde_json::from_str::<Qos>(r#"{"priority":null}"#).unwrap(),
+            none
+        );
+    }
+
+    #[test]
+    fn invalid_values() {
+        for json in [
+            r#"{"priority":0}"#,
+            r#"{"priority":"Control"}"#,
+            r#"{"priority":-1}"#,
+            r#"{"priority":300}"#,
+            r#"{"congestion_control":"block"}"#,
+            r#"{"reliability":2}"#,
+            r#"{"consolidation":true}"#,
+        ] {
+            assert!(serde_json::from_str::<Qos>(json).is_err(), "{json}");
+        }
+    }
+}
@@ -0,0 +1,623 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! JSON Schema and AsyncAPI descriptions of the wire protocol, for clients written in
+//! other languages than TypeScript.
+
+use schemars::schema_for;
+use serde_json::{json, Map, Value};
+
+use crate::RemoteAPIMsg;
+
+/// Which side of the WebSocket sends a message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    ClientToPlugin,
+    PluginToClient,
+}
+
+use Direction::{ClientToPlugin, PluginToClient};
+
+// The variants of an enum of messages, nested in `RemoteAPIMsg` along `path`
+struct MessageGroup {
+    definition: &'static str,
+    path: &'static [&'static str],
+    variants: &'static [(&'static str, Direction)],
+}
+
+const MESSAGE_GROUPS: [MessageGroup; 4] = [
+    MessageGroup {
+        definition: "ControlMsg",
+        path: &["Control"],
+        variants: &[
+            ("OpenSession", ClientToPlugin),
+            ("CloseSession", ClientToPlugin),
+            ("Session", PluginToClient),
+            ("Error", PluginToClient),
+            ("Get", ClientToPlugin),
+            ("GetFinished", PluginToClient),
+            ("Put", ClientToPlugin),
+            ("Delete", ClientToPlugin),
+            ("DeclareSubscriber", ClientToPlugin),
+            ("Subscriber", PluginToClient),
+            ("UndeclareSubscriber", ClientToPlugin),
+            ("DeclarePublisher", ClientToPlugin),
+            ("UndeclarePublisher", ClientToPlugin),
+            ("DeclareMatchingListener", ClientToPlugin),
+            ("UndeclareMatchingListener", ClientToPlugin),
+            ("DeclareQueryable", ClientToPlugin),
+            ("UndeclareQueryable", ClientToPlugin),
+            ("DeclareQuerier", ClientToPlugin),
+            ("UndeclareQuerier", ClientToPlugin),
+            ("QuerierGet", ClientToPlugin),
+            ("DeclareAdvancedPublisher", ClientToPlugin),
+            ("UndeclareAdvancedPublisher", ClientToPlugin),
+            ("DeclareAdvancedSubscriber", ClientToPlugin),
+            ("UndeclareAdvancedSubscriber", ClientToPlugin),
+        ],
+    },
+    MessageGroup {
+        definition: "LivelinessMsg",
+        path: &["Control", "Liveliness"],
+        variants: &[
+            ("DeclareToken", ClientToPlugin),
+            ("UndeclareToken", ClientToPlugin),
+            ("DeclareSubscriber", ClientToPlugin),
+            ("UndeclareSubscriber", ClientToPlugin),
+            ("Get", ClientToPlugin),
+        ],
+    },
+    MessageGroup {
+        definition: "DataMsg",
+        path: &["Data"],
+        variants: &[
+            ("PublisherPut", ClientToPlugin),
+            ("PublisherDelete", ClientToPlugin),
+            ("Sample", PluginToClient),
+            ("Samples", PluginToClient),
+            ("GetReply", PluginToClient),
+            ("MatchingStatus", PluginToClient),
+            ("SampleMiss", PluginToClient),
+        ],
+    },
+    MessageGroup {
+        definition: "QueryableMsg",
+        path: &["Data", "Queryable"],
+        variants: &[("Query", PluginToClient), ("Reply", ClientToPlugin)],
+    },
+];
+
+/// A message of the protocol, named as [`RemoteAPIMsg::label`]
+pub struct MessageDescription {
+    pub name: String,
+    pub direction: Direction,
+    /// Schema of the whole `RemoteAPIMsg`, referring to the definitions of [`json_schema`]
+    pub payload: Value,
+}
+
+/// JSON Schema (draft 7) of `RemoteAPIMsg`, with all the types it contains in `definitions`
+pub fn json_schema() -> Value {
+    serde_json::to_value(schema_for!(RemoteAPIMsg)).expect("JSON Schema should serialize")
+}
+
+/// Every message of the protocol, with the schema of its variant only
+pub fn messages() -> Vec<MessageDescription> {
+    let schema = json_schema();
+    let definitions = &schema["definitions"];
+    MESSAGE_GROUPS
+        .iter()
+        .flat_map(|group| {
+            group.variants.iter().map(move |(variant, direction)| {
+                let variant_schema = variant_schema(&definitions[group.definition], variant)
+                    .unwrap_or_else(|| panic!("{} has no variant {variant}", group.definition));
+                let payload = group
+                    .path
+                    .iter()
+                    .rev()
+                    .fold(variant_schema, |schema, tag| tagged(tag, schema));
+                MessageDescription {
+                    name: format!("{}::{variant}", group.definition),
+                    direction: *direction,
+                    payload,
+                }
+            })
+        })
+        .collect()
+}
+
+/// AsyncAPI 2.6 document of the protocol, a single WebSocket channel carrying the
+/// messages sent by the client (`publish`) and by the plugin (`subscribe`)
+pub fn asyncapi() -> Value {
+    let schema = json_schema();
+    let mut components = Map::new();
+    let mut publish = Vec::new();
+    let mut subscribe = Vec::new();
+    for message in messages() {
+        // AsyncAPI component names cannot contain ':'
+        let key = message.name.replace("::", ".");
+        let reference = json!({ "$ref": format!("#/components/messages/{key}") });
+        match message.direction {
+            ClientToPlugin => publish.push(reference),
+            PluginToClient => subscribe.push(reference),
+        }
+        let mut payload = message.payload;
+        rewrite_refs(&mut payload);
+        components.insert(
+            key.clone(),
+            json!({ "name": key, "title": message.name, "payload": payload }),
+        );
+    }
+
+    let mut schemas = schema["definitions"].clone();
+    rewrite_refs(&mut schemas);
+
+    json!({
+        "asyncapi": "2.6.0",
+        "info": {
+            "title": "Zenoh remote API",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "Zenoh sessions driven over a WebSocket, each message being a JSON encoded RemoteAPIMsg in a text frame",
+        },
+        "defaultContentType": "application/json",
+        "servers": {
+            "remote_api": {
+                "url": "localhost:10000",
+                "protocol": "ws",
+                "description": "The `websocket_port` of the remote_api plugin, `wss` with `secure_websocket`",
+            },
+        },
+        "channels": {
+            "/": {
+                "publish": {
+                    "operationId": "clientToPlugin",
+                    "summary": "Messages sent by the client to the plugin",
+                    "message": { "oneOf": publish },
+                },
+                "subscribe": {
+                    "operationId": "pluginToClient",
+                    "summary": "Messages sent by the plugin to the client",
+                    "message": { "oneOf": subscribe },
+                },
+            },
+        },
+        "components": {
+            "messages": components,
+            "schemas": schemas,
+        },
+    })
+}
+
+// Schema of one variant of an externally tagged enum, unit variants being grouped in a
+// string enum by schemars
+fn variant_schema(enum_schema: &Value, variant: &str) -> Option<Value> {
+    enum_schema["oneOf"]
+        .as_array()?
+        .iter()
+        .find_map(|schema| match &schema["enum"] {
+            Value::Array(names) if names.iter().any(|name| name == variant) => {
+                Some(json!({ "type": "string", "enum": [variant] }))
+            }
+            _ if schema["properties"].get(variant).is_some() => Some(schema.clone()),
+            _ => None,
+        })
+}
+
+// Schema of `{ tag: <schema> }`
+fn tagged(tag: &str, schema: Value) -> Value {
+    json!({
+        "type": "object",
+        "required": [tag],
+        "properties": { tag: schema },
+        "additionalProperties": false,
+    })
+}
+
+fn rewrite_refs(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                match value {
+                    Value::String(reference) if key == "$ref" => {
+                        *reference = reference.replace("#/definitions/", "#/components/schemas/");
+                    }
+                    value => rewrite_refs(value),
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(rewrite_refs),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, str::FromStr};
+
+    use uuid::Uuid;
+    use zenoh_keyexpr::{keyexpr, OwnedKeyExpr};
+
+    use super::*;
+    use crate::{
+        B64String, BatchConfigWS, CacheConfigWS, CompressionWS, ControlMsg, DataMsg, EncodingWS,
+        EntityGlobalIdWS, HandlerChannel, HistoryConfigWS, JsonPredicateWS, LivelinessMsg,
+        MissDetectionConfigWS, QueryReplyVariant, QueryReplyWS, QueryWS, QueryableMsg,
+        RecoveryConfigWS, ReplyErrorWS, ReplyWS, SampleFilterWS, SampleKindWS, SampleWS,
+        SourceInfoWS, ThrottleWS, TimestampWS,
+    };
+
+    fn ke() -> OwnedKeyExpr {
+        keyexpr::new("demo/test").unwrap().to_owned()
+    }
+
+    fn b64() -> B64String {
+        B64String::encode([1, 2, 3])
+    }
+
+    fn sample() -> SampleWS {
+        SampleWS::new(ke(), b64(), SampleKindWS::Put, EncodingWS::default())
+    }
+
+    fn text_plain() -> EncodingWS {
+        EncodingWS::new(4, Some("utf-8".into()))
+    }
+
+    fn source_info() -> SourceInfoWS {
+        SourceInfoWS::new(EntityGlobalIdWS::new("a1b2".into(), 3), 42)
+    }
+
+    // One message of each variant
+    fn all_messages() -> Vec<RemoteAPIMsg> {
+        let id = Uuid::from_str("a2663bb1-128c-4dd3-a42b-d1d3337e2e51").unwrap();
+        let control = [
+            ControlMsg::OpenSession,
+            ControlMsg::CloseSession,
+            ControlMsg::Session(id),
+            ControlMsg::Error("Invalid encoding".into()),
+            ControlMsg::Get {
+                key_expr: ke(),
+                parameters: Some("a=1".into()),
+                handler: HandlerChannel::Ring(8),
+                id,
+                consolidation: Some(crate::ConsolidationMode::Latest),
+                congestion_control: Some(crate::CongestionControl::Block),
+                priority: Some(crate::Priority::RealTime),
+                express: Some(true),
+                encoding: Some(text_plain()),
+                payload: Some(b64()),
+                attachment: None,
+            },
+            ControlMsg::GetFinished { id },
+            ControlMsg::Put {
+                key_expr: ke(),
+                payload: b64(),
+                encoding: None,
+                congestion_control: None,
+                priority: Some(crate::Priority::Background),
+                express: None,
+                attachment: Some(b64()),
+                timestamp: Some(TimestampWS::new(1, 2, "33".into())),
+                source_info: Some(source_info()),
+            },
+            ControlMsg::Delete {
+                key_expr: ke(),
+                congestion_control: Some(crate::CongestionControl::Drop),
+                priority: None,
+                express: None,
+                attachment: None,
+                timestamp: None,
+                source_info: None,
+            },
+            ControlMsg::DeclareSubscriber {
+                key_expr: ke(),
+                handler: HandlerChannel::Fifo(256),
+                id,
+                transcode_to: Some(EncodingWS::new(5, None)),
+                compression: Some(CompressionWS::Zstd),
+                batch: Some(BatchConfigWS {
+                    max_size: 100,
+                    max_latency: 10,
+                }),
+                throttle: Some(ThrottleWS::KeepLatest { period: 100 }),
+                filters: Some(vec![
+                    SampleFilterWS::Kind(SampleKindWS::Put),
+                    SampleFilterWS::Attachment {
+                        key: "unit".into(),
+                        value: Some("C".into()),
+                    },
+                    SampleFilterWS::Json {
+                        path: "$.temperature".into(),
+                        predicate: JsonPredicateWS::Gt(30.0),
+                    },
+                    SampleFilterWS::Json {
+                        path: "$.tags[0]".into(),
+                        predicate: JsonPredicateWS::Eq(json!("alarm")),
+                    },
+                ]),
+            },
+            ControlMsg::Subscriber(id),
+            ControlMsg::UndeclareSubscriber(id),
+            ControlMsg::DeclarePublisher {
+                key_expr: ke(),
+                encoding: None,
+                congestion_control: None,
+                priority: None,
+                reliability: Some(crate::Reliability::BestEffort),
+                express: None,
+                id,
+            },
+            ControlMsg::UndeclarePublisher(id),
+            ControlMsg::DeclareMatchingListener {
+                id,
+                publisher_id: id,
+            },
+            ControlMsg::UndeclareMatchingListener(id),
+            ControlMsg::DeclareQueryable {
+                key_expr: ke(),
+                id,
+                complete: true,
+            },
+            ControlMsg::UndeclareQueryable(id),
+            ControlMsg::DeclareQuerier {
+                key_expr: ke(),
+                id,
+                consolidation: None,
+                congestion_control: None,
+                priority: None,
+                express: None,
+                timeout: Some(10_000),
+            },
+            ControlMsg::UndeclareQuerier(id),
+            ControlMsg::QuerierGet {
+                querier_id: id,
+                id,
+                parameters: None,
+                handler: HandlerChannel::Fifo(16),
+                encoding: None,
+                payload: None,
+                attachment: None,
+            },
+            ControlMsg::DeclareAdvancedPublisher {
+                key_expr: ke(),
+                encoding: None,
+                congestion_control: None,
+                priority: None,
+                reliability: None,
+                express: None,
+                cache: Some(CacheConfigWS {
+                    max_samples: Some(10),
+                }),
+                sample_miss_detection: Some(MissDetectionConfigWS {
+                    heartbeat: Some(500),
+                }),
+                publisher_detection: Some(true),
+                id,
+            },
+            ControlMsg::UndeclareAdvancedPublisher(id),
+            ControlMsg::DeclareAdvancedSubscriber {
+                key_expr: ke(),
+                handler: HandlerChannel::Fifo(256),
+                id,
+                history: Some(HistoryConfigWS {
+                    detect_late_publishers: Some(true),
+                    max_samples: None,
+                    max_age: Some(1500),
+                }),
+                recovery: Some(RecoveryConfigWS {
+                    periodic_queries: None,
+                }),
+                subscriber_detection: None,
+            },
+            ControlMsg::UndeclareAdvancedSubscriber(id),
+            ControlMsg::Liveliness(LivelinessMsg::DeclareToken { key_expr: ke(), id }),
+            ControlMsg::Liveliness(LivelinessMsg::UndeclareToken(id)),
+            ControlMsg::Liveliness(LivelinessMsg::DeclareSubscriber {
+                key_expr: ke(),
+                id,
+                history: true,
+            }),
+            ControlMsg::Liveliness(LivelinessMsg::UndeclareSubscriber(id)),
+            ControlMsg::Liveliness(LivelinessMsg::Get {
+                key_expr: ke(),
+                id,
+                timeout: Some(1000),
+            }),
+        ];
+        let data = [
+            DataMsg::PublisherPut {
+                id,
+                payload: b64(),
+                attachment: None,
+                encoding: Some(text_plain()),
+                timestamp: None,
+                source_info: Some(source_info()),
+            },
+            DataMsg::PublisherDelete {
+                id,
+                attachment: Some(b64()),
+                timestamp: Some(TimestampWS::new(1, 2, "33".into())),
+                source_info: None,
+            },
+            DataMsg::Sample(sample(), id),
+            DataMsg::Samples(vec![sample(), sample()], id),
+            DataMsg::GetReply(ReplyWS::new(id, Ok(sample()))),
+            DataMsg::GetReply(ReplyWS::new(
+                id,
+                Err(ReplyErrorWS::new(b64(), text_plain())),
+            )),
+            DataMsg::MatchingStatus { id, matching: true },
+            DataMsg::SampleMiss {
+                id,
+                source: EntityGlobalIdWS::new("a1b2".into(), 3),
+                nb: 2,
+            },
+            DataMsg::Queryable(QueryableMsg::Query {
+                queryable_uuid: id,
+                query: QueryWS::new(id, ke(), "a=1".into(), None, None, Some(b64())),
+            }),
+            DataMsg::Queryable(QueryableMsg::Reply {
+                reply: QueryReplyWS::new(
+                    id,
+                    QueryReplyVariant::Reply {
+                        key_expr: ke(),
+                        payload: b64(),
+                    },
+                ),
+            }),
+            DataMsg::Queryable(QueryableMsg::Reply {
+                reply: QueryReplyWS::new(id, QueryReplyVariant::ReplyErr { payload: b64() }),
+            }),
+            DataMsg::Queryable(QueryableMsg::Reply {
+                reply: QueryReplyWS::new(id, QueryReplyVariant::ReplyDelete { key_expr: ke() }),
+            }),
+        ];
+        control
+            .into_iter()
+            .map(RemoteAPIMsg::Control)
+            .chain(data.into_iter().map(RemoteAPIMsg::Data))
+            .collect()
+    }
+
+    fn validator(mut schema: Value) -> jsonschema::JSONSchema {
+        schema["definitions"] = json_schema()["definitions"].clone();
+        jsonschema::JSONSchema::compile(&schema).unwrap()
+    }
+
+    #[test]
+    fn every_variant_has_a_direction() {
+        let schema = json_schema();
+        for group in MESSAGE_GROUPS.iter() {
+            let mut variants = HashSet::new();
+            for variant_schema in schema["definitions"][group.definition]["oneOf"]
+                .as_array()
+                .unwrap()
+            {
+                match variant_schema["enum"].as_array() {
+                    Some(names) => variants.extend(names.iter().map(|n| n.as_str().unwrap())),
+                    None => variants.extend(
+                        variant_schema["properties"]
+                            .as_object()
+                            .unwrap()
+                            .keys()
+                            .map(String::as_str),
+                    ),
+                }
+            }
+            // Variants wrapping the messages of a nested group
+            let nested = MESSAGE_GROUPS
+                .iter()
+                .filter(|other| {
+                    other.path.len() == group.path.len() + 1 && other.path.starts_with(group.path)
+                })
+                .map(|other| other.path[group.path.len()]);
+            let described: HashSet<&str> = group
+                .variants
+                .iter()
+                .map(|(variant, _)| *variant)
+                .chain(nested)
+                .collect();
+            assert_eq!(variants, described, "{}", group.definition);
+        }
+    }
+
+    #[test]
+    fn messages_match_their_schemas() {
+        let schema = validator(json_schema());
+        let messages = messages();
+        let mut validated = HashSet::new();
+
+        for msg in all_messages() {
+            let json = msg.to_json().unwrap();
+            let value: Value = serde_json::from_str(&json).unwrap();
+            assert!(schema.is_valid(&value), "{json}");
+
+            let description = messages
+                .iter()
+                .find(|description| description.name == msg.label())
+                .unwrap_or_else(|| panic!("{} is not described", msg.label()));
+            assert!(
+                validator(description.payload.clone()).is_valid(&value),
+                "{json}"
+            );
+            for other in messages.iter().filter(|other| other.name != msg.label()) {
+                assert!(
+                    !validator(other.payload.clone()).is_valid(&value),
+                    "{json} matches {}",
+                    other.name
+                );
+            }
+
+            // Round trip
+            let decoded = RemoteAPIMsg::from_json(&json).unwrap();
+            assert_eq!(decoded.to_json().unwrap(), json);
+            validated.insert(description.name.clone());
+        }
+
+        assert_eq!(validated.len(), messages.len());
+        assert!(!schema.is_valid(&json!({ "Control": "Unknown" })));
+        assert!(!schema.is_valid(&json!({ "Data": { "MatchingStatus": { "id": "x" } } })));
+    }
+
+    #[test]
+    fn asyncapi_directions() {
+        let asyncapi = asyncapi();
+        let operation = |name: &str| -> Vec<String> {
+            asyncapi["channels"]["/"][name]["message"]["oneOf"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|reference| reference["$ref"].as_str().unwrap().to_string())
+                .collect()
+        };
+        let publish = operation("publish");
+        let subscribe = operation("subscribe");
+
+        assert!(publish.contains(&"#/components/messages/ControlMsg.OpenSession".to_string()));
+        assert!(publish.contains(&"#/components/messages/QueryableMsg.Reply".to_string()));
+        assert!(subscribe.contains(&"#/components/messages/DataMsg.Sample".to_string()));
+        assert!(subscribe.contains(&"#/components/messages/QueryableMsg.Query".to_string()));
+        assert_eq!(publish.len() + subscribe.len(), messages().len());
+
+        let text = serde_json::to_string(&asyncapi).unwrap();
+        assert!(!text.contains("#/definitions/"));
+        for reference in publish.iter().chain(subscribe.iter()) {
+            let key = reference.trim_start_matches("#/components/messages/");
+            assert!(asyncapi["components"]["messages"].get(key).is_some());
+        }
+    }
+
+    // The schemas committed in the `schema` directory of the crate, rewritten with `UPDATE_SCHEMAS=1`
+    #[test]
+    fn committed_schemas_are_up_to_date() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("schema");
+        let update = std::env::var_os("UPDATE_SCHEMAS").is_some();
+        for (file_name, schema) in [
+            ("remote_api.schema.json", json_schema()),
+            ("remote_api.asyncapi.json", asyncapi()),
+        ] {
+            let path = dir.join(file_name);
+            let generated = serde_json::to_string_pretty(&schema).unwrap() + "\n";
+            if update {
+                std::fs::create_dir_all(&dir).unwrap();
+                std::fs::write(&path, generated).unwrap();
+            } else {
+                let committed = std::fs::read_to_string(&path).unwrap_or_default();
+                assert!(
+                    committed == generated,
+                    "{} is stale, regenerate it with `UPDATE_SCHEMAS=1 cargo test -p zenoh-remote-api-protocol`",
+                    path.display()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,515 @@
+//
+// Copyright (c) 2024 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+use std::{str::FromStr, sync::Arc};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uhlc::{Timestamp, ID, NTP64};
+use uuid::Uuid;
+use zenoh_keyexpr::OwnedKeyExpr;
+use zenoh_protocol::core::{CongestionControl, Priority, Reliability};
+use zenoh_result::zerror;
+
+use crate::{
+    qos::{deserialize_qos, serialize_qos, CongestionControlWS, PriorityWS, ReliabilityWS},
+    B64String, EncodingWS,
+};
+
+// ██     ██ ██████   █████  ██████  ██████  ███████ ██████  ███████
+// ██     ██ ██   ██ ██   ██ ██   ██ ██   ██ ██      ██   ██ ██
+// ██  █  ██ ██████  ███████ ██████  ██████  █████   ██████  ███████
+// ██ ███ ██ ██   ██ ██   ██ ██      ██      ██      ██   ██      ██
+//  ███ ███  ██   ██ ██   ██ ██      ██      ███████ ██   ██ ███████
+
+// Wrapper to get OwnerKeyExpr to play with TS
+#[allow(dead_code)] // To allow OwnedKeyExpr to be converted to String
+#[derive(Debug, Deserialize, TS)]
+#[serde(from = "String")]
+pub struct OwnedKeyExprWrapper(Arc<str>);
+
+impl From<String> for OwnedKeyExprWrapper {
+    fn from(s: String) -> Self {
+        OwnedKeyExprWrapper(s.into())
+    }
+}
+
+#[derive(TS)]
+#[ts(export)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct QueryWS {
+    query_uuid: Uuid,
+    #[ts(as = "OwnedKeyExprWrapper")]
+    key_expr: OwnedKeyExpr,
+    parameters: String,
+    encoding: Option<EncodingWS>,
+    #[ts(type = "string | undefined")]
+    attachment: Option<B64String>,
+    #[ts(type = "string | undefined")]
+    payload: Option<B64String>,
+}
+
+impl QueryWS {
+    pub fn new(
+        query_uuid: Uuid,
+        key_expr: OwnedKeyExpr,
+        parameters: String,
+        encoding: Option<EncodingWS>,
+        attachment: Option<B64String>,
+        payload: Option<B64String>,
+    ) -> Self {
+        QueryWS {
+            query_uuid,
+            key_expr,
+            parameters,
+            encoding,
+            attachment,
+            payload,
+        }
+    }
+
+    pub fn query_uuid(&self) -> Uuid {
+        self.query_uuid
+    }
+
+    pub fn key_expr(&self) -> &OwnedKeyExpr {
+        &self.key_expr
+    }
+
+    pub fn parameters(&self) -> &str {
+        &self.parameters
+    }
+
+    pub fn encoding(&self) -> Option<&EncodingWS> {
+        self.encoding.as_ref()
+    }
+
+    pub fn attachment(&self) -> Option<&B64String> {
+        self.attachment.as_ref()
+    }
+
+    pub fn payload(&self) -> Option<&B64String> {
+        self.payload.as_ref()
+    }
+}
+
+#[derive(TS)]
+#[ts(export)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ReplyWS {
+    query_uuid: Uuid,
+    result: Result<SampleWS, ReplyErrorWS>,
+}
+
+impl ReplyWS {
+    pub fn new(query_uuid: Uuid, result: Result<SampleWS, ReplyErrorWS>) -> Self {
+        ReplyWS { query_uuid, result }
+    }
+
+    /// Id of the Get this reply answers
+    pub fn query_uuid(&self) -> Uuid {
+        self.query_uuid
+    }
+
+    pub fn result(&self) -> Result<&SampleWS, &ReplyErrorWS> {
+        self.result.as_ref()
+    }
+
+    pub fn into_result(self) -> Result<SampleWS, ReplyErrorWS> {
+        self.result
+    }
+}
+
+#[derive(TS)]
+#[ts(export)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct QueryReplyWS {
+    query_uuid: Uuid,
+    result: QueryReplyVariant,
+}
+
+impl QueryReplyWS {
+    pub fn new(query_uuid: Uuid, result: QueryReplyVariant) -> Self {
+        QueryReplyWS { query_uuid, result }
+    }
+
+    /// Id of the Query this reply answers
+    pub fn query_uuid(&self) -> Uuid {
+        self.query_uuid
+    }
+
+    pub fn result(&self) -> &QueryReplyVariant {
+        &self.result
+    }
+
+    pub fn into_result(self) -> QueryReplyVariant {
+        self.result
+    }
+}
+
+#[derive(TS)]
+#[ts(export)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub enum QueryReplyVariant {
+    Reply {
+        #[ts(as = "OwnedKeyExprWrapper")]
+        key_expr: OwnedKeyExpr,
+        payload: B64String,
+    },
+    ReplyErr {
+        payload: B64String,
+    },
+    ReplyDelete {
+        #[ts(as = "OwnedKeyExprWrapper")]
+        key_expr: OwnedKeyExpr,
+    },
+}
+
+#[derive(TS)]
+#[ts(export)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ReplyErrorWS {
+    payload: B64String,
+    encoding: EncodingWS,
+}
+
+impl ReplyErrorWS {
+    pub fn new(payload: B64String, encoding: EncodingWS) -> Self {
+        ReplyErrorWS { payload, encoding }
+    }
+
+    pub fn payload(&self) -> &B64String {
+        &self.payload
+    }
+
+    pub fn encoding(&self) -> &EncodingWS {
+        &self.encoding
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub struct SampleWS {
+    #[ts(as = "OwnedKeyExprWrapper")]
+    pub(crate) key_expr: OwnedKeyExpr,
+    pub(crate) value: B64String,
+    pub(crate) kind: SampleKindWS,
+    pub(crate) encoding: EncodingWS,
+    // Compression of the value, the encoding being the one of the uncompressed value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub(crate) compression: Option<CompressionWS>,
+    pub(crate) timestamp: Option<TimestampWS>,
+    #[schemars(with = "CongestionControlWS")]
+    #[serde(deserialize_with = "deserialize_qos", serialize_with = "serialize_qos")]
+    #[ts(as = "CongestionControlWS")]
+    pub(crate) congestion_control: CongestionControl,
+    #[schemars(with = "PriorityWS")]
+    #[serde(deserialize_with = "deserialize_qos", serialize_with = "serialize_qos")]
+    #[ts(as = "PriorityWS")]
+    pub(crate) priority: Priority,
+    #[schemars(with = "ReliabilityWS")]
+    #[serde(deserialize_with = "deserialize_qos", serialize_with = "serialize_qos")]
+    #[ts(as = "ReliabilityWS")]
+    pub(crate) reliability: Reliability,
+    pub(crate) express: bool,
+    pub(crate) attachement: Option<B64String>,
+    pub(crate) source_info: Option<SourceInfoWS>,
+}
+
+impl SampleWS {
+    /// A sample with the default QoS of zenoh (`Drop`, `Data`, `Reliable`, not express),
+    /// without timestamp, attachment nor source info
+    pub fn new(
+        key_expr: OwnedKeyExpr,
+        value: B64String,
+        kind: SampleKindWS,
+        encoding: EncodingWS,
+    ) -> Self {
+        SampleWS {
+            key_expr,
+            value,
+            kind,
+            encoding,
+            compression: None,
+            timestamp: None,
+            congestion_control: CongestionControl::Drop,
+            priority: Priority::Data,
+            reliability: Reliability::Reliable,
+            express: false,
+            attachement: None,
+            source_info: None,
+        }
+    }
+
+    pub fn key_expr(&self) -> &OwnedKeyExpr {
+        &self.key_expr
+    }
+
+    pub fn value(&self) -> &B64String {
+        &self.value
+    }
+
+    pub fn kind(&self) -> SampleKindWS {
+        self.kind
+    }
+
+    pub fn encoding(&self) -> &EncodingWS {
+        &self.encoding
+    }
+
+    pub fn compression(&self) -> Option<CompressionWS> {
+        self.compression
+    }
+
+    /// Marks the value as compressed with `compression`
+    pub fn with_compression(mut self, compression: CompressionWS) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    pub fn timestamp(&self) -> Option<&TimestampWS> {
+        self.timestamp.as_ref()
+    }
+
+    pub fn congestion_control(&self) -> CongestionControl {
+        self.congestion_control
+    }
+
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+
+    pub fn reliability(&self) -> Reliability {
+        self.reliability
+    }
+
+    pub fn express(&self) -> bool {
+        self.express
+    }
+
+    pub fn attachment(&self) -> Option<&B64String> {
+        self.attachement.as_ref()
+    }
+
+    pub fn source_info(&self) -> Option<&SourceInfoWS> {
+        self.source_info.as_ref()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub struct EntityGlobalIdWS {
+    zid: String,
+    eid: u32,
+}
+
+impl EntityGlobalIdWS {
+    pub fn new(zid: String, eid: u32) -> Self {
+        EntityGlobalIdWS { zid, eid }
+    }
+
+    // Hexadecimal representation of the Zenoh ID
+    pub fn zid(&self) -> &str {
+        &self.zid
+    }
+
+    pub fn eid(&self) -> u32 {
+        self.eid
+    }
+}
+
+// NTP64 time split in two 32 bits halves so that it can be represented losslessly as
+// Javascript numbers, along with the ID of the HLC that generated the Timestamp
+#[derive(Debug, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub struct TimestampWS {
+    // Seconds since the UNIX Epoch
+    seconds: u32,
+    // Fraction of second in units of 1 / 2^32 seconds
+    fraction: u32,
+    // Hexadecimal representation of the HLC ID
+    id: String,
+}
+
+impl TimestampWS {
+    pub fn new(seconds: u32, fraction: u32, id: String) -> Self {
+        TimestampWS {
+            seconds,
+            fraction,
+            id,
+        }
+    }
+
+    pub fn seconds(&self) -> u32 {
+        self.seconds
+    }
+
+    pub fn fraction(&self) -> u32 {
+        self.fraction
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl From<&Timestamp> for TimestampWS {
+    fn from(timestamp: &Timestamp) -> Self {
+        let time = timestamp.get_time().as_u64();
+        TimestampWS {
+            seconds: (time >> 32) as u32,
+            fraction: time as u32,
+            id: timestamp.get_id().to_string(),
+        }
+    }
+}
+
+impl TryFrom<TimestampWS> for Timestamp {
+    type Error = zenoh_result::Error;
+
+    fn try_from(timestamp: TimestampWS) -> Result<Self, Self::Error> {
+        let id = ID::from_str(&timestamp.id)
+            .map_err(|err| zerror!("Invalid Timestamp ID {} : {}", timestamp.id, err.cause))?;
+        let time = NTP64(((timestamp.seconds as u64) << 32) | timestamp.fraction as u64);
+        Ok(Timestamp::new(time, id))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub struct SourceInfoWS {
+    id: EntityGlobalIdWS,
+    sn: u32,
+}
+
+impl SourceInfoWS {
+    pub fn new(id: EntityGlobalIdWS, sn: u32) -> Self {
+        SourceInfoWS { id, sn }
+    }
+
+    pub fn id(&self) -> &EntityGlobalIdWS {
+        &self.id
+    }
+
+    pub fn sn(&self) -> u32 {
+        self.sn
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub enum SampleKindWS {
+    Put = 0,
+    Delete = 1,
+}
+
+// zstd frames, or lz4 blocks prefixed by their uncompressed size as a little endian u32
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[ts(export)]
+pub enum CompressionWS {
+    Zstd,
+    Lz4,
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::str::FromStr;
+
+    use zenoh_keyexpr::keyexpr;
+
+    use super::*;
+    use crate::DataMsg;
+
+    #[test]
+    fn serialize_messages() {
+        let uuid = Uuid::from_str("a2663bb1-128c-4dd3-a42b-d1d3337e2e51").unwrap();
+        let key_expr: OwnedKeyExpr = keyexpr::new("demo/test").unwrap().to_owned();
+
+        let _sample_ws = SampleWS {
+            key_expr: key_expr.clone(),
+            value: B64String::encode(vec![1, 2, 3]),
+            kind: SampleKindWS::Put,
+            encoding: EncodingWS::default(),
+            compression: None,
+            timestamp: None,
+            priority: Priority::RealTime,
+            congestion_control: CongestionControl::Block,
+            reliability: Reliability::Reliable,
+            express: false,
+            attachement: None,
+            source_info: None,
+        };
+
+        let sample_ws = SampleWS {
+            key_expr,
+            value: B64String::encode(vec![1, 2, 3]),
+            kind: SampleKindWS::Put,
+            encoding: EncodingWS::default(),
+            compression: None,
+            timestamp: None,
+            priority: Priority::RealTime,
+            congestion_control: CongestionControl::Block,
+            reliability: Reliability::Reliable,
+            express: false,
+            attachement: None,
+            source_info: None,
+        };
+        let _json: String = serde_json::to_string(&DataMsg::Sample(sample_ws, uuid)).unwrap();
+    }
+
+    #[test]
+    fn sample_default_qos() {
+        let key_expr: OwnedKeyExpr = keyexpr::new("demo/test").unwrap().to_owned();
+        let sample_ws = SampleWS::new(
+            key_expr,
+            B64String::encode("value"),
+            SampleKindWS::Put,
+            EncodingWS::default(),
+        );
+
+        let json = serde_json::to_string(&sample_ws).unwrap();
+        assert_eq!(
+            json,
+            r#"{"key_expr":"demo/test","value":"dmFsdWU=","kind":"Put","encoding":{"id":0},"timestamp":null,"congestion_control":"Drop","priority":"Data","reliability":"Reliable","express":false,"attachement":null,"source_info":null}"#
+        );
+        let sample_ws: SampleWS = serde_json::from_str(&json).unwrap();
+        assert_eq!(sample_ws.key_expr().as_str(), "demo/test");
+        assert_eq!(sample_ws.value().to_bytes().unwrap(), b"value");
+        assert_eq!(sample_ws.kind(), SampleKindWS::Put);
+
+        // QoS numbers are still accepted
+        let json = json.replace(
+            r#""congestion_control":"Drop","priority":"Data","reliability":"Reliable""#,
+            r#""congestion_control":1,"priority":2,"reliability":1"#,
+        );
+        let sample_ws: SampleWS = serde_json::from_str(&json).unwrap();
+        assert_eq!(sample_ws.congestion_control(), CongestionControl::Block);
+        assert_eq!(sample_ws.priority(), Priority::InteractiveHigh);
+        assert_eq!(sample_ws.reliability(), Reliability::BestEffort);
+    }
+
+    #[test]
+    fn timestamp_round_trip() {
+        let timestamp = Timestamp::new(NTP64(7386690599959157260), ID::try_from([0x33]).unwrap());
+        let timestamp_ws = TimestampWS::from(&timestamp);
+        assert_eq!(timestamp_ws.id, "33");
+
+        let json = serde_json::to_string(&timestamp_ws).unwrap();
+        let timestamp_ws: TimestampWS = serde_json::from_str(&json).unwrap();
+        assert_eq!(Timestamp::try_from(timestamp_ws).unwrap(), timestamp);
+    }
+}
@@ -12,6 +12,8 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 
+import { EncodingWS } from "./remote_api/interface/EncodingWS.js";
+
 enum encoding {
   ZENOH_BYTES = "zenoh/bytes",
   ZENOH_STRING = "zenoh/string",
@@ -68,6 +70,65 @@ enum encoding {
   VIDEO_VP9 = "video/vp9",
 }
 
+// Encodings known by zenoh, indexed by their id
+const ID_TO_ENCODING: string[] = [
+  encoding.ZENOH_BYTES,
+  encoding.ZENOH_STRING,
+  encoding.ZENOH_SERIALIZED,
+  encoding.APPLICATION_OCTET_STREAM,
+  encoding.TEXT_PLAIN,
+  encoding.APPLICATION_JSON,
+  encoding.TEXT_JSON,
+  encoding.APPLICATION_CDR,
+  encoding.APPLICATION_CBOR,
+  encoding.APPLICATION_YAML,
+  encoding.TEXT_YAML,
+  encoding.TEXT_JSON5,
+  encoding.APPLICATION_PYTHON_SERIALIZED_OBJECT,
+  encoding.APPLICATION_PROTOBUF,
+  encoding.APPLICATION_JAVA_SERIALIZED_OBJECT,
+  encoding.APPLICATION_OPENMETRICS_TEXT,
+  encoding.IMAGE_PNG,
+  encoding.IMAGE_JPEG,
+  encoding.IMAGE_GIF,
+  encoding.IMAGE_BMP,
+  encoding.IMAGE_WEBP,
+  encoding.APPLICATION_XML,
+  encoding.APPLICATION_X_WWW_FORM_URLENCODED,
+  encoding.TEXT_HTML,
+  encoding.TEXT_XML,
+  encoding.TEXT_CSS,
+  encoding.TEXT_JAVASCRIPT,
+  encoding.TEXT_MARKDOWN,
+  encoding.TEXT_CSV,
+  encoding.APPLICATION_SQL,
+  encoding.APPLICATION_COAP_PAYLOAD,
+  encoding.APPLICATION_JSON_PATCH_JSON,
+  encoding.APPLICATION_JSON_SEQ,
+  encoding.APPLICATION_JSONPATH,
+  encoding.APPLICATION_JWT,
+  encoding.APPLICATION_MP4,
+  encoding.APPLICATION_SOAP_XML,
+  encoding.APPLICATION_YANG,
+  encoding.AUDIO_AAC,
+  encoding.AUDIO_FLAC,
+  encoding.AUDIO_MP4,
+  encoding.AUDIO_OGG,
+  encoding.AUDIO_VORBIS,
+  encoding.VIDEO_H261,
+  encoding.VIDEO_H263,
+  encoding.VIDEO_H264,
+  encoding.VIDEO_H265,
+  encoding.VIDEO_H266,
+  encoding.VIDEO_MP4,
+  encoding.VIDEO_OGG,
+  encoding.VIDEO_RAW,
+  encoding.VIDEO_VP8,
+  encoding.VIDEO_VP9,
+];
+
+const SCHEMA_SEP = ";";
+
 export type IntoEncoding = Encoding | String | string;
 
 /**
@@ -99,6 +160,46 @@ export class Encoding {
     return new Encoding(input);
   }
 
+  /**
+   * Encoding from its id and schema, as sent by the remote API
+   */
+  static from_ws(encoding_ws: EncodingWS): Encoding {
+    let name = ID_TO_ENCODING[encoding_ws.id];
+    let schema = encoding_ws.schema;
+    if (name == undefined) {
+      name = "unknown(" + encoding_ws.id + ")";
+    }
+    if (schema == undefined) {
+      return new Encoding(name);
+    }
+    return new Encoding(name + SCHEMA_SEP + schema);
+  }
+
+  /**
+   * Id and schema of the encoding, as sent to the remote API
+   */
+  to_ws(): EncodingWS {
+    if (this._schema == "") {
+      return { id: 0 };
+    }
+    let sep = this._schema.indexOf(SCHEMA_SEP);
+    let name = sep == -1 ? this._schema : this._schema.substring(0, sep);
+    let schema = sep == -1 ? "" : this._schema.substring(sep + 1);
+    let id = ID_TO_ENCODING.indexOf(name);
+    if (id == -1) {
+      let unknown = name.match(/^unknown\((\d+)\)$/);
+      if (unknown == null) {
+        // Same as zenoh: a name it does not know is the schema of zenoh/bytes
+        return { id: 0, schema: this._schema };
+      }
+      id = Number(unknown[1]);
+    }
+    if (schema == "") {
+      return { id: id };
+    }
+    return { id: id, schema: schema };
+  }
+
   // Enum Variants
   /**
    * Constant alias for string "zenoh/bytes"
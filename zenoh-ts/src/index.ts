@@ -15,10 +15,10 @@
 // API Layer Files
 import { KeyExpr, IntoKeyExpr } from "./key_expr.js";
 import { ZBytes, IntoZBytes, deserialize_bool, deserialize_uint, deserialize_int, deserialize_float, deserialize_string } from "./z_bytes.js";
-import { CongestionControl, ConsolidationMode, Priority, Reliability, Sample, SampleKind } from "./sample.js";
-import { Publisher, Subscriber, FifoChannel, RingChannel } from "./pubsub.js";
+import { Compression, CongestionControl, ConsolidationMode, Priority, Reliability, Sample, SampleKind } from "./sample.js";
+import { Publisher, Subscriber, FifoChannel, RingChannel, MatchingListener, MatchingStatus } from "./pubsub.js";
 import { IntoSelector, Parameters, IntoParameters, Query, Queryable, Reply, ReplyError, Selector } from "./query.js";
-import { Session, RecvErr, Receiver, DeleteOptions, PutOptions, GetOptions, QueryableOptions, PublisherOptions } from "./session.js";
+import { Session, RecvErr, Receiver, DeleteOptions, PutOptions, GetOptions, QueryableOptions, PublisherOptions, SubscriberOptions, BatchOptions, ThrottleMode, ThrottleOptions, JsonComparison, SampleFilter } from "./session.js";
 import { Config } from "./config.js";
 import { Encoding, IntoEncoding } from "./encoding.js";
 import { Liveliness, LivelinessToken } from "./liveliness.js";
@@ -28,10 +28,10 @@ import { TimeDuration as Duration } from 'typed-duration'
 // Exports
 export { KeyExpr, IntoKeyExpr };
 export { ZBytes, IntoZBytes, deserialize_bool, deserialize_uint, deserialize_int, deserialize_float, deserialize_string };
-export { CongestionControl, ConsolidationMode, Priority, Reliability, Sample, SampleKind };
-export { Publisher, Subscriber, FifoChannel, RingChannel };
+export { Compression, CongestionControl, ConsolidationMode, Priority, Reliability, Sample, SampleKind };
+export { Publisher, Subscriber, FifoChannel, RingChannel, MatchingListener, MatchingStatus };
 export { IntoSelector, Parameters, IntoParameters, Query, Queryable, Reply, ReplyError, Selector };
-export { Session, RecvErr, Receiver, DeleteOptions as DeleteOpts, PutOptions, GetOptions, QueryableOptions, PublisherOptions };
+export { Session, RecvErr, Receiver, DeleteOptions as DeleteOpts, PutOptions, GetOptions, QueryableOptions, PublisherOptions, SubscriberOptions, BatchOptions, ThrottleMode, ThrottleOptions, JsonComparison, SampleFilter };
 export { Config };
 export { Encoding, IntoEncoding };
 export { Liveliness, LivelinessToken };
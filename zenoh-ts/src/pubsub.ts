@@ -262,10 +262,27 @@ export class Publisher {
     return this._remote_publisher.put(
       Array.from(zbytes.buffer()),
       _attachment,
-      _encoding.toString(),
+      _encoding.to_ws(),
     );
   }
 
+  /**
+   * Deletes the key expression associated with this publisher
+   *
+   * @param {IntoZBytes=} attachment - optional extra data to send with the delete
+   *
+   * @returns void
+   */
+  delete(attachment?: IntoZBytes): void {
+    let _attachment = null;
+    if (attachment != null) {
+      let att_bytes = new ZBytes(attachment);
+      _attachment = Array.from(att_bytes.buffer());
+    }
+
+    return this._remote_publisher.delete(_attachment);
+  }
+
   /**
   * get Encoding declared for Publisher
   *   
@@ -302,6 +319,21 @@ export class Publisher {
     return this._congestion_control;
   }
 
+  /**
+   * Declares a listener of the matching status of the publisher,
+   * called whenever subscribers matching its key expression appear or disappear
+   *
+   * @param {(status: MatchingStatus) => void} callback - called with each new matching status
+   *
+   * @returns {MatchingListener}
+   */
+  matching_listener(callback: (status: MatchingStatus) => void): MatchingListener {
+    let id = this._remote_publisher.declare_matching_listener(
+      (matching: boolean) => callback(new MatchingStatus(matching))
+    );
+    return new MatchingListener(this._remote_publisher, id);
+  }
+
   /**
    * undeclares publisher
    *   
@@ -313,3 +345,52 @@ export class Publisher {
   }
 
 }
+
+/**
+ * Matching status of a publisher, passed to the callback of its `MatchingListener`
+ */
+export class MatchingStatus {
+  private _matching: boolean;
+
+  /**
+   * @ignore
+   */
+  constructor(matching: boolean) {
+    this._matching = matching;
+  }
+
+  /**
+   * whether some subscribers match the key expression of the publisher
+   *
+   * @returns {boolean}
+   */
+  matching(): boolean {
+    return this._matching;
+  }
+}
+
+/**
+ * Listener of the matching status of a publisher,
+ * created by calling `matching_listener()` on a `Publisher`
+ */
+export class MatchingListener {
+  private _remote_publisher: RemotePublisher;
+  private _id: string;
+
+  /**
+   * @ignore
+   */
+  constructor(remote_publisher: RemotePublisher, id: string) {
+    this._remote_publisher = remote_publisher;
+    this._id = id;
+  }
+
+  /**
+   * undeclares the matching listener
+   *
+   * @returns void
+   */
+  undeclare() {
+    this._remote_publisher.undeclare_matching_listener(this._id);
+  }
+}
@@ -121,7 +121,7 @@ export function QueryWS_to_Query(
     attachment = new ZBytes(query_ws.attachment);
   }
   if (query_ws.encoding != null) {
-    encoding = Encoding.from_str(query_ws.encoding);
+    encoding = Encoding.from_ws(query_ws.encoding);
   }
 
   return new Query(
@@ -428,7 +428,7 @@ export class ReplyError {
     */
   constructor(reply_err_ws: ReplyErrorWS) {
     let payload = new ZBytes(reply_err_ws.payload);
-    let encoding = Encoding.from_str(reply_err_ws.encoding);
+    let encoding = Encoding.from_ws(reply_err_ws.encoding);
     this._encoding = encoding;
     this._payload = payload;
   }
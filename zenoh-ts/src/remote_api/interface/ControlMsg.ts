@@ -1,7 +1,22 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { B64String } from "./B64String";
+import type { BatchConfigWS } from "./BatchConfigWS";
+import type { CacheConfigWS } from "./CacheConfigWS";
+import type { CompressionWS } from "./CompressionWS";
+import type { CongestionControlWS } from "./CongestionControlWS";
+import type { ConsolidationModeWS } from "./ConsolidationModeWS";
+import type { EncodingWS } from "./EncodingWS";
 import type { HandlerChannel } from "./HandlerChannel";
+import type { HistoryConfigWS } from "./HistoryConfigWS";
 import type { LivelinessMsg } from "./LivelinessMsg";
+import type { MissDetectionConfigWS } from "./MissDetectionConfigWS";
 import type { OwnedKeyExprWrapper } from "./OwnedKeyExprWrapper";
+import type { PriorityWS } from "./PriorityWS";
+import type { RecoveryConfigWS } from "./RecoveryConfigWS";
+import type { ReliabilityWS } from "./ReliabilityWS";
+import type { SampleFilterWS } from "./SampleFilterWS";
+import type { SourceInfoWS } from "./SourceInfoWS";
+import type { ThrottleWS } from "./ThrottleWS";
+import type { TimestampWS } from "./TimestampWS";
 
-export type ControlMsg = "OpenSession" | "CloseSession" | { "Session": string } | { "Get": { key_expr: OwnedKeyExprWrapper, parameters: string | null, handler: HandlerChannel, id: string, consolidation: number | undefined, congestion_control: number | undefined, priority: number | undefined, express: boolean | undefined, encoding: string | undefined, payload: string | undefined, attachment: string | undefined, } } | { "GetFinished": { id: string, } } | { "Put": { key_expr: OwnedKeyExprWrapper, payload: B64String, encoding: string | undefined, congestion_control: number | undefined, priority: number | undefined, express: boolean | undefined, attachment: string | undefined, } } | { "Delete": { key_expr: OwnedKeyExprWrapper, congestion_control: number | undefined, priority: number | undefined, express: boolean | undefined, attachment: string | undefined, } } | { "DeclareSubscriber": { key_expr: OwnedKeyExprWrapper, handler: HandlerChannel, id: string, } } | { "Subscriber": string } | { "UndeclareSubscriber": string } | { "DeclarePublisher": { key_expr: OwnedKeyExprWrapper, encoding: string | undefined, congestion_control: number | undefined, priority: number | undefined, reliability: number | undefined, express: boolean | undefined, id: string, } } | { "UndeclarePublisher": string } | { "DeclareQueryable": { key_expr: OwnedKeyExprWrapper, id: string, complete: boolean, } } | { "UndeclareQueryable": string } | { "Liveliness": LivelinessMsg };
+export type ControlMsg = "OpenSession" | "CloseSession" | { "Session": string } | { "Error": string } | { "Get": { key_expr: OwnedKeyExprWrapper, parameters: string | null, handler: HandlerChannel, id: string, consolidation?: ConsolidationModeWS, congestion_control?: CongestionControlWS, priority?: PriorityWS, express: boolean | undefined, encoding?: EncodingWS, payload: string | undefined, attachment: string | undefined, } } | { "GetFinished": { id: string, } } | { "Put": { key_expr: OwnedKeyExprWrapper, payload: B64String, encoding?: EncodingWS, congestion_control?: CongestionControlWS, priority?: PriorityWS, express: boolean | undefined, attachment: string | undefined, timestamp?: TimestampWS, source_info?: SourceInfoWS, } } | { "Delete": { key_expr: OwnedKeyExprWrapper, congestion_control?: CongestionControlWS, priority?: PriorityWS, express: boolean | undefined, attachment: string | undefined, timestamp?: TimestampWS, source_info?: SourceInfoWS, } } | { "DeclareSubscriber": { key_expr: OwnedKeyExprWrapper, handler: HandlerChannel, id: string, transcode_to?: EncodingWS, compression?: CompressionWS, batch?: BatchConfigWS, throttle?: ThrottleWS, filters?: Array<SampleFilterWS>, } } | { "Subscriber": string } | { "UndeclareSubscriber": string } | { "DeclarePublisher": { key_expr: OwnedKeyExprWrapper, encoding?: EncodingWS, congestion_control?: CongestionControlWS, priority?: PriorityWS, reliability?: ReliabilityWS, express: boolean | undefined, id: string, } } | { "UndeclarePublisher": string } | { "DeclareMatchingListener": { id: string, publisher_id: string, } } | { "UndeclareMatchingListener": string } | { "DeclareQueryable": { key_expr: OwnedKeyExprWrapper, id: string, complete: boolean, } } | { "UndeclareQueryable": string } | { "DeclareQuerier": { key_expr: OwnedKeyExprWrapper, id: string, consolidation?: ConsolidationModeWS, congestion_control?: CongestionControlWS, priority?: PriorityWS, express: boolean | undefined, timeout: number | undefined, } } | { "UndeclareQuerier": string } | { "QuerierGet": { querier_id: string, id: string, parameters: string | null, handler: HandlerChannel, encoding?: EncodingWS, payload: string | undefined, attachment: string | undefined, } } | { "DeclareAdvancedPublisher": { key_expr: OwnedKeyExprWrapper, encoding?: EncodingWS, congestion_control?: CongestionControlWS, priority?: PriorityWS, reliability?: ReliabilityWS, express: boolean | undefined, cache?: CacheConfigWS, sample_miss_detection?: MissDetectionConfigWS, publisher_detection: boolean | undefined, id: string, } } | { "UndeclareAdvancedPublisher": string } | { "DeclareAdvancedSubscriber": { key_expr: OwnedKeyExprWrapper, handler: HandlerChannel, id: string, history?: HistoryConfigWS, recovery?: RecoveryConfigWS, subscriber_detection: boolean | undefined, } } | { "UndeclareAdvancedSubscriber": string } | { "Liveliness": LivelinessMsg };
@@ -1,7 +1,11 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { B64String } from "./B64String";
+import type { EncodingWS } from "./EncodingWS";
+import type { EntityGlobalIdWS } from "./EntityGlobalIdWS";
 import type { QueryableMsg } from "./QueryableMsg";
 import type { ReplyWS } from "./ReplyWS";
 import type { SampleWS } from "./SampleWS";
+import type { SourceInfoWS } from "./SourceInfoWS";
+import type { TimestampWS } from "./TimestampWS";
 
-export type DataMsg = { "PublisherPut": { id: string, payload: B64String, attachment: B64String | null, encoding: string | null, } } | { "Sample": [SampleWS, string] } | { "GetReply": ReplyWS } | { "Queryable": QueryableMsg };
+export type DataMsg = { "PublisherPut": { id: string, payload: B64String, attachment: B64String | null, encoding: EncodingWS | null, timestamp?: TimestampWS, source_info?: SourceInfoWS, } } | { "PublisherDelete": { id: string, attachment?: B64String, timestamp?: TimestampWS, source_info?: SourceInfoWS, } } | { "Sample": [SampleWS, string] } | { "Samples": [Array<SampleWS>, string] } | { "GetReply": ReplyWS } | { "MatchingStatus": { id: string, matching: boolean, } } | { "SampleMiss": { id: string, source: EntityGlobalIdWS, nb: number, } } | { "Queryable": QueryableMsg };
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonPredicateWS } from "./JsonPredicateWS";
+import type { SampleKindWS } from "./SampleKindWS";
+
+export type SampleFilterWS = { "Kind": SampleKindWS } | { "Attachment": { key: string, value?: string, } } | { "Json": { path: string, predicate: JsonPredicateWS, } };
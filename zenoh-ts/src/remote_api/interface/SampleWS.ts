@@ -1,6 +1,13 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { B64String } from "./B64String";
+import type { CompressionWS } from "./CompressionWS";
+import type { CongestionControlWS } from "./CongestionControlWS";
+import type { EncodingWS } from "./EncodingWS";
 import type { OwnedKeyExprWrapper } from "./OwnedKeyExprWrapper";
+import type { PriorityWS } from "./PriorityWS";
+import type { ReliabilityWS } from "./ReliabilityWS";
 import type { SampleKindWS } from "./SampleKindWS";
+import type { SourceInfoWS } from "./SourceInfoWS";
+import type { TimestampWS } from "./TimestampWS";
 
-export type SampleWS = { key_expr: OwnedKeyExprWrapper, value: B64String, kind: SampleKindWS, encoding: string, timestamp: string | null, congestion_control: number, priority: number, express: boolean, attachement: B64String | null, };
+export type SampleWS = { key_expr: OwnedKeyExprWrapper, value: B64String, kind: SampleKindWS, encoding: EncodingWS, compression?: CompressionWS, timestamp: TimestampWS | null, congestion_control: CongestionControlWS, priority: PriorityWS, reliability: ReliabilityWS, express: boolean, attachement: B64String | null, source_info: SourceInfoWS | null, };
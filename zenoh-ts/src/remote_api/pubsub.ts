@@ -20,6 +20,7 @@ import { encode as b64_str_from_bytes } from "base64-arraybuffer";
 import { SampleWS } from "./interface/SampleWS.js";
 import { DataMsg } from "./interface/DataMsg.js";
 import { ControlMsg } from "./interface/ControlMsg.js";
+import { EncodingWS } from "./interface/EncodingWS.js";
 
 // Remote Api
 import { RemoteSession } from "./session.js";
@@ -56,7 +57,7 @@ export class RemotePublisher {
   put(
     payload: Array<number>,
     attachment: Array<number> | null,
-    encoding: string | null,
+    encoding: EncodingWS | null,
   ) {
     if (this.undeclared == true) {
       let message =
@@ -85,6 +86,40 @@ export class RemotePublisher {
     this.session_ref.send_data_message(data_msg);
   }
 
+  delete(attachment: Array<number> | null) {
+    if (this.undeclared == true) {
+      let message =
+        "Publisher keyexpr:`" +
+        this.key_expr +
+        "` id:`" +
+        this.publisher_id +
+        "` already undeclared";
+      console.warn(message);
+      return;
+    }
+
+    let optional_attachment = undefined;
+    if (attachment != null) {
+      optional_attachment = b64_str_from_bytes(new Uint8Array(attachment));
+    }
+
+    let data_msg: DataMsg = {
+      PublisherDelete: {
+        id: this.publisher_id.toString(),
+        attachment: optional_attachment,
+      },
+    };
+    this.session_ref.send_data_message(data_msg);
+  }
+
+  declare_matching_listener(callback: (matching: boolean) => void): string {
+    return this.session_ref.declare_matching_listener(this.publisher_id.toString(), callback);
+  }
+
+  undeclare_matching_listener(id: string) {
+    this.session_ref.undeclare_matching_listener(id);
+  }
+
   undeclare() {
     if (this.undeclared == true) {
       let message =
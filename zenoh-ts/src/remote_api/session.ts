@@ -32,6 +32,15 @@ import { ReplyWS } from "./interface/ReplyWS.js";
 import { QueryableMsg } from "./interface/QueryableMsg.js";
 import { QueryReplyWS } from "./interface/QueryReplyWS.js";
 import { HandlerChannel } from "./interface/HandlerChannel.js";
+import { CongestionControlWS } from "./interface/CongestionControlWS.js";
+import { ConsolidationModeWS } from "./interface/ConsolidationModeWS.js";
+import { EncodingWS } from "./interface/EncodingWS.js";
+import { CompressionWS } from "./interface/CompressionWS.js";
+import { BatchConfigWS } from "./interface/BatchConfigWS.js";
+import { ThrottleWS } from "./interface/ThrottleWS.js";
+import { SampleFilterWS } from "./interface/SampleFilterWS.js";
+import { PriorityWS } from "./interface/PriorityWS.js";
+import { ReliabilityWS } from "./interface/ReliabilityWS.js";
 
 // ██████  ███████ ███    ███  ██████  ████████ ███████     ███████ ███████ ███████ ███████ ██  ██████  ███    ██
 // ██   ██ ██      ████  ████ ██    ██    ██    ██          ██      ██      ██      ██      ██ ██    ██ ████   ██
@@ -59,6 +68,7 @@ export class RemoteSession {
   get_receiver: Map<UUIDv4, SimpleChannel<ReplyWS | RemoteRecvErr>>;
   liveliness_subscribers: Map<UUIDv4, SimpleChannel<SampleWS>>;
   liveliness_get_receiver: Map<UUIDv4, SimpleChannel<ReplyWS>>;
+  matching_listeners: Map<UUIDv4, (matching: boolean) => void>;
 
   private constructor(ws: WebSocket, ws_channel: SimpleChannel<JSONMessage>) {
     this.ws = ws;
@@ -69,6 +79,7 @@ export class RemoteSession {
     this.get_receiver = new Map<UUIDv4, SimpleChannel<ReplyWS>>();
     this.liveliness_subscribers = new Map<UUIDv4, SimpleChannel<SampleWS>>();
     this.liveliness_get_receiver = new Map<UUIDv4, SimpleChannel<ReplyWS>>();
+    this.matching_listeners = new Map<UUIDv4, (matching: boolean) => void>();
   }
 
   //
@@ -138,9 +149,9 @@ export class RemoteSession {
   // Put
   put(key_expr: string,
     payload: Array<number>,
-    encoding?: string,
-    congestion_control?: number,
-    priority?: number,
+    encoding?: EncodingWS,
+    congestion_control?: CongestionControlWS,
+    priority?: PriorityWS,
     express?: boolean,
     attachment?: Array<number>
   ): void {
@@ -170,11 +181,11 @@ export class RemoteSession {
     key_expr: string,
     parameters: string | null,
     handler: HandlerChannel,
-    consolidation?: number,
-    congestion_control?: number,
-    priority?: number,
+    consolidation?: ConsolidationModeWS,
+    congestion_control?: CongestionControlWS,
+    priority?: PriorityWS,
     express?: boolean,
-    encoding?: string,
+    encoding?: EncodingWS,
     payload?: Array<number>,
     attachment?: Array<number>
   ): Promise<SimpleChannel<ReplyWS>> {
@@ -213,8 +224,8 @@ export class RemoteSession {
   // delete
   async delete(
     key_expr: string,
-    congestion_control?: number,
-    priority?: number,
+    congestion_control?: CongestionControlWS,
+    priority?: PriorityWS,
     express?: boolean,
     attachment?: Array<number>
   ): Promise<void> {
@@ -245,11 +256,16 @@ export class RemoteSession {
     key_expr: string,
     handler: HandlerChannel,
     callback?: (sample: SampleWS) => Promise<void>,
+    transcode_to?: EncodingWS,
+    compression?: CompressionWS,
+    batch?: BatchConfigWS,
+    throttle?: ThrottleWS,
+    filters?: SampleFilterWS[],
   ): Promise<RemoteSubscriber> {
     let uuid = uuidv4();
 
     let control_message: ControlMsg = {
-      DeclareSubscriber: { key_expr: key_expr, id: uuid, handler: handler },
+      DeclareSubscriber: { key_expr: key_expr, id: uuid, handler: handler, transcode_to: transcode_to, compression: compression, batch: batch, throttle: throttle, filters: filters },
     };
 
     let channel: SimpleChannel<SampleWS> = new SimpleChannel<SampleWS>();
@@ -301,11 +317,11 @@ export class RemoteSession {
 
   declare_remote_publisher(
     key_expr: string,
-    encoding?: string,
-    congestion_control?: number,
-    priority?: number,
+    encoding?: EncodingWS,
+    congestion_control?: CongestionControlWS,
+    priority?: PriorityWS,
     express?: boolean,
-    reliability?: number,
+    reliability?: ReliabilityWS,
   ): RemotePublisher {
     let uuid: string = uuidv4();
     let publisher = new RemotePublisher(key_expr, uuid, this);
@@ -324,6 +340,30 @@ export class RemoteSession {
     return publisher;
   }
 
+  // Matching Listener
+  declare_matching_listener(
+    publisher_id: UUIDv4,
+    callback: (matching: boolean) => void,
+  ): string {
+    let uuid = uuidv4();
+    this.matching_listeners.set(uuid, callback);
+
+    let control_message: ControlMsg = {
+      DeclareMatchingListener: { id: uuid, publisher_id: publisher_id.toString() }
+    };
+    this.send_ctrl_message(control_message);
+
+    return uuid;
+  }
+
+  undeclare_matching_listener(id: UUIDv4) {
+    this.matching_listeners.delete(id);
+    let control_message: ControlMsg = {
+      UndeclareMatchingListener: id.toString()
+    };
+    this.send_ctrl_message(control_message);
+  }
+
   // Liveliness 
   declare_liveliness_token(
     key_expr: string,
@@ -442,6 +482,8 @@ export class RemoteSession {
     } else if (typeof control_msg === "object") {
       if ("Session" in control_msg) {
         this.session = control_msg["Session"];
+      } else if ("Error" in control_msg) {
+        log.error("Message rejected by the Remote API:", control_msg["Error"]);
       } else if ("GetFinished" in control_msg) {
         let channel = this.get_receiver.get(control_msg["GetFinished"].id);
         channel?.send(RemoteRecvErr.Disconnected);
@@ -468,6 +510,18 @@ export class RemoteSession {
       } else {
         console.warn("Subscrption UUID not in map", subscription_uuid);
       }
+    } else if ("Samples" in data_msg) {
+      let subscription_uuid: UUIDv4 = data_msg["Samples"][1];
+
+      let opt_subscriber = this.subscribers.get(subscription_uuid);
+      if (opt_subscriber != undefined) {
+        let channel: SimpleChannel<SampleWS> = opt_subscriber;
+        for (let sample of data_msg["Samples"][0]) {
+          channel.send(sample);
+        }
+      } else {
+        console.warn("Subscrption UUID not in map", subscription_uuid);
+      }
     } else if ("GetReply" in data_msg) {
       let get_reply: ReplyWS = data_msg["GetReply"];
 
@@ -481,6 +535,14 @@ export class RemoteSession {
         let channel: SimpleChannel<ReplyWS | RemoteRecvErr> = opt_liveliness_receiver;
         channel.send(get_reply);
       }
+    } else if ("MatchingStatus" in data_msg) {
+      let matching_status = data_msg["MatchingStatus"];
+      let opt_callback = this.matching_listeners.get(matching_status.id);
+      if (opt_callback != undefined) {
+        opt_callback(matching_status.matching);
+      } else {
+        console.warn("Matching Listener UUID not in map", matching_status.id);
+      }
     } else if ("Queryable" in data_msg) {
       let queryable_msg: QueryableMsg = data_msg["Queryable"];
       if ("Query" in queryable_msg) {
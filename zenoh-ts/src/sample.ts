@@ -16,9 +16,16 @@
 import { encode as b64_str_from_bytes, decode as b64_bytes_from_str, } from "base64-arraybuffer";
 // Internal
 import { KeyExpr } from "./key_expr.js";
+import { CompressionWS } from "./remote_api/interface/CompressionWS.js";
+import { CongestionControlWS } from "./remote_api/interface/CongestionControlWS.js";
+import { ConsolidationModeWS } from "./remote_api/interface/ConsolidationModeWS.js";
 import { OwnedKeyExprWrapper } from "./remote_api/interface/OwnedKeyExprWrapper.js";
+import { PriorityWS } from "./remote_api/interface/PriorityWS.js";
+import { ReliabilityWS } from "./remote_api/interface/ReliabilityWS.js";
 import { SampleKindWS } from "./remote_api/interface/SampleKindWS.js";
 import { SampleWS } from "./remote_api/interface/SampleWS.js";
+import { SourceInfoWS } from "./remote_api/interface/SourceInfoWS.js";
+import { TimestampWS } from "./remote_api/interface/TimestampWS.js";
 import { ZBytes } from "./z_bytes.js";
 import { Encoding } from "./encoding.js";
 
@@ -58,37 +65,37 @@ export enum ConsolidationMode {
 }
 
 /**
- * Convenience function to convert between Congestion function and int
+ * Convenience function to convert between Congestion and its remote API name
  * @internal
  */
-export function consolidation_mode_to_int(
+export function consolidation_mode_to_ws(
   congestion_control?: ConsolidationMode,
-): number {
+): ConsolidationModeWS {
   switch (congestion_control) {
     case ConsolidationMode.Auto:
-      return 0
+      return "Auto";
     case ConsolidationMode.None:
-      return 1
+      return "None";
     case ConsolidationMode.Monotonic:
-      return 2
+      return "Monotonic";
     case ConsolidationMode.Latest:
-      return 3
+      return "Latest";
     default:
-      return 0;
+      return "Auto";
   }
 }
 
 /**
- * Convenience function to convert between Congestion function and int
+ * Convenience function to convert between Congestion and its remote API name
  * @internal
  */
-export function congestion_control_from_int(
-  prio_u8?: number,
+export function congestion_control_from_ws(
+  congestion_control?: CongestionControlWS,
 ): CongestionControl {
-  switch (prio_u8) {
-    case 0:
+  switch (congestion_control) {
+    case "Drop":
       return CongestionControl.DROP;
-    case 1:
+    case "Block":
       return CongestionControl.BLOCK;
     default:
       return CongestionControl.DROP;
@@ -96,19 +103,19 @@ export function congestion_control_from_int(
 }
 
 /**
- * Convenience function to convert between Congestion function and int
+ * Convenience function to convert between Congestion and its remote API name
  * @internal
  */
-export function congestion_control_to_int(
+export function congestion_control_to_ws(
   congestion_control?: CongestionControl,
-): number {
+): CongestionControlWS {
   switch (congestion_control) {
     case CongestionControl.DROP:
-      return 0;
+      return "Drop";
     case CongestionControl.BLOCK:
-      return 1;
+      return "Block";
     default:
-      return 0;
+      return "Drop";
   }
 }
 
@@ -127,24 +134,24 @@ export enum Priority {
 }
 
 /**
- * Convenience function to convert between Priority function and int
+ * Convenience function to convert between Priority and its remote API name
  * @internal
  */
-export function priority_from_int(prio_u8: number): Priority {
-  switch (prio_u8) {
-    case 1:
+export function priority_from_ws(prio: PriorityWS): Priority {
+  switch (prio) {
+    case "RealTime":
       return Priority.REAL_TIME;
-    case 2:
+    case "InteractiveHigh":
       return Priority.INTERACTIVE_HIGH;
-    case 3:
+    case "InteractiveLow":
       return Priority.INTERACTIVE_LOW;
-    case 4:
+    case "DataHigh":
       return Priority.DATA_HIGH;
-    case 5:
+    case "Data":
       return Priority.DATA;
-    case 6:
+    case "DataLow":
       return Priority.DATA_LOW;
-    case 7:
+    case "Background":
       return Priority.BACKGROUND;
     default:
       console.warn("Unknown Priority Variant, default to Data");
@@ -153,28 +160,28 @@ export function priority_from_int(prio_u8: number): Priority {
 }
 
 /**
- * Convenience function to convert between Priority function and int
+ * Convenience function to convert between Priority and its remote API name
  * @internal
  */
-export function priority_to_int(prio?: Priority): number {
+export function priority_to_ws(prio?: Priority): PriorityWS {
   switch (prio) {
     case Priority.REAL_TIME:
-      return 1;
+      return "RealTime";
     case Priority.INTERACTIVE_HIGH:
-      return 2;
+      return "InteractiveHigh";
     case Priority.INTERACTIVE_LOW:
-      return 3;
+      return "InteractiveLow";
     case Priority.DATA_HIGH:
-      return 4;
+      return "DataHigh";
     case Priority.DATA:
-      return 5;
+      return "Data";
     case Priority.DATA_LOW:
-      return 6;
+      return "DataLow";
     case Priority.BACKGROUND:
-      return 7;
+      return "Background";
     default:
       // Default is Priority.DATA
-      return 5;
+      return "Data";
   }
 }
 
@@ -190,14 +197,66 @@ export enum Reliability {
 /**
  * @internal
  */
-export function reliability_to_int(reliability: Reliability) {
+export function reliability_from_ws(reliability: ReliabilityWS): Reliability {
+  switch (reliability) {
+    case "Reliable":
+      return Reliability.RELIABLE;
+    case "BestEffort":
+      return Reliability.BEST_EFFORT;
+    default:
+      console.warn("Unknown Reliability Variant, default to Reliable");
+      return Reliability.RELIABLE;
+  }
+}
+
+/**
+ * @internal
+ */
+export function reliability_to_ws(reliability: Reliability): ReliabilityWS {
   switch (reliability) {
     case Reliability.RELIABLE:
-      return 0
+      return "Reliable";
     case Reliability.BEST_EFFORT:
-      return 1
+      return "BestEffort";
     default:
-      return 0;
+      return "Reliable";
+  }
+}
+
+/**
+ * Compression the Remote API plugin applies to the payloads of a subscriber
+ * @enum
+ */
+export enum Compression {
+  ZSTD = "ZSTD",
+  LZ4 = "LZ4",
+}
+
+/**
+ * @internal
+ */
+export function compression_from_ws(compression?: CompressionWS): Compression | undefined {
+  switch (compression) {
+    case "Zstd":
+      return Compression.ZSTD;
+    case "Lz4":
+      return Compression.LZ4;
+    default:
+      return undefined;
+  }
+}
+
+/**
+ * @internal
+ */
+export function compression_to_ws(compression?: Compression): CompressionWS | undefined {
+  switch (compression) {
+    case Compression.ZSTD:
+      return "Zstd";
+    case Compression.LZ4:
+      return "Lz4";
+    default:
+      return undefined;
   }
 }
 
@@ -211,10 +270,13 @@ export class Sample {
   private _kind: SampleKind;
   private _encoding: Encoding;
   private _priority: Priority;
-  private _timestamp: string | undefined;
+  private _timestamp: TimestampWS | undefined;
   private _congestion_control: CongestionControl;
   private _express: boolean;
   private _attachment: ZBytes | undefined;
+  private _reliability: Reliability;
+  private _source_info: SourceInfoWS | undefined;
+  private _compression: Compression | undefined;
 
   keyexpr(): KeyExpr {
     return this._keyexpr;
@@ -228,7 +290,7 @@ export class Sample {
   encoding(): Encoding {
     return this._encoding;
   }
-  timestamp(): string | undefined {
+  timestamp(): TimestampWS | undefined {
     return this._timestamp;
   }
   congestion_control(): CongestionControl {
@@ -243,6 +305,18 @@ export class Sample {
   attachment(): ZBytes | undefined {
     return this._attachment;
   }
+  reliability(): Reliability {
+    return this._reliability;
+  }
+  source_info(): SourceInfoWS | undefined {
+    return this._source_info;
+  }
+  /**
+   * Compression of the payload, which is left compressed, set when the subscriber asked for it
+   */
+  compression(): Compression | undefined {
+    return this._compression;
+  }
 
   constructor(
     keyexpr: KeyExpr,
@@ -250,10 +324,13 @@ export class Sample {
     kind: SampleKind,
     encoding: Encoding,
     priority: Priority,
-    timestamp: string | undefined,
+    timestamp: TimestampWS | undefined,
     congestion_control: CongestionControl,
     express: boolean,
     attachment: ZBytes | undefined,
+    reliability: Reliability = Reliability.RELIABLE,
+    source_info: SourceInfoWS | undefined = undefined,
+    compression: Compression | undefined = undefined,
   ) {
     this._keyexpr = keyexpr;
     this._payload = payload;
@@ -264,6 +341,9 @@ export class Sample {
     this._congestion_control = congestion_control;
     this._express = express;
     this._attachment = attachment;
+    this._reliability = reliability;
+    this._source_info = source_info;
+    this._compression = compression;
   }
 
 }
@@ -284,18 +364,24 @@ export function Sample_from_SampleWS(sample_ws: SampleWS) {
 
   let key_exr = new KeyExpr(sample_ws.key_expr);
 
-  let encoding = Encoding.from_str(sample_ws.encoding);
+  let encoding = Encoding.from_ws(sample_ws.encoding);
 
-  let priority = priority_from_int(sample_ws.priority);
+  let priority = priority_from_ws(sample_ws.priority);
 
-  let congestion_control = congestion_control_from_int(
+  let congestion_control = congestion_control_from_ws(
     sample_ws.congestion_control,
   );
 
-  let timestamp: string | undefined = sample_ws.timestamp as string | undefined;
+  let timestamp: TimestampWS | undefined = sample_ws.timestamp ?? undefined;
 
   let express: boolean = sample_ws.express;
 
+  let reliability = reliability_from_ws(sample_ws.reliability);
+
+  let source_info: SourceInfoWS | undefined = sample_ws.source_info ?? undefined;
+
+  let compression = compression_from_ws(sample_ws.compression);
+
   let attachment = undefined;
   if (sample_ws.attachement != undefined) {
     attachment = new ZBytes(new Uint8Array(b64_bytes_from_str(sample_ws.attachement)));
@@ -311,6 +397,9 @@ export function Sample_from_SampleWS(sample_ws: SampleWS) {
     congestion_control,
     express,
     attachment,
+    reliability,
+    source_info,
+    compression,
   );
 }
 
@@ -350,12 +439,15 @@ export function SampleWS_from_Sample(
     key_expr: key_expr,
     value: b64_str_from_bytes(new Uint8Array(value)),
     kind: sample_kind,
-    encoding: encoding.toString(),
-    timestamp: null,
-    priority: priority_to_int(priority),
-    congestion_control: congestion_control_to_int(congestion_control),
+    encoding: encoding.to_ws(),
+    compression: compression_to_ws(sample.compression()),
+    timestamp: sample.timestamp() ?? null,
+    priority: priority_to_ws(priority),
+    congestion_control: congestion_control_to_ws(congestion_control),
+    reliability: reliability_to_ws(sample.reliability()),
     express: express,
     attachement: attach,
+    source_info: sample.source_info() ?? null,
   };
 
   return sample_ws;
@@ -36,22 +36,32 @@ import {
 } from "./query.js";
 import { ChannelType, FifoChannel, Handler, NewSubscriber, Publisher, RingChannel, Subscriber } from "./pubsub.js";
 import {
-  priority_to_int,
-  congestion_control_to_int,
+  priority_to_ws,
+  congestion_control_to_ws,
   CongestionControl,
   Priority,
   Sample,
   Sample_from_SampleWS,
-  consolidation_mode_to_int,
+  consolidation_mode_to_ws,
   ConsolidationMode,
   Reliability,
-  reliability_to_int,
+  reliability_to_ws,
+  Compression,
+  compression_to_ws,
+  SampleKind,
 } from "./sample.js";
 import { State } from "channel-ts/lib/channel.js";
 import { Config } from "./config.js";
-import { Encoding } from "./encoding.js";
+import { Encoding, IntoEncoding } from "./encoding.js";
 import { QueryReplyWS } from "./remote_api/interface/QueryReplyWS.js";
 import { HandlerChannel } from "./remote_api/interface/HandlerChannel.js";
+import { BatchConfigWS } from "./remote_api/interface/BatchConfigWS.js";
+import { ThrottleWS } from "./remote_api/interface/ThrottleWS.js";
+import { SampleFilterWS } from "./remote_api/interface/SampleFilterWS.js";
+import { ReliabilityWS } from "./remote_api/interface/ReliabilityWS.js";
+import { JsonPredicateWS } from "./remote_api/interface/JsonPredicateWS.js";
+// External
+import { Duration, TimeDuration } from 'typed-duration'
 // External deps
 import { SimpleChannel } from "channel-ts";
 
@@ -136,6 +146,128 @@ export interface PublisherOptions {
   reliability?: Reliability,
 }
 
+/**
+ * Batching of the samples of a subscriber by the Remote API plugin
+ * @prop {number} max_size - Maximum number of samples in a batch
+ * @prop {TimeDuration} max_latency - Maximum time a sample waits for its batch to be sent
+ */
+export interface BatchOptions {
+  max_size: number,
+  max_latency: TimeDuration,
+}
+
+/**
+ * How the Remote API plugin throttles the samples of a subscriber
+ * @enum
+ */
+export enum ThrottleMode {
+  /** At most one sample per key expression and period, the others are dropped */
+  MAX_FREQUENCY = "MAX_FREQUENCY",
+  /** The latest sample of each key expression, sent at most once per period */
+  KEEP_LATEST = "KEEP_LATEST",
+  /** The latest sample of each period, whatever its key expression */
+  WINDOW = "WINDOW",
+}
+
+/**
+ * Rate limit the Remote API plugin applies to the samples of a subscriber
+ * @prop {ThrottleMode} mode - Which samples are kept
+ * @prop {TimeDuration} period - Throttling period
+ */
+export interface ThrottleOptions {
+  mode: ThrottleMode,
+  period: TimeDuration,
+}
+
+/**
+ * Comparison of a value in a JSON payload
+ * @enum
+ */
+export enum JsonComparison {
+  EXISTS = "EXISTS",
+  EQ = "EQ",
+  NE = "NE",
+  LT = "LT",
+  LE = "LE",
+  GT = "GT",
+  GE = "GE",
+}
+
+/**
+ * Filter the Remote API plugin applies to the samples of a subscriber, either on:
+ * - the kind of the sample
+ * - a parameter of the attachment, `attachment_value` being unset to only check its presence
+ * - the value at `json_path` (e.g. `/temperature`) in a JSON payload, compared to `value` (a number for LT, LE, GT and GE)
+ */
+export type SampleFilter =
+  { kind: SampleKind } |
+  { attachment_key: string, attachment_value?: string } |
+  { json_path: string, comparison: JsonComparison, value?: unknown };
+
+/**
+ * Set of options used when declaring a subscriber
+ * @prop {IntoEncoding=} transcode_to - Optional, Encoding the plugin converts the payloads to (JSON, CBOR or MessagePack), the samples it cannot convert are received untouched
+ * @prop {Compression=} compression - Optional, the plugin compresses the payloads above its size threshold, which are received compressed with `Sample.compression()` set
+ * @prop {BatchOptions=} batch - Optional, the plugin then sends the samples in batches
+ * @prop {ThrottleOptions=} throttle - Optional, rate limit the plugin applies to the samples before sending them
+ * @prop {SampleFilter[]=} filters - Optional, the plugin only sends the samples matching all the filters
+ */
+export interface SubscriberOptions {
+  transcode_to?: IntoEncoding,
+  compression?: Compression,
+  batch?: BatchOptions,
+  throttle?: ThrottleOptions,
+  filters?: SampleFilter[],
+}
+
+function batch_options_to_ws(batch: BatchOptions): BatchConfigWS {
+  return {
+    max_size: batch.max_size,
+    max_latency: Duration.milliseconds.from(batch.max_latency),
+  };
+}
+
+function throttle_options_to_ws(throttle: ThrottleOptions): ThrottleWS {
+  let period = Duration.milliseconds.from(throttle.period);
+  switch (throttle.mode) {
+    case ThrottleMode.KEEP_LATEST:
+      return { KeepLatest: { period: period } };
+    case ThrottleMode.WINDOW:
+      return { Window: { period: period } };
+    default:
+      return { MaxFrequency: { period: period } };
+  }
+}
+
+function json_predicate_to_ws(comparison: JsonComparison, value: unknown): JsonPredicateWS {
+  switch (comparison) {
+    case JsonComparison.EQ:
+      return { Eq: value };
+    case JsonComparison.NE:
+      return { Ne: value };
+    case JsonComparison.LT:
+      return { Lt: value as number };
+    case JsonComparison.LE:
+      return { Le: value as number };
+    case JsonComparison.GT:
+      return { Gt: value as number };
+    case JsonComparison.GE:
+      return { Ge: value as number };
+    default:
+      return "Exists";
+  }
+}
+
+function sample_filter_to_ws(filter: SampleFilter): SampleFilterWS {
+  if ("kind" in filter) {
+    return { Kind: filter.kind == SampleKind.DELETE ? "Delete" : "Put" };
+  } else if ("attachment_key" in filter) {
+    return { Attachment: { key: filter.attachment_key, value: filter.attachment_value } };
+  } else {
+    return { Json: { path: filter.json_path, predicate: json_predicate_to_ws(filter.comparison, filter.value) } };
+  }
+}
+
 // ███████ ███████ ███████ ███████ ██  ██████  ███    ██
 // ██      ██      ██      ██      ██ ██    ██ ████   ██
 // ███████ █████   ███████ ███████ ██ ██    ██ ██ ██  ██
@@ -209,12 +341,12 @@ export class Session {
     let _express;
     let _attachment;
 
-    let _encoding = put_opts?.encoding?.toString()
+    let _encoding = put_opts?.encoding?.to_ws()
 
-    let _congestion_control = congestion_control_to_int(put_opts?.congestion_control);
+    let _congestion_control = congestion_control_to_ws(put_opts?.congestion_control);
 
     if (put_opts?.priority != undefined) {
-      _priority = priority_to_int(put_opts?.priority);
+      _priority = priority_to_ws(put_opts?.priority);
     }
     _express = put_opts?.express?.valueOf();
 
@@ -246,8 +378,8 @@ export class Session {
     delete_opts?: DeleteOptions
   ): void {
     let key_expr = new KeyExpr(into_key_expr);
-    let _congestion_control = congestion_control_to_int(delete_opts?.congestion_control);
-    let _priority = priority_to_int(delete_opts?.priority);
+    let _congestion_control = congestion_control_to_ws(delete_opts?.congestion_control);
+    let _priority = priority_to_ws(delete_opts?.priority);
     let _express = delete_opts?.express;
     let _attachment
 
@@ -328,10 +460,10 @@ export class Session {
     let [callback, handler_type] = this.check_handler_or_callback<Reply>(handler);
 
     // Optional Parameters 
-    let _consolidation = consolidation_mode_to_int(get_options?.consolidation)
-    let _encoding = get_options?.encoding?.toString();
-    let _congestion_control = congestion_control_to_int(get_options?.congestion_control);
-    let _priority = priority_to_int(get_options?.priority);
+    let _consolidation = consolidation_mode_to_ws(get_options?.consolidation)
+    let _encoding = get_options?.encoding?.to_ws();
+    let _congestion_control = congestion_control_to_ws(get_options?.congestion_control);
+    let _priority = priority_to_ws(get_options?.priority);
     let _express = get_options?.express;
     let _attachment;
     let _payload;
@@ -386,6 +518,7 @@ export class Session {
    * 
    * @param {IntoKeyExpr} key_expr - string of key_expression
    * @param {((sample: Sample) => Promise<void>) | Handler} handler - Either a HandlerChannel or a Callback Function to be called for all samples
+   * @param {SubscriberOptions=} subscriber_opts - Optional, set of options to be used when declaring a subscriber
    *
    * @returns Subscriber
    */
@@ -393,8 +526,23 @@ export class Session {
   async declare_subscriber(
     key_expr: IntoKeyExpr,
     handler: ((sample: Sample) => Promise<void>) | Handler = new FifoChannel(256),
+    subscriber_opts?: SubscriberOptions,
   ): Promise<Subscriber> {
     let _key_expr = new KeyExpr(key_expr);
+    let _transcode_to;
+    if (subscriber_opts?.transcode_to != undefined) {
+      _transcode_to = Encoding.intoEncoding(subscriber_opts.transcode_to).to_ws();
+    }
+    let _compression = compression_to_ws(subscriber_opts?.compression);
+    let _batch;
+    if (subscriber_opts?.batch != undefined) {
+      _batch = batch_options_to_ws(subscriber_opts.batch);
+    }
+    let _throttle;
+    if (subscriber_opts?.throttle != undefined) {
+      _throttle = throttle_options_to_ws(subscriber_opts.throttle);
+    }
+    let _filters = subscriber_opts?.filters?.map(sample_filter_to_ws);
     let remote_subscriber: RemoteSubscriber;
     let callback_subscriber = false;
     let [callback, handler_type] = this.check_handler_or_callback<Sample>(handler);
@@ -411,11 +559,22 @@ export class Session {
         _key_expr.toString(),
         handler_type,
         callback_conversion,
+        _transcode_to,
+        _compression,
+        _batch,
+        _throttle,
+        _filters,
       );
     } else {
       remote_subscriber = await this.remote_session.declare_remote_subscriber(
         _key_expr.toString(),
         handler_type,
+        undefined,
+        _transcode_to,
+        _compression,
+        _batch,
+        _throttle,
+        _filters,
       );
     }
     
@@ -506,27 +665,27 @@ export class Session {
     let _priority;
     let priority = Priority.DATA;
     if (publisher_opts?.priority != null) {
-      _priority = priority_to_int(publisher_opts?.priority);
+      _priority = priority_to_ws(publisher_opts?.priority);
       priority = publisher_opts?.priority;
     }
 
     let _congestion_control;
     let congestion_control = CongestionControl.DROP;
     if (publisher_opts?.congestion_control != null) {
-      _congestion_control = congestion_control_to_int(publisher_opts?.congestion_control);
+      _congestion_control = congestion_control_to_ws(publisher_opts?.congestion_control);
       congestion_control = publisher_opts?.congestion_control;
     }
 
-    let _reliability = 0; // Default Reliable
+    let _reliability: ReliabilityWS = "Reliable";
     let reliability = Reliability.RELIABLE;
     if (publisher_opts?.reliability != null) {
-      _reliability = reliability_to_int(publisher_opts?.reliability);
+      _reliability = reliability_to_ws(publisher_opts?.reliability);
     }
 
-    let _encoding = "";
+    let _encoding;
     let encoding = Encoding.default();
     if (publisher_opts?.encoding != null) {
-      _encoding = publisher_opts?.encoding.toString();
+      _encoding = publisher_opts?.encoding.to_ws();
       encoding = publisher_opts?.encoding;
     }
 